@@ -1,5 +1,14 @@
 use crate::data::types::ColumnInfo;
 
+/// The column's type, followed by its semantic type when one was detected,
+/// as in `integer, identifier`.
+fn describe_type(col: &ColumnInfo) -> String {
+    match col.semantic_type {
+        Some(semantic_type) => format!("{}, {}", col.type_label(), semantic_type),
+        None => col.type_label(),
+    }
+}
+
 pub fn build_visualization_prompt(
     user_query: &str,
     columns: &[ColumnInfo],
@@ -11,7 +20,7 @@ pub fn build_visualization_prompt(
             format!(
                 "  - {} ({}{})",
                 col.name,
-                col.dtype,
+                describe_type(col),
                 if col.nullable { ", nullable" } else { "" }
             )
         })
@@ -49,6 +58,7 @@ RULES:
 4. Always use a valid aggregation: sum, avg, count, min, max, or median
 5. If the query is ambiguous, make reasonable assumptions
 6. NEVER use "none" or "None" for aggregation - always choose count as default
+7. NEVER sum identifier or postal-code columns (use count), nor latitude, longitude or percentage columns (use avg)
 
 OUTPUT FORMAT (use exact lowercase values):
 {{
@@ -76,7 +86,7 @@ pub fn build_chat_prompt(
 ) -> String {
     let schema_description = columns
         .iter()
-        .map(|col| format!("  - {} ({})", col.name, col.dtype))
+        .map(|col| format!("  - {} ({})", col.name, describe_type(col)))
         .collect::<Vec<_>>()
         .join("\n");
 
@@ -147,6 +157,7 @@ RULES:
 - Keep explanations concise (1-2 sentences)
 - Insights are optional; include only if there are relevant supporting metrics
 - For aggregation, choose: sum for totals, avg for averages, count for frequencies, min/max for ranges, median for central tendency
+- NEVER sum identifier or postal-code columns (use count), nor latitude, longitude or percentage columns (use avg)
 - NEVER use "none" or "None" for aggregation field"#,
         row_count,
         schema_description,
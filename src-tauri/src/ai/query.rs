@@ -1,10 +1,12 @@
 use crate::ai::groq::GroqClient;
 use crate::ai::prompts::{build_chat_prompt, build_visualization_prompt};
-use crate::ai::types::{AIChatResponse, DataInsight, VisualizationSpec};
-use crate::data::state::AppDataState;
-use crate::data::types::ColumnInfo;
+use crate::ai::types::{AIChatResponse, AggregationType, DataInsight, VisualizationSpec};
+use crate::data::semantic;
+use crate::data::state::{AppDataState, DataState};
+use crate::data::types::{ColumnInfo, SemanticType};
 use crate::error::AIError;
 use crate::settings::AppSettings;
+use polars::prelude::IdxSize;
 use serde::Deserialize;
 use tauri::State;
 
@@ -46,6 +48,41 @@ fn validate_spec(spec: &VisualizationSpec, columns: &[ColumnInfo]) -> Result<(),
     Ok(())
 }
 
+/// Replaces a sum over a column whose values cannot be added up, which the
+/// model still suggests now and then: identifiers and postal codes are
+/// counted, coordinates and percentages averaged.
+fn fix_aggregation(spec: &mut VisualizationSpec, columns: &[ColumnInfo]) {
+    if !matches!(spec.aggregation, AggregationType::Sum) {
+        return;
+    }
+    let semantic_type = columns
+        .iter()
+        .find(|c| c.name == spec.y_field)
+        .and_then(|c| c.semantic_type);
+
+    spec.aggregation = match semantic_type {
+        Some(SemanticType::Identifier | SemanticType::PostalCode) => AggregationType::Count,
+        Some(semantic_type) if !semantic_type.is_additive() => AggregationType::Avg,
+        _ => return,
+    };
+}
+
+/// Columns of the active table. Columns parsed from formatted numbers are
+/// tagged as currency or percentage from their format.
+fn active_columns(data_state: &DataState) -> Result<Vec<ColumnInfo>, AIError> {
+    let table = data_state
+        .get_active_table()
+        .ok_or_else(|| AIError::RequestFailed("No data loaded".to_string()))?;
+
+    let mut columns = table
+        .columns()
+        .map_err(|e| AIError::RequestFailed(e.to_string()))?;
+    if let Some(name) = data_state.active_table.as_deref() {
+        semantic::apply_number_formats(&mut columns, &data_state.view_number_formats(name));
+    }
+    Ok(columns)
+}
+
 fn extract_json(response: &str) -> Option<&str> {
     let trimmed = response.trim();
     
@@ -104,21 +141,16 @@ pub async fn process_ai_query(
             .lock()
             .map_err(|e| AIError::RequestFailed(e.to_string()))?;
 
-        let df = data_state
-            .get_active_dataframe()
+        let table = data_state
+            .get_active_table()
             .ok_or_else(|| AIError::RequestFailed("No data loaded".to_string()))?;
 
-        let cols: Vec<ColumnInfo> = df
-            .get_columns()
-            .iter()
-            .map(|col| ColumnInfo {
-                name: col.name().to_string(),
-                dtype: format!("{:?}", col.dtype()),
-                nullable: true,
-            })
-            .collect();
+        let columns = active_columns(&data_state)?;
+        let row_count = table
+            .row_count()
+            .map_err(|e| AIError::RequestFailed(e.to_string()))?;
 
-        (cols, df.height())
+        (columns, row_count)
     };
 
     let prompt = build_visualization_prompt(&query, &columns, row_count);
@@ -131,10 +163,11 @@ pub async fn process_ai_query(
 
     let sanitized = sanitize_json_response(json_str);
 
-    let spec: VisualizationSpec =
+    let mut spec: VisualizationSpec =
         serde_json::from_str(&sanitized).map_err(|e| AIError::ParseError(e.to_string()))?;
 
     validate_spec(&spec, &columns)?;
+    fix_aggregation(&mut spec, &columns);
 
     Ok(spec)
 }
@@ -160,22 +193,21 @@ fn extract_sample_rows(
         .lock()
         .map_err(|e| AIError::RequestFailed(e.to_string()))?;
 
-    let df = data_state
-        .get_active_dataframe()
+    let table = data_state
+        .get_active_table()
         .ok_or_else(|| AIError::RequestFailed("No data loaded".to_string()))?;
 
-    let columns: Vec<ColumnInfo> = df
-        .get_columns()
-        .iter()
-        .map(|col| ColumnInfo {
-            name: col.name().to_string(),
-            dtype: format!("{:?}", col.dtype()),
-            nullable: true,
-        })
-        .collect();
+    let row_count = table
+        .row_count()
+        .map_err(|e| AIError::RequestFailed(e.to_string()))?;
+    let columns = active_columns(&data_state)?;
+    let df = table
+        .lazy()
+        .limit(max_rows as IdxSize)
+        .collect()
+        .map_err(|e| AIError::RequestFailed(e.to_string()))?;
 
-    let row_count = df.height();
-    let sample_count = std::cmp::min(max_rows, row_count);
+    let sample_count = df.height();
 
     let mut sample_rows: Vec<Vec<String>> = Vec::with_capacity(sample_count);
     for i in 0..sample_count {
@@ -219,11 +251,12 @@ pub async fn process_ai_chat(
 
     match raw.intent.as_str() {
         "visualization" => {
-            let spec = raw.spec.ok_or_else(|| {
+            let mut spec = raw.spec.ok_or_else(|| {
                 AIError::ParseError("Missing visualization spec in response".to_string())
             })?;
 
             validate_spec(&spec, &columns)?;
+            fix_aggregation(&mut spec, &columns);
 
             let explanation = raw
                 .explanation
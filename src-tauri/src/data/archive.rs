@@ -0,0 +1,264 @@
+//! # Compressed Files and Archives
+//!
+//! Decompresses gzip, zstd and bzip2 files on the fly so text formats can be
+//! read without unpacking them first, and reads the data files out of .zip,
+//! .tar and .tar.gz archives.
+
+use crate::error::DataError;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+/// Extensions of the archive members that can be loaded as tables.
+const MEMBER_EXTENSIONS: [&str; 8] = ["csv", "tsv", "json", "xlsx", "xlsm", "xls", "xlsb", "ods"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    /// Compression implied by a file name's last extension.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match extension(name).as_str() {
+            "gz" | "gzip" | "tgz" => Some(Self::Gzip),
+            "zst" | "zstd" => Some(Self::Zstd),
+            "bz2" => Some(Self::Bzip2),
+            _ => None,
+        }
+    }
+
+    /// Wraps a reader so that reading from it yields the decompressed bytes.
+    pub fn decoder<'a, R: Read + 'a>(self, reader: R) -> io::Result<Box<dyn Read + 'a>> {
+        Ok(match self {
+            Self::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+            Self::Zstd => Box::new(zstd::stream::read::Decoder::new(reader)?),
+            Self::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+        })
+    }
+}
+
+fn extension(name: &str) -> String {
+    Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// Strips a compression extension, so `sales.csv.gz` becomes `sales.csv`.
+fn strip_compression(name: &str) -> &str {
+    match Compression::from_name(name) {
+        Some(_) => name.rsplit_once('.').map_or(name, |(stem, _)| stem),
+        None => name,
+    }
+}
+
+/// Extension of the data inside a possibly compressed file, e.g. `csv` for
+/// both `sales.csv` and `sales.csv.gz`.
+pub fn data_extension(name: &str) -> String {
+    extension(strip_compression(name))
+}
+
+/// Whether the file is a .zip, .tar, .tar.gz or .tgz archive.
+pub fn is_archive(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    lower.ends_with(".zip")
+        || lower.ends_with(".tar")
+        || lower.ends_with(".tar.gz")
+        || lower.ends_with(".tgz")
+}
+
+/// Whether the file is a single compressed file rather than an archive.
+pub fn is_compressed(name: &str) -> bool {
+    Compression::from_name(name).is_some() && !is_archive(name)
+}
+
+/// Opens a file for reading, decompressing it if its extension says so.
+pub fn open(path: &str) -> io::Result<Box<dyn Read>> {
+    let file = BufReader::new(File::open(path)?);
+    match Compression::from_name(path) {
+        Some(compression) => compression.decoder(file),
+        None => Ok(Box::new(file)),
+    }
+}
+
+/// The decompressed contents of a compressed file, or `None` for a plain file
+/// that can be read from disk as it is.
+pub fn read_decompressed(path: &str) -> io::Result<Option<Vec<u8>>> {
+    if !is_compressed(path) {
+        return Ok(None);
+    }
+    let mut contents = Vec::new();
+    open(path)?.read_to_end(&mut contents)?;
+    Ok(Some(contents))
+}
+
+/// A data file read out of an archive.
+pub struct ArchiveMember {
+    /// Path inside the archive, without any compression extension.
+    pub name: String,
+    pub contents: Vec<u8>,
+}
+
+impl ArchiveMember {
+    /// The member's path without its extension, used as its table name.
+    pub fn table_name(&self) -> &str {
+        self.name
+            .rsplit_once('.')
+            .map_or(&self.name, |(stem, _)| stem)
+    }
+}
+
+/// Keeps data files, skipping unsupported formats and the resource forks
+/// macOS adds to archives.
+fn is_data_member(name: &str) -> bool {
+    let file_name = name.rsplit('/').next().unwrap_or(name);
+    !name.starts_with("__MACOSX/")
+        && !file_name.starts_with("._")
+        && MEMBER_EXTENSIONS.contains(&data_extension(name).as_str())
+}
+
+/// Adds a member, decompressing it if it is itself a compressed file.
+fn push_member(
+    members: &mut Vec<ArchiveMember>,
+    name: String,
+    contents: Vec<u8>,
+) -> io::Result<()> {
+    let contents = match Compression::from_name(&name) {
+        Some(compression) => {
+            let mut decompressed = Vec::new();
+            compression
+                .decoder(contents.as_slice())?
+                .read_to_end(&mut decompressed)?;
+            decompressed
+        }
+        None => contents,
+    };
+    let name = strip_compression(&name).to_string();
+    members.push(ArchiveMember { name, contents });
+    Ok(())
+}
+
+fn read_zip(path: &str) -> Result<Vec<ArchiveMember>, DataError> {
+    let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
+    let mut members = Vec::new();
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if !entry.is_file() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        if !is_data_member(&name) {
+            continue;
+        }
+        let mut contents = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut contents)?;
+        push_member(&mut members, name, contents)?;
+    }
+
+    Ok(members)
+}
+
+fn read_tar(reader: impl Read) -> Result<Vec<ArchiveMember>, DataError> {
+    let mut archive = tar::Archive::new(reader);
+    let mut members = Vec::new();
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().into_owned();
+        if !is_data_member(&name) {
+            continue;
+        }
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        push_member(&mut members, name, contents)?;
+    }
+
+    Ok(members)
+}
+
+/// Reads the CSV, JSON and spreadsheet files in an archive, in archive order.
+/// Directories and other files are skipped.
+pub fn read_members(path: &str) -> Result<Vec<ArchiveMember>, DataError> {
+    if extension(path) == "zip" {
+        read_zip(path)
+    } else {
+        read_tar(open(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_data_extension_looks_through_compression() {
+        assert_eq!(data_extension("sales.csv.gz"), "csv");
+        assert_eq!(data_extension("DATA.JSON.ZST"), "json");
+        assert_eq!(data_extension("sales.csv"), "csv");
+        assert!(is_compressed("sales.csv.bz2"));
+        assert!(!is_compressed("bundle.tar.gz"));
+        assert!(is_archive("bundle.tgz"));
+    }
+
+    #[test]
+    fn test_open_decompresses_each_format() {
+        let dir = TempDir::new().unwrap();
+        let text = b"id,name\n1,Ana\n";
+
+        let gz = dir.path().join("a.csv.gz");
+        let mut encoder =
+            GzEncoder::new(File::create(&gz).unwrap(), flate2::Compression::default());
+        encoder.write_all(text).unwrap();
+        encoder.finish().unwrap();
+
+        let zst = dir.path().join("a.csv.zst");
+        std::fs::write(&zst, zstd::encode_all(&text[..], 0).unwrap()).unwrap();
+
+        let bz2 = dir.path().join("a.csv.bz2");
+        let mut encoder = bzip2::write::BzEncoder::new(
+            File::create(&bz2).unwrap(),
+            bzip2::Compression::default(),
+        );
+        encoder.write_all(text).unwrap();
+        encoder.finish().unwrap();
+
+        for path in [gz, zst, bz2] {
+            let contents = read_decompressed(path.to_str().unwrap()).unwrap();
+            assert_eq!(contents.as_deref(), Some(&text[..]));
+        }
+    }
+
+    #[test]
+    fn test_read_members_skips_other_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bundle.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, contents) in [
+            ("data/sales.csv", "id\n1\n"),
+            ("README.md", "notes"),
+            ("__MACOSX/data/._sales.csv", "fork"),
+            ("data/items.json", "[]"),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let members = read_members(path.to_str().unwrap()).unwrap();
+        let names: Vec<&str> = members.iter().map(|m| m.table_name()).collect();
+        assert_eq!(names, ["data/sales", "data/items"]);
+        assert_eq!(members[0].contents, b"id\n1\n");
+    }
+}
@@ -0,0 +1,294 @@
+//! # Column Management
+//!
+//! Renaming, dropping, retyping, reordering and splitting columns. Each is
+//! recorded as a transformation step, so it can be undone and is replayed
+//! when the source is loaded again. Renaming is how headers such as
+//! `Unnamed: 3` get names fit for chart titles and axis labels.
+
+use crate::data::report;
+use crate::data::state::AppDataState;
+use crate::data::transform::{apply_step, change_table, push_step};
+use crate::data::types::{
+    ColumnIssue, ColumnType, LogicalType, RejectedValue, RetypedTable, SplitPattern, TransformStep,
+    TransformedTable,
+};
+use crate::error::DataError;
+use polars::prelude::*;
+use tauri::State;
+
+/// Values of `column` that are present in `before`, a table without a type
+/// change, but null once the column is converted to `to`. They are counted in
+/// one pass over the table, which keeps the first few as samples.
+pub fn cast_issue(
+    before: LazyFrame,
+    column: &str,
+    to: ColumnType,
+) -> Result<Option<ColumnIssue>, DataError> {
+    const ROW: &str = "__row";
+    const RAW: &str = "__raw";
+
+    let raw = col(column)
+        .cast(DataType::String)
+        .str()
+        .strip_chars(lit(NULL))
+        .alias(RAW);
+    let step = TransformStep::Cast {
+        column: column.to_string(),
+        to,
+    };
+    let mut after = apply_step(
+        before
+            .with_row_index(ROW, Some(1))
+            .select([col(ROW), col(column), raw]),
+        &step,
+    )?;
+    let target_type = after
+        .collect_schema()?
+        .get(column)
+        .map(LogicalType::from)
+        .ok_or_else(|| DataError::ParseError(format!("Column '{}' not found", column)))?;
+
+    let present = col(RAW).neq(lit(""));
+    let lost = present.clone().and(col(column).is_null());
+    let samples = |name: &str| {
+        col(name)
+            .filter(lost.clone())
+            .head(Some(report::MAX_SAMPLES_PER_COLUMN))
+            .implode()
+    };
+    let summary = after
+        .select([
+            lost.clone().sum().alias("failed"),
+            present.sum().alias("present"),
+            samples(ROW),
+            samples(RAW),
+        ])
+        .collect()?;
+
+    let count = |name: &str| -> Result<usize, DataError> {
+        Ok(summary
+            .column(name)?
+            .get(0)?
+            .extract::<usize>()
+            .unwrap_or(0))
+    };
+    let failed_count = count("failed")?;
+    if failed_count == 0 {
+        return Ok(None);
+    }
+    let rows = summary
+        .column(ROW)?
+        .list()?
+        .get_as_series(0)
+        .unwrap_or_default();
+    let values = summary
+        .column(RAW)?
+        .list()?
+        .get_as_series(0)
+        .unwrap_or_default();
+    let samples = rows
+        .cast(&IDX_DTYPE)?
+        .idx()?
+        .into_iter()
+        .zip(values.str()?)
+        .filter_map(|(row, value)| {
+            Some(RejectedValue {
+                row: row? as usize,
+                value: value?.to_string(),
+            })
+        })
+        .collect();
+
+    Ok(Some(ColumnIssue {
+        column: column.to_string(),
+        target_type,
+        failed_count,
+        failed_ratio: failed_count as f64 / count("present")? as f64,
+        samples,
+    }))
+}
+
+/// Renames a column of a table, the active table by default.
+#[tauri::command]
+pub async fn rename_column(
+    table: Option<String>,
+    column: String,
+    to: String,
+    state: State<'_, AppDataState>,
+) -> Result<TransformedTable, DataError> {
+    change_table(&state, table, |data_state, table| {
+        push_step(data_state, table, TransformStep::Rename { column, to })
+    })
+}
+
+/// Drops columns of a table, the active table by default.
+#[tauri::command]
+pub async fn drop_columns(
+    table: Option<String>,
+    columns: Vec<String>,
+    state: State<'_, AppDataState>,
+) -> Result<TransformedTable, DataError> {
+    change_table(&state, table, |data_state, table| {
+        push_step(data_state, table, TransformStep::Drop { columns })
+    })
+}
+
+/// Converts a column of a table, the active table by default, to another
+/// type. Values that cannot be converted become null and are reported.
+#[tauri::command]
+pub async fn cast_column(
+    table: Option<String>,
+    column: String,
+    to: ColumnType,
+    state: State<'_, AppDataState>,
+) -> Result<RetypedTable, DataError> {
+    let mut before = None;
+    let TransformedTable { dataset, pipeline } =
+        change_table(&state, table, |data_state, table| {
+            before = data_state.get_table(table).map(|t| t.lazy());
+            let step = TransformStep::Cast {
+                column: column.clone(),
+                to: to.clone(),
+            };
+            push_step(data_state, table, step)
+        })?;
+    // Checked once the lock is released, as it reads the whole column.
+    let issue = match before {
+        Some(before) => cast_issue(before, &column, to)?,
+        None => None,
+    };
+    Ok(RetypedTable {
+        dataset,
+        pipeline,
+        issue,
+    })
+}
+
+/// Moves `columns` to the front of a table, the active table by default, in
+/// the given order.
+#[tauri::command]
+pub async fn reorder_columns(
+    table: Option<String>,
+    columns: Vec<String>,
+    state: State<'_, AppDataState>,
+) -> Result<TransformedTable, DataError> {
+    change_table(&state, table, |data_state, table| {
+        push_step(data_state, table, TransformStep::Reorder { columns })
+    })
+}
+
+/// Splits a column of a table, the active table by default, on a delimiter
+/// or a regex into new columns named `into`.
+#[tauri::command]
+pub async fn split_column(
+    table: Option<String>,
+    column: String,
+    by: SplitPattern,
+    into: Vec<String>,
+    state: State<'_, AppDataState>,
+) -> Result<TransformedTable, DataError> {
+    change_table(&state, table, |data_state, table| {
+        push_step(data_state, table, TransformStep::Split { column, by, into })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::state::Table;
+    use crate::data::transform::build_view;
+
+    fn contacts() -> Table {
+        Table::Materialized(
+            df!(
+                "Unnamed: 0" => ["Meyer, Anna", "Li,Wei", "Bo"],
+                "phone" => ["030 1234", "n/a", "089-55"],
+                "zip" => ["01067", "10115", "x"],
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_split_by_delimiter_and_regex() {
+        let steps = [
+            TransformStep::Split {
+                column: "Unnamed: 0".to_string(),
+                by: SplitPattern::Regex {
+                    pattern: r",\s*".to_string(),
+                },
+                into: vec!["last".to_string(), "first".to_string()],
+            },
+            TransformStep::Split {
+                column: "phone".to_string(),
+                by: SplitPattern::Delimiter {
+                    delimiter: " ".to_string(),
+                },
+                into: vec!["area".to_string(), "number".to_string()],
+            },
+            TransformStep::Reorder {
+                columns: vec!["first".to_string(), "last".to_string()],
+            },
+        ];
+
+        let view = build_view(contacts(), &steps, &[]);
+        assert!(view.failed_steps.is_empty());
+        let df = view.table.collect().unwrap();
+        assert_eq!(
+            df.get_column_names(),
+            [
+                "first",
+                "last",
+                "Unnamed: 0",
+                "phone",
+                "area",
+                "number",
+                "zip"
+            ]
+        );
+        let first: Vec<Option<&str>> = df
+            .column("first")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(first, [Some("Anna"), Some("Wei"), None]);
+        let number: Vec<Option<&str>> = df
+            .column("number")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(number, [Some("1234"), None, None]);
+    }
+
+    #[test]
+    fn test_split_rejects_taken_names() {
+        let steps = [TransformStep::Split {
+            column: "phone".to_string(),
+            by: SplitPattern::Delimiter {
+                delimiter: "-".to_string(),
+            },
+            into: vec!["zip".to_string(), "rest".to_string()],
+        }];
+        assert_eq!(build_view(contacts(), &steps, &[]).failed_steps.len(), 1);
+    }
+
+    #[test]
+    fn test_cast_reports_values_that_became_null() {
+        let issue = cast_issue(contacts().lazy(), "zip", ColumnType::Integer)
+            .unwrap()
+            .unwrap();
+        assert_eq!(issue.failed_count, 1);
+        assert_eq!(issue.samples[0].row, 3);
+        assert_eq!(issue.samples[0].value, "x");
+
+        assert!(
+            cast_issue(contacts().lazy(), "zip", ColumnType::Categorical)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,564 @@
+//! # Spreadsheet Reading
+//!
+//! Opens .xlsx, .xlsm, .xls, .xlsb and .ods workbooks and turns worksheet
+//! ranges into DataFrames, typing date, datetime and duration cells as
+//! temporal columns instead of raw serial numbers. A load can be narrowed to
+//! one sheet, a cell range, or an Excel Table or defined name.
+
+use crate::data::sniff;
+use crate::data::types::{ExcelImportOptions, SchemaOverrides, SkippedTable};
+use crate::error::DataError;
+use calamine::{
+    open_workbook_auto, open_workbook_auto_from_rs, Data, DataType as _, Range, Reader, Sheets,
+};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use polars::prelude::*;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read, Seek};
+
+pub type Workbook<RS = BufReader<File>> = Sheets<RS>;
+
+/// Opens a workbook of any supported format, detected from the extension.
+pub fn open(path: &str) -> Result<Workbook, DataError> {
+    Ok(open_workbook_auto(path)?)
+}
+
+/// Opens a workbook held in memory, such as one read out of an archive. The
+/// format is detected from the contents.
+pub fn open_bytes(bytes: Vec<u8>) -> Result<Workbook<Cursor<Vec<u8>>>, DataError> {
+    Ok(open_workbook_auto_from_rs(Cursor::new(bytes))?)
+}
+
+/// What a single cell holds, after resolving Excel's date serials.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CellKind {
+    Empty,
+    Text,
+    Number,
+    Bool,
+    Date,
+    DateTime,
+    Duration,
+}
+
+fn cell_datetime(cell: &Data) -> Option<NaiveDateTime> {
+    match cell {
+        Data::DateTime(dt) if !dt.is_duration() => dt.as_datetime(),
+        Data::DateTimeIso(s) => cell.as_datetime().or_else(|| {
+            s.parse::<NaiveDate>()
+                .ok()
+                .map(|d| d.and_time(NaiveTime::MIN))
+        }),
+        _ => None,
+    }
+}
+
+fn cell_duration(cell: &Data) -> Option<chrono::Duration> {
+    match cell {
+        Data::DateTime(dt) if dt.is_duration() => dt.as_duration(),
+        Data::DurationIso(_) => cell.as_duration(),
+        _ => None,
+    }
+}
+
+fn cell_kind(cell: &Data) -> CellKind {
+    match cell {
+        Data::String(s) if s.trim().is_empty() => CellKind::Empty,
+        Data::String(_) => CellKind::Text,
+        Data::Int(_) | Data::Float(_) => CellKind::Number,
+        Data::Bool(_) => CellKind::Bool,
+        Data::DateTime(_) | Data::DateTimeIso(_) | Data::DurationIso(_) => {
+            if cell_duration(cell).is_some() {
+                CellKind::Duration
+            } else {
+                match cell_datetime(cell) {
+                    Some(dt) if dt.time() == NaiveTime::MIN => CellKind::Date,
+                    Some(_) => CellKind::DateTime,
+                    None => CellKind::Text,
+                }
+            }
+        }
+        Data::Error(_) | Data::Empty => CellKind::Empty,
+    }
+}
+
+fn format_duration(duration: chrono::Duration) -> String {
+    let seconds = duration.num_seconds();
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.abs();
+    format!(
+        "{}{}:{:02}:{:02}",
+        sign,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Text form of a cell, used for mixed columns and columns with a type
+/// override. Dates are written as ISO 8601 rather than serial numbers.
+pub fn cell_to_text(cell: &Data) -> Option<String> {
+    match cell_kind(cell) {
+        CellKind::Empty => None,
+        CellKind::Date => cell_datetime(cell).map(|dt| dt.date().to_string()),
+        CellKind::DateTime => cell_datetime(cell).map(|dt| dt.to_string()),
+        CellKind::Duration => cell_duration(cell).map(format_duration),
+        _ => match cell {
+            Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => Some(s.clone()),
+            Data::Int(i) => Some(i.to_string()),
+            Data::Float(f) => Some(f.to_string()),
+            Data::Bool(b) => Some(b.to_string()),
+            _ => None,
+        },
+    }
+}
+
+/// Builds a typed column from a worksheet column. A column is only given a
+/// temporal type when every non-empty cell agrees; text in any cell makes the
+/// whole column text.
+pub fn column_series(name: &str, cells: &[&Data], as_text: bool) -> PolarsResult<Series> {
+    let name: PlSmallStr = name.into();
+    let kinds: Vec<CellKind> = cells.iter().map(|c| cell_kind(c)).collect();
+    let present = || kinds.iter().filter(|k| **k != CellKind::Empty);
+    let all = |allowed: &[CellKind]| present().all(|k| allowed.contains(k));
+
+    if as_text || present().any(|k| *k == CellKind::Text) {
+        let values: Vec<Option<String>> = cells.iter().map(|c| cell_to_text(c)).collect();
+        return Ok(Series::new(name, values));
+    }
+
+    if present().next().is_none() {
+        return Ok(Series::new_null(name, cells.len()));
+    }
+
+    if all(&[CellKind::Date]) {
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap_or_default();
+        let days: Int32Chunked = cells
+            .iter()
+            .map(|c| cell_datetime(c).map(|dt| (dt.date() - epoch).num_days() as i32))
+            .collect();
+        return Ok(days.with_name(name).into_date().into_series());
+    }
+
+    if all(&[CellKind::Date, CellKind::DateTime]) {
+        let millis: Int64Chunked = cells
+            .iter()
+            .map(|c| cell_datetime(c).map(|dt| dt.and_utc().timestamp_millis()))
+            .collect();
+        return Ok(millis
+            .with_name(name)
+            .into_datetime(TimeUnit::Milliseconds, None)
+            .into_series());
+    }
+
+    if all(&[CellKind::Duration]) {
+        let millis: Int64Chunked = cells
+            .iter()
+            .map(|c| cell_duration(c).map(|d| d.num_milliseconds()))
+            .collect();
+        return Ok(millis
+            .with_name(name)
+            .into_duration(TimeUnit::Milliseconds)
+            .into_series());
+    }
+
+    if all(&[CellKind::Bool]) {
+        let values: Vec<Option<bool>> = cells
+            .iter()
+            .map(|c| match c {
+                Data::Bool(b) => Some(*b),
+                _ => None,
+            })
+            .collect();
+        return Ok(Series::new(name, values));
+    }
+
+    if present().any(|k| *k == CellKind::Number) {
+        let values: Vec<Option<f64>> = cells
+            .iter()
+            .map(|c| match c {
+                Data::Int(i) => Some(*i as f64),
+                Data::Float(f) => Some(*f),
+                Data::DateTime(dt) => Some(dt.as_f64()),
+                _ => None,
+            })
+            .collect();
+        return Ok(Series::new(name, values));
+    }
+
+    let values: Vec<Option<String>> = cells.iter().map(|c| cell_to_text(c)).collect();
+    Ok(Series::new(name, values))
+}
+
+/// Rows inspected when looking for the header row.
+const HEADER_SCAN_ROWS: usize = 50;
+
+static EMPTY_CELL: Data = Data::Empty;
+
+/// Sheets often start with a title and blank rows above the table. The header
+/// is taken to be the first row with at least half as many filled cells as the
+/// table body, allowing for blank header cells, and more than one unless the
+/// table is a single column.
+fn detect_header_row(rows: &[&[Data]]) -> usize {
+    let counts: Vec<usize> = rows
+        .iter()
+        .take(HEADER_SCAN_ROWS)
+        .map(|row| {
+            row.iter()
+                .filter(|c| cell_kind(c) != CellKind::Empty)
+                .count()
+        })
+        .collect();
+    let filled: Vec<usize> = counts.iter().copied().filter(|&c| c > 0).collect();
+    let (body_width, _) = sniff::mode(&filled);
+    let min_width = body_width.div_ceil(2).max(body_width.min(2)).max(1);
+
+    counts
+        .iter()
+        .position(|&count| count >= min_width)
+        .unwrap_or(0)
+}
+
+/// Names blank headers `column_N` after their position and suffixes repeated
+/// names with `_2`, `_3`, ... so that every column name is unique.
+pub fn unique_headers(headers: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+
+    headers
+        .into_iter()
+        .enumerate()
+        .map(|(idx, header)| {
+            let base = match header.trim() {
+                "" => format!("column_{}", idx + 1),
+                trimmed => trimmed.to_string(),
+            };
+            let mut name = base.clone();
+            let mut n = 2;
+            while !seen.insert(name.clone()) {
+                name = format!("{}_{}", base, n);
+                n += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+/// Builds a DataFrame from column names and data rows. Columns with a type
+/// override are read as text so the override conversion sees what the cell
+/// showed.
+fn build_dataframe(
+    headers: Vec<String>,
+    rows: &[&[Data]],
+    overrides: &SchemaOverrides,
+) -> PolarsResult<DataFrame> {
+    let columns = unique_headers(headers)
+        .iter()
+        .enumerate()
+        .map(|(idx, header)| {
+            let cells: Vec<&Data> = rows
+                .iter()
+                .map(|row| row.get(idx).unwrap_or(&EMPTY_CELL))
+                .collect();
+            column_series(header, &cells, overrides.contains_key(header))
+        })
+        .collect::<PolarsResult<Vec<Series>>>()?;
+
+    DataFrame::new(columns)
+}
+
+/// Converts a worksheet range to a DataFrame. Column names come from
+/// `header_row` (0-based, in sheet coordinates) or from the detected header
+/// row; anything above the header is dropped.
+pub fn range_to_dataframe(
+    range: &Range<Data>,
+    header_row: Option<u32>,
+    overrides: &SchemaOverrides,
+) -> Result<Option<DataFrame>, DataError> {
+    let rows: Vec<&[Data]> = range.rows().collect();
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let header_idx = match header_row {
+        Some(row) => {
+            let first_row = range.start().map_or(0, |(row, _)| row);
+            row.checked_sub(first_row)
+                .map(|idx| idx as usize)
+                .filter(|&idx| idx < rows.len())
+                .ok_or_else(|| {
+                    DataError::ParseError(format!("Header row {} is outside the data", row + 1))
+                })?
+        }
+        None => detect_header_row(&rows),
+    };
+
+    let headers = rows[header_idx]
+        .iter()
+        .map(|c| cell_to_text(c).unwrap_or_default())
+        .collect();
+    let df = build_dataframe(headers, &rows[header_idx + 1..], overrides)?;
+    Ok(Some(df))
+}
+
+/// Parses an A1-style cell such as `B4` or `$B$4` into a 0-based
+/// (row, column) position.
+fn parse_cell(cell: &str) -> Option<(u32, u32)> {
+    let cell = cell.replace('$', "");
+    let split = cell.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = cell.split_at(split);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let column = letters.chars().try_fold(0u32, |acc, c| {
+        acc.checked_mul(26)?
+            .checked_add(c.to_ascii_uppercase() as u32 - 'A' as u32 + 1)
+    })?;
+    let row: u32 = digits.parse().ok()?;
+    Some((row.checked_sub(1)?, column - 1))
+}
+
+/// First and last (row, column) of a cell range, inclusive and 0-based.
+pub type CellSpan = ((u32, u32), (u32, u32));
+
+/// Parses a cell range such as `B4:K900`. A single cell is a one-cell range.
+pub fn parse_range(reference: &str) -> Option<CellSpan> {
+    let (start, end) = match reference.trim().split_once(':') {
+        Some((start, end)) => (parse_cell(start)?, parse_cell(end)?),
+        None => {
+            let cell = parse_cell(reference.trim())?;
+            (cell, cell)
+        }
+    };
+
+    Some((
+        (start.0.min(end.0), start.1.min(end.1)),
+        (start.0.max(end.0), start.1.max(end.1)),
+    ))
+}
+
+/// Splits the formula of a defined name, such as `'Q1 Sales'!$A$1:$D$40`,
+/// into its sheet and cell range.
+fn parse_defined_name(formula: &str) -> Option<(String, CellSpan)> {
+    let formula = formula.trim().trim_start_matches('=');
+    let (sheet, reference) = formula.rsplit_once('!')?;
+    let sheet = match sheet.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        Some(quoted) => quoted.replace("''", "'"),
+        None => sheet.to_string(),
+    };
+    Some((sheet, parse_range(reference)?))
+}
+
+fn invalid_range(reference: &str) -> DataError {
+    DataError::ParseError(format!("Invalid cell range '{}'", reference))
+}
+
+/// Names of the Excel Tables and defined names in a workbook.
+pub fn table_names<RS: Read + Seek>(workbook: &mut Workbook<RS>) -> Result<Vec<String>, DataError> {
+    let mut names = Vec::new();
+    if let Sheets::Xlsx(xlsx) = workbook {
+        xlsx.load_tables()?;
+        names.extend(xlsx.table_names().into_iter().cloned());
+    }
+    names.extend(
+        workbook
+            .defined_names()
+            .iter()
+            .filter(|(_, formula)| parse_defined_name(formula).is_some())
+            .map(|(name, _)| name.clone()),
+    );
+    Ok(names)
+}
+
+/// Reads an Excel Table, whose header row is known, or else a defined name
+/// that refers to a single cell range.
+fn read_named<RS: Read + Seek>(
+    workbook: &mut Workbook<RS>,
+    name: &str,
+    header_row: Option<u32>,
+    overrides: &SchemaOverrides,
+) -> Result<DataFrame, DataError> {
+    if let Sheets::Xlsx(xlsx) = workbook {
+        xlsx.load_tables()?;
+        if xlsx
+            .table_names()
+            .iter()
+            .any(|table| table.as_str() == name)
+        {
+            let table = xlsx.table_by_name(name)?;
+            let rows: Vec<&[Data]> = table.data().rows().collect();
+            return Ok(build_dataframe(table.columns().to_vec(), &rows, overrides)?);
+        }
+    }
+
+    let formula = workbook
+        .defined_names()
+        .iter()
+        .find(|(defined, _)| defined.eq_ignore_ascii_case(name))
+        .map(|(_, formula)| formula.clone())
+        .ok_or_else(|| {
+            DataError::ParseError(format!("No table or defined name called '{}'", name))
+        })?;
+    let (sheet, (start, end)) =
+        parse_defined_name(&formula).ok_or_else(|| invalid_range(&formula))?;
+
+    let range = workbook.worksheet_range(&sheet)?.range(start, end);
+    range_to_dataframe(&range, header_row, overrides)?.ok_or(DataError::NoData)
+}
+
+/// Tables read from a workbook, as (sheet or table name, data) pairs.
+pub type SheetTables = Vec<(String, DataFrame)>;
+
+/// Reads the part of a workbook selected by `options`, as (table name, data)
+/// pairs. Without a selection every sheet is read: sheets without usable data
+/// are left out, and sheets that fail to read are returned separately with
+/// the reason. `on_sheet` is called with each sheet or table name before it
+/// is read, and can stop the read by returning an error.
+pub fn read_tables<RS: Read + Seek>(
+    workbook: &mut Workbook<RS>,
+    options: &ExcelImportOptions,
+    overrides: &SchemaOverrides,
+    mut on_sheet: impl FnMut(&str) -> Result<(), DataError>,
+) -> Result<(SheetTables, Vec<SkippedTable>), DataError> {
+    let header_row = options.header_row.map(|row| row.saturating_sub(1));
+
+    if let Some(name) = &options.table {
+        on_sheet(name)?;
+        let df = read_named(workbook, name, header_row, overrides)?;
+        return Ok((vec![(name.clone(), df)], Vec::new()));
+    }
+
+    let sheets = workbook.sheet_names().to_vec();
+
+    if options.sheet.is_none() && options.range.is_none() {
+        let mut tables = Vec::with_capacity(sheets.len());
+        let mut skipped = Vec::new();
+        for sheet in sheets {
+            on_sheet(&sheet)?;
+            let read = workbook
+                .worksheet_range(&sheet)
+                .map_err(DataError::from)
+                .and_then(|range| range_to_dataframe(&range, header_row, overrides));
+            match read {
+                Ok(Some(df)) => tables.push((sheet, df)),
+                Ok(None) => {}
+                Err(e) => skipped.push(SkippedTable {
+                    name: sheet,
+                    reason: e.to_string(),
+                }),
+            }
+        }
+        if tables.is_empty() {
+            if let Some(first) = skipped.first() {
+                return Err(DataError::ParseError(format!(
+                    "No sheet could be read. '{}': {}",
+                    first.name, first.reason
+                )));
+            }
+        }
+        return Ok((tables, skipped));
+    }
+
+    let sheet = match &options.sheet {
+        Some(sheet) if sheets.contains(sheet) => sheet.clone(),
+        Some(sheet) => return Err(DataError::InvalidSheet(sheet.clone())),
+        None => sheets.first().cloned().ok_or(DataError::NoData)?,
+    };
+    on_sheet(&sheet)?;
+
+    let mut range = workbook.worksheet_range(&sheet)?;
+    if let Some(reference) = &options.range {
+        let (start, end) = parse_range(reference).ok_or_else(|| invalid_range(reference))?;
+        range = range.range(start, end);
+    }
+
+    let df = range_to_dataframe(&range, header_row, overrides)?.ok_or(DataError::NoData)?;
+    Ok((vec![(sheet, df)], Vec::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::{ExcelDateTime, ExcelDateTimeType};
+
+    fn serial(value: f64) -> Data {
+        Data::DateTime(ExcelDateTime::new(
+            value,
+            ExcelDateTimeType::DateTime,
+            false,
+        ))
+    }
+
+    #[test]
+    fn test_date_cells_become_temporal_columns() {
+        // 45292 is 2024-01-01 in the 1900 date system.
+        let dates = [serial(45292.0), Data::Empty, serial(45293.0)];
+        let datetimes = [serial(45292.5), serial(45293.0), Data::Empty];
+        let durations = [
+            Data::DateTime(ExcelDateTime::new(1.5, ExcelDateTimeType::TimeDelta, false)),
+            Data::DurationIso("PT1H30M0S".to_string()),
+            Data::Empty,
+        ];
+
+        let s = column_series("d", &dates.iter().collect::<Vec<_>>(), false).unwrap();
+        assert_eq!(s.dtype(), &DataType::Date);
+        assert_eq!(s.get(0).unwrap().to_string(), "2024-01-01");
+        assert_eq!(s.null_count(), 1);
+
+        let s = column_series("dt", &datetimes.iter().collect::<Vec<_>>(), false).unwrap();
+        assert!(matches!(
+            s.dtype(),
+            DataType::Datetime(TimeUnit::Milliseconds, None)
+        ));
+
+        let s = column_series("dur", &durations.iter().collect::<Vec<_>>(), false).unwrap();
+        assert!(matches!(
+            s.dtype(),
+            DataType::Duration(TimeUnit::Milliseconds)
+        ));
+        assert_eq!(s.duration().unwrap().get(0), Some(36 * 60 * 60 * 1000));
+    }
+
+    #[test]
+    fn test_mixed_column_renders_dates_as_text() {
+        let cells = [serial(45292.0), Data::String("pending".to_string())];
+        let s = column_series("due", &cells.iter().collect::<Vec<_>>(), false).unwrap();
+
+        assert_eq!(s.dtype(), &DataType::String);
+        assert_eq!(s.str().unwrap().get(0), Some("2024-01-01"));
+    }
+
+    #[test]
+    fn test_header_row_detected_below_title_rows() {
+        let mut range = Range::new((0, 0), (4, 2));
+        range.set_value((0, 0), Data::String("Quarterly report".to_string()));
+        for (col, header) in ["region", "", "region"].iter().enumerate() {
+            range.set_value((2, col as u32), Data::String(header.to_string()));
+        }
+        for row in 3..5 {
+            range.set_value((row, 0), Data::String("North".to_string()));
+            range.set_value((row, 1), Data::Float(row as f64));
+            range.set_value((row, 2), Data::String("South".to_string()));
+        }
+
+        let df = range_to_dataframe(&range, None, &SchemaOverrides::new())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(df.height(), 2);
+        let names: Vec<&str> = df.get_column_names().iter().map(|n| n.as_str()).collect();
+        assert_eq!(names, ["region", "column_2", "region_2"]);
+    }
+
+    #[test]
+    fn test_parse_ranges_and_defined_names() {
+        assert_eq!(parse_range("B4:K900"), Some(((3, 1), (899, 10))));
+        assert_eq!(parse_range("$AA$1"), Some(((0, 26), (0, 26))));
+        assert_eq!(parse_range("4B:K9"), None);
+        assert_eq!(
+            parse_defined_name("'Q1 ''24'!$A$2:$C$10"),
+            Some(("Q1 '24".to_string(), ((1, 0), (9, 2))))
+        );
+    }
+}
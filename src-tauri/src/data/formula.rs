@@ -0,0 +1,593 @@
+//! # Calculated Fields
+//!
+//! Formulas such as `revenue - cost`, `if(region = "EU", price * 1.2, price)`
+//! or `year(order_date)` define new columns. A formula is parsed into a Polars
+//! expression and added to its table's lazy frame after its transformation
+//! steps, so fields are computed only when a query reads them and can be used
+//! wherever a column can.
+//!
+//! Columns are referred to by name, or in brackets when the name is not a
+//! plain identifier, as in `[Order Date]`. Text is quoted with `"` or `'`.
+//! Operators, from loosest to tightest binding: `or`, `and`, `not`,
+//! comparisons (`=`, `!=`, `<>`, `<`, `<=`, `>`, `>=`), `+`, `-` and `&`
+//! (joins text), then `*`, `/` and `%`. Division always gives a decimal.
+
+use crate::data::ingest::dataset_info;
+use crate::data::state::AppDataState;
+use crate::data::types::{CalculatedField, DatasetInfo};
+use crate::error::DataError;
+use polars::prelude::*;
+use tauri::State;
+
+/// Functions formulas can call, for error messages.
+const FUNCTIONS: &str = "if, coalesce, isnull, abs, round, floor, ceil, sqrt, \
+    year, quarter, month, day, weekday, hour, len, upper, lower, trim, contains, concat";
+
+fn error(message: String) -> DataError {
+    DataError::ParseError(message)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(String),
+    Text(String),
+    Ident(String),
+    Column(String),
+    Op(&'static str),
+    Open,
+    Close,
+    Comma,
+}
+
+/// Longer operators first, so `<=` is not read as `<` followed by `=`.
+const OPERATORS: [&str; 14] = [
+    "<=", ">=", "!=", "<>", "==", "=", "<", ">", "+", "-", "*", "/", "%", "&",
+];
+
+/// Splits a formula into tokens, each with the 1-based character position it
+/// starts at.
+fn tokenize(formula: &str) -> Result<Vec<(usize, Token)>, DataError> {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => {
+                i += 1;
+                Token::Open
+            }
+            ')' => {
+                i += 1;
+                Token::Close
+            }
+            ',' => {
+                i += 1;
+                Token::Comma
+            }
+            '"' | '\'' | '[' => {
+                let end = if c == '[' { ']' } else { c };
+                let close = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == end)
+                    .ok_or_else(|| {
+                        error(format!(
+                            "Missing closing {} for {} at {}",
+                            end,
+                            c,
+                            start + 1
+                        ))
+                    })?;
+                let text: String = chars[i + 1..i + 1 + close].iter().collect();
+                i += close + 2;
+                if c == '[' {
+                    Token::Column(text)
+                } else {
+                    Token::Text(text)
+                }
+            }
+            c if c.is_ascii_digit()
+                || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) =>
+            {
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                Token::Number(chars[start..i].iter().collect())
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                Token::Ident(chars[start..i].iter().collect())
+            }
+            _ => {
+                let rest: String = chars[i..].iter().take(2).collect();
+                let op = OPERATORS
+                    .iter()
+                    .find(|op| rest.starts_with(*op))
+                    .copied()
+                    .ok_or_else(|| error(format!("Unexpected '{}' at {}", c, start + 1)))?;
+                i += op.chars().count();
+                Token::Op(op)
+            }
+        };
+        tokens.push((start + 1, token));
+    }
+
+    Ok(tokens)
+}
+
+/// A parsed formula.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bool(bool),
+    Null,
+    Column(String),
+    Neg(Box<Node>),
+    Not(Box<Node>),
+    Binary(&'static str, Box<Node>, Box<Node>),
+    Call(String, Vec<Node>),
+}
+
+/// Recursive-descent parser, one method per precedence level.
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    /// Position of the next token, or just past the end of the formula.
+    fn at(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.len + 1, |(at, _)| *at)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(_, token)| token.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, expected: Token, what: &str) -> Result<(), DataError> {
+        if self.peek() == Some(&expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(error(format!("Expected {} at {}", what, self.at())))
+        }
+    }
+
+    fn or(&mut self) -> Result<Node, DataError> {
+        let mut node = self.and()?;
+        while self.is_keyword("or") {
+            self.pos += 1;
+            node = Node::Binary("or", Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node, DataError> {
+        let mut node = self.not()?;
+        while self.is_keyword("and") {
+            self.pos += 1;
+            node = Node::Binary("and", Box::new(node), Box::new(self.not()?));
+        }
+        Ok(node)
+    }
+
+    fn not(&mut self) -> Result<Node, DataError> {
+        if self.is_keyword("not") {
+            self.pos += 1;
+            return Ok(Node::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Node, DataError> {
+        let node = self.additive()?;
+        match self.peek() {
+            Some(&Token::Op(op @ ("=" | "==" | "!=" | "<>" | "<" | "<=" | ">" | ">="))) => {
+                self.pos += 1;
+                Ok(Node::Binary(op, Box::new(node), Box::new(self.additive()?)))
+            }
+            _ => Ok(node),
+        }
+    }
+
+    fn additive(&mut self) -> Result<Node, DataError> {
+        let mut node = self.multiplicative()?;
+        while let Some(&Token::Op(op @ ("+" | "-" | "&"))) = self.peek() {
+            self.pos += 1;
+            node = Node::Binary(op, Box::new(node), Box::new(self.multiplicative()?));
+        }
+        Ok(node)
+    }
+
+    fn multiplicative(&mut self) -> Result<Node, DataError> {
+        let mut node = self.unary()?;
+        while let Some(&Token::Op(op @ ("*" | "/" | "%"))) = self.peek() {
+            self.pos += 1;
+            node = Node::Binary(op, Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node, DataError> {
+        if self.peek() == Some(&Token::Op("-")) {
+            self.pos += 1;
+            return Ok(Node::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Node, DataError> {
+        let at = self.at();
+        match self.next() {
+            Some(Token::Number(text)) => match text.parse::<i64>() {
+                Ok(n) => Ok(Node::Int(n)),
+                Err(_) => text
+                    .parse::<f64>()
+                    .map(Node::Float)
+                    .map_err(|_| error(format!("Invalid number '{}' at {}", text, at))),
+            },
+            Some(Token::Text(text)) => Ok(Node::Text(text)),
+            Some(Token::Column(name)) => Ok(Node::Column(name)),
+            Some(Token::Ident(name)) if self.peek() == Some(&Token::Open) => {
+                self.pos += 1;
+                let mut args = Vec::new();
+                if self.peek() != Some(&Token::Close) {
+                    args.push(self.or()?);
+                    while self.peek() == Some(&Token::Comma) {
+                        self.pos += 1;
+                        args.push(self.or()?);
+                    }
+                }
+                self.expect(Token::Close, "')'")?;
+                Ok(Node::Call(name.to_lowercase(), args))
+            }
+            Some(Token::Ident(name)) => Ok(match name.to_lowercase().as_str() {
+                "true" => Node::Bool(true),
+                "false" => Node::Bool(false),
+                "null" => Node::Null,
+                _ => Node::Column(name),
+            }),
+            Some(Token::Open) => {
+                let node = self.or()?;
+                self.expect(Token::Close, "')'")?;
+                Ok(node)
+            }
+            Some(_) => Err(error(format!("Unexpected token at {}", at))),
+            None => Err(error(format!("Formula ends early at {}", at))),
+        }
+    }
+}
+
+/// Parses a formula into its syntax tree.
+fn parse(formula: &str) -> Result<Node, DataError> {
+    let mut parser = Parser {
+        tokens: tokenize(formula)?,
+        pos: 0,
+        len: formula.chars().count(),
+    };
+    if parser.tokens.is_empty() {
+        return Err(error("Formula is empty".to_string()));
+    }
+    let node = parser.or()?;
+    if parser.peek().is_some() {
+        return Err(error(format!("Unexpected token at {}", parser.at())));
+    }
+    Ok(node)
+}
+
+/// Smallest and largest number of arguments of each function, or `None`
+/// for names that are not functions.
+fn arity(name: &str) -> Option<(usize, usize)> {
+    Some(match name {
+        "if" => (2, 3),
+        "coalesce" | "concat" => (1, usize::MAX),
+        "round" => (1, 2),
+        "contains" => (2, 2),
+        "isnull" | "abs" | "floor" | "ceil" | "sqrt" | "year" | "quarter" | "month" | "day"
+        | "weekday" | "hour" | "len" | "upper" | "lower" | "trim" => (1, 1),
+        _ => return None,
+    })
+}
+
+fn compile_call(name: &str, args: &[Node], schema: &Schema) -> Result<Expr, DataError> {
+    let (min, max) = arity(name).ok_or_else(|| {
+        error(format!(
+            "Unknown function '{}'; use one of {}",
+            name, FUNCTIONS
+        ))
+    })?;
+    if !(min..=max).contains(&args.len()) {
+        let expected = match max {
+            max if max == min => min.to_string(),
+            usize::MAX => format!("at least {}", min),
+            max => format!("{} to {}", min, max),
+        };
+        return Err(error(format!(
+            "{}() takes {} arguments, got {}",
+            name,
+            expected,
+            args.len()
+        )));
+    }
+
+    let mut compiled = args
+        .iter()
+        .map(|arg| compile(arg, schema))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter();
+    let mut arg = || compiled.next().unwrap_or(lit(NULL));
+
+    Ok(match name {
+        "if" => when(arg()).then(arg()).otherwise(arg()),
+        "coalesce" => coalesce(&compiled.collect::<Vec<_>>()),
+        "concat" => {
+            let parts: Vec<Expr> = compiled.map(|e| e.cast(DataType::String)).collect();
+            concat_str(parts, "", false)
+        }
+        "round" => {
+            let digits = match args.get(1) {
+                None => 0,
+                Some(Node::Int(n)) if (0..=15).contains(n) => *n as u32,
+                Some(_) => {
+                    return Err(error(
+                        "round() takes a whole number of digits from 0 to 15".to_string(),
+                    ))
+                }
+            };
+            arg().round(digits)
+        }
+        "contains" => arg().str().contains_literal(arg()),
+        "isnull" => arg().is_null(),
+        "abs" => arg().abs(),
+        "floor" => arg().floor(),
+        "ceil" => arg().ceil(),
+        "sqrt" => arg().sqrt(),
+        "year" => arg().dt().year(),
+        "quarter" => arg().dt().quarter(),
+        "month" => arg().dt().month(),
+        "day" => arg().dt().day(),
+        "weekday" => arg().dt().weekday(),
+        "hour" => arg().dt().hour(),
+        "len" => arg().str().len_chars(),
+        "upper" => arg().str().to_uppercase(),
+        "lower" => arg().str().to_lowercase(),
+        _ => arg().str().strip_chars(lit(NULL)),
+    })
+}
+
+/// Turns a syntax tree into an expression over the columns in `schema`.
+fn compile(node: &Node, schema: &Schema) -> Result<Expr, DataError> {
+    Ok(match node {
+        Node::Int(n) => lit(*n),
+        Node::Float(x) => lit(*x),
+        Node::Text(text) => lit(text.clone()),
+        Node::Bool(b) => lit(*b),
+        Node::Null => lit(NULL),
+        Node::Column(name) if schema.contains(name) => col(name),
+        Node::Column(name) => return Err(error(format!("Unknown column '{}'", name))),
+        Node::Neg(inner) => -compile(inner, schema)?,
+        Node::Not(inner) => compile(inner, schema)?.not(),
+        Node::Binary(op, left, right) => {
+            let (l, r) = (compile(left, schema)?, compile(right, schema)?);
+            match *op {
+                "+" => l + r,
+                "-" => l - r,
+                "*" => l * r,
+                "/" => l.cast(DataType::Float64) / r.cast(DataType::Float64),
+                "%" => l % r,
+                "&" => concat_str(
+                    [l.cast(DataType::String), r.cast(DataType::String)],
+                    "",
+                    false,
+                ),
+                "=" | "==" => l.eq(r),
+                "!=" | "<>" => l.neq(r),
+                "<" => l.lt(r),
+                "<=" => l.lt_eq(r),
+                ">" => l.gt(r),
+                ">=" => l.gt_eq(r),
+                "and" => l.and(r),
+                _ => l.or(r),
+            }
+        }
+        Node::Call(name, args) => compile_call(name, args, schema)?,
+    })
+}
+
+/// The expression computing `field` on top of `lf`. Fails when the formula
+/// does not parse or refers to a missing column. Operations on columns of the
+/// wrong type only fail when the expression runs; `transform::build_view`
+/// checks for that.
+pub fn field_expr(lf: &LazyFrame, field: &CalculatedField) -> Result<Expr, DataError> {
+    let name = field.name.trim();
+    if name.is_empty() {
+        return Err(error("Calculated fields need a name".to_string()));
+    }
+    let schema = lf.clone().collect_schema()?;
+    if schema.contains(name) {
+        return Err(error(format!("Column '{}' already exists", name)));
+    }
+
+    Ok(compile(&parse(&field.formula)?, &schema)?.alias(name))
+}
+
+fn target_table(table: Option<String>, active: Option<&String>) -> Result<String, DataError> {
+    table.or_else(|| active.cloned()).ok_or(DataError::NoData)
+}
+
+/// Adds a calculated field to a table, the active table by default, or
+/// changes the formula of the field with that name. The table becomes active.
+#[tauri::command]
+pub async fn add_calculated_field(
+    table: Option<String>,
+    name: String,
+    formula: String,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    let mut data_state = state
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+    let table = target_table(table, data_state.active_table.as_ref())?;
+    let field = CalculatedField {
+        name: name.trim().to_string(),
+        formula,
+    };
+
+    data_state.set_calculated_field(&table, field)?;
+    data_state
+        .set_active_table(table)
+        .map_err(DataError::ParseError)?;
+    dataset_info(&data_state, None)
+}
+
+/// Removes a calculated field from a table, the active table by default.
+#[tauri::command]
+pub async fn remove_calculated_field(
+    table: Option<String>,
+    name: String,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    let mut data_state = state
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+    let table = target_table(table, data_state.active_table.as_ref())?;
+
+    data_state.remove_calculated_field(&table, &name)?;
+    data_state
+        .set_active_table(table)
+        .map_err(DataError::ParseError)?;
+    dataset_info(&data_state, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orders() -> LazyFrame {
+        df!(
+            "revenue" => [100i64, 250, 80],
+            "cost" => [60i64, 200, 90],
+            "region" => ["EU", "US", "EU"],
+            "name" => ["Anna", "Bo", "Li Wei"],
+            // 2024-01-01, 2024-05-15 and 2023-12-31 as days since the epoch.
+            "order_date" => [19723i32, 19858, 19722],
+        )
+        .unwrap()
+        .lazy()
+        .with_column(col("order_date").cast(DataType::Date))
+    }
+
+    fn field(name: &str, formula: &str) -> CalculatedField {
+        CalculatedField {
+            name: name.to_string(),
+            formula: formula.to_string(),
+        }
+    }
+
+    fn evaluate(formula: &str) -> Series {
+        let lf = orders();
+        let expr = field_expr(&lf, &field("result", formula)).unwrap();
+        let df = lf.select([expr]).collect().unwrap();
+        df.column("result").unwrap().clone()
+    }
+
+    #[test]
+    fn test_parses_precedence_and_keywords() {
+        assert_eq!(
+            parse("a + b * -2 = 3 and not c").unwrap(),
+            Node::Binary(
+                "and",
+                Box::new(Node::Binary(
+                    "=",
+                    Box::new(Node::Binary(
+                        "+",
+                        Box::new(Node::Column("a".to_string())),
+                        Box::new(Node::Binary(
+                            "*",
+                            Box::new(Node::Column("b".to_string())),
+                            Box::new(Node::Neg(Box::new(Node::Int(2)))),
+                        )),
+                    )),
+                    Box::new(Node::Int(3)),
+                )),
+                Box::new(Node::Not(Box::new(Node::Column("c".to_string())))),
+            )
+        );
+        assert_eq!(
+            parse("[Order Date]").unwrap(),
+            Node::Column("Order Date".to_string())
+        );
+    }
+
+    #[test]
+    fn test_evaluates_arithmetic_conditions_and_functions() {
+        let margin = evaluate("revenue - cost");
+        assert_eq!(
+            margin.i64().unwrap().to_vec(),
+            [Some(40), Some(50), Some(-10)]
+        );
+
+        let price = evaluate("if(region = \"EU\", revenue * 1.2, revenue)");
+        assert_eq!(
+            price.f64().unwrap().to_vec(),
+            [Some(120.0), Some(250.0), Some(96.0)]
+        );
+
+        let year = evaluate("year(order_date)");
+        assert_eq!(
+            year.i32().unwrap().to_vec(),
+            [Some(2024), Some(2024), Some(2023)]
+        );
+
+        let len = evaluate("len(name)");
+        assert_eq!(
+            len.cast(&DataType::Int64).unwrap().i64().unwrap().to_vec(),
+            [Some(4), Some(2), Some(6)]
+        );
+
+        let ratio = evaluate("round(cost / revenue, 2)");
+        assert_eq!(ratio.f64().unwrap().get(0), Some(0.6));
+
+        let label = evaluate("upper(region) & '-' & revenue");
+        assert_eq!(label.str().unwrap().get(1), Some("US-250"));
+    }
+
+    #[test]
+    fn test_reports_unknown_names_and_syntax_errors() {
+        let lf = orders();
+        let message = |formula: &str| {
+            field_expr(&lf, &field("f", formula))
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(message("revenue - costs").contains("Unknown column 'costs'"));
+        assert!(message("median(revenue)").contains("Unknown function 'median'"));
+        assert!(message("(revenue - cost").contains("Expected ')'"));
+        assert!(message("revenue cost").contains("Unexpected token at 9"));
+        assert!(field_expr(&lf, &field("cost", "revenue")).is_err());
+    }
+}
@@ -1,69 +1,61 @@
-use crate::data::state::AppDataState;
-use crate::data::types::{ColumnInfo, DataPage, DatasetInfo};
+use crate::data::archive;
+use crate::data::excel;
+use crate::data::json;
+use crate::data::numeric;
+use crate::data::overrides;
+use crate::data::progress;
+use crate::data::report;
+use crate::data::semantic;
+use crate::data::sniff;
+use crate::data::sqlite;
+use crate::data::state::{self, AppDataState, DataSource, DataState, Table};
+use crate::data::tail;
+use crate::data::text;
+use crate::data::types::{
+    CsvImportOptions, CsvPreview, DataPage, DatasetInfo, DateFormats, ExcelImportOptions,
+    FixedWidthOptions, FixedWidthPreview, IngestReport, JsonImportOptions, LoadOptions,
+    NumberFormats, NumberLocale, SchemaOverrides, SkippedTable, SourceInfo, SqliteObject,
+    TextEncoding,
+};
+use crate::data::union;
+use crate::data::watch::{self, AppWatchState};
 use crate::error::DataError;
-use calamine::{open_workbook, Reader, Xlsx, Data as CalamineData};
+use calamine::{Reader, Sheets};
 use polars::prelude::*;
 use std::fs;
-use std::path::Path;
-use tauri::State;
-
-fn polars_dtype_to_string(dtype: &DataType) -> String {
-    match dtype {
-        DataType::Int8
-        | DataType::Int16
-        | DataType::Int32
-        | DataType::Int64
-        | DataType::UInt8
-        | DataType::UInt16
-        | DataType::UInt32
-        | DataType::UInt64 => "integer".to_string(),
-        DataType::Float32 | DataType::Float64 => "float".to_string(),
-        DataType::Boolean => "boolean".to_string(),
-        DataType::Date | DataType::Datetime(_, _) => "date".to_string(),
-        _ => "string".to_string(),
-    }
-}
-
-fn df_to_columns(df: &DataFrame) -> Vec<ColumnInfo> {
-    df.get_columns()
-        .iter()
-        .map(|col| ColumnInfo {
-            name: col.name().to_string(),
-            dtype: polars_dtype_to_string(col.dtype()),
-            nullable: true,
-        })
-        .collect()
-}
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
 
-fn try_parse_dates(df: DataFrame) -> DataFrame {
-    let mut df = df;
-    let date_formats = vec![
-        "%Y-%m-%d",
-        "%Y/%m/%d",
-        "%m/%d/%Y",
-        "%m-%d-%Y",
-        "%d/%m/%Y",
-        "%d-%m-%Y",
-        "%Y-%m-%d %H:%M:%S",
-        "%Y/%m/%d %H:%M:%S",
-    ];
-
-    let string_cols: Vec<(usize, String)> = df
-        .get_columns()
-        .iter()
-        .enumerate()
-        .filter(|(_, col)| matches!(col.dtype(), DataType::String))
-        .map(|(idx, col)| (idx, col.name().to_string()))
-        .collect();
+const DATE_FORMATS: [&str; 8] = [
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%m/%d/%Y",
+    "%m-%d-%Y",
+    "%d/%m/%Y",
+    "%d-%m-%Y",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y/%m/%d %H:%M:%S",
+];
+
+/// Finds string columns whose leading values parse as dates, returning the
+/// best matching format for each. Columns with a user override are left alone.
+pub(crate) fn detect_date_formats(
+    df: &DataFrame,
+    overrides: &SchemaOverrides,
+) -> Vec<(String, &'static str)> {
+    let mut detected = Vec::new();
+
+    for col in df.get_columns() {
+        if !matches!(col.dtype(), DataType::String) || overrides.contains_key(col.name().as_str()) {
+            continue;
+        }
 
-    for (idx, col_name) in string_cols {
-        let col = df.column(&col_name).unwrap();
-        
         let sample_size = 100.min(col.len());
         let mut successful_parses = 0;
-        let mut found_format: Option<&str> = None;
+        let mut found_format: Option<&'static str> = None;
 
-        for format in &date_formats {
+        for format in DATE_FORMATS {
             let mut parses = 0;
             for i in 0..sample_size {
                 if let Ok(AnyValue::String(s)) = col.get(i) {
@@ -77,7 +69,7 @@ fn try_parse_dates(df: DataFrame) -> DataFrame {
                     }
                 }
             }
-            
+
             if parses > successful_parses {
                 successful_parses = parses;
                 found_format = Some(format);
@@ -86,56 +78,101 @@ fn try_parse_dates(df: DataFrame) -> DataFrame {
 
         if successful_parses > 0 && successful_parses as f64 / sample_size as f64 > 0.8 {
             if let Some(format) = found_format {
-                if let Ok(parsed) = col
-                    .str()
-                    .and_then(|ca| ca.as_date(Some(format), false))
-                {
-                    df.replace(&col_name, parsed.into_series()).ok();
-                }
+                detected.push((col.name().to_string(), format));
             }
         }
     }
 
+    detected
+}
+
+/// Parses the text columns named in `formats` as dates. Values that do not
+/// match their column's format become null.
+fn parse_dates(df: DataFrame, formats: &DateFormats) -> DataFrame {
+    let mut df = df;
+
+    for (col_name, format) in formats {
+        let Ok(col) = df.column(col_name) else {
+            continue;
+        };
+        if let Ok(parsed) = col.str().and_then(|ca| ca.as_date(Some(format), false)) {
+            df.replace(col_name, parsed.into_series()).ok();
+        }
+    }
+
     df
 }
 
-fn df_to_rows(df: &DataFrame, offset: usize, limit: usize) -> Vec<Vec<serde_json::Value>> {
-    let height = df.height();
-    let end = (offset + limit).min(height);
+/// Detects and parses date columns, returning the formats that were used.
+fn detect_and_parse_dates(df: DataFrame, overrides: &SchemaOverrides) -> (DataFrame, DateFormats) {
+    let formats: DateFormats = detect_date_formats(&df, overrides)
+        .into_iter()
+        .map(|(col_name, format)| (col_name, format.to_string()))
+        .collect();
+    (parse_dates(df, &formats), formats)
+}
+
+fn try_parse_dates(df: DataFrame, overrides: &SchemaOverrides) -> DataFrame {
+    detect_and_parse_dates(df, overrides).0
+}
+
+/// Lazy counterpart of `try_parse_dates`: formats are detected on the first
+/// rows of the scan and applied as expressions, so the file is not read here.
+fn try_parse_dates_lazy(
+    lf: LazyFrame,
+    overrides: &SchemaOverrides,
+) -> Result<LazyFrame, DataError> {
+    let sample = lf.clone().limit(100).collect()?;
 
-    if offset >= height {
-        return vec![];
+    let conversions: Vec<Expr> = detect_date_formats(&sample, overrides)
+        .into_iter()
+        .map(|(col_name, format)| {
+            col(&col_name).str().to_date(StrptimeOptions {
+                format: Some(format.into()),
+                strict: false,
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    if conversions.is_empty() {
+        Ok(lf)
+    } else {
+        Ok(lf.with_columns(conversions))
     }
+}
 
-    let sliced = df.slice(offset as i64, end - offset);
-    let mut rows = Vec::with_capacity(sliced.height());
+pub(crate) fn any_value_to_json(value: AnyValue) -> serde_json::Value {
+    match value {
+        AnyValue::Null => serde_json::Value::Null,
+        AnyValue::Int8(v) => serde_json::json!(v),
+        AnyValue::Int16(v) => serde_json::json!(v),
+        AnyValue::Int32(v) => serde_json::json!(v),
+        AnyValue::Int64(v) => serde_json::json!(v),
+        AnyValue::UInt8(v) => serde_json::json!(v),
+        AnyValue::UInt16(v) => serde_json::json!(v),
+        AnyValue::UInt32(v) => serde_json::json!(v),
+        AnyValue::UInt64(v) => serde_json::json!(v),
+        AnyValue::Float32(v) => serde_json::json!(v),
+        AnyValue::Float64(v) => serde_json::json!(v),
+        AnyValue::Boolean(v) => serde_json::json!(v),
+        AnyValue::String(v) => serde_json::json!(v),
+        AnyValue::StringOwned(v) => serde_json::json!(v.to_string()),
+        v @ (AnyValue::Date(_) | AnyValue::Datetime(..)) => serde_json::json!(v.to_string()),
+        v => serde_json::json!(v.to_string()),
+    }
+}
+
+fn df_to_rows(df: &DataFrame) -> Vec<Vec<serde_json::Value>> {
+    let mut rows = Vec::with_capacity(df.height());
 
-    for i in 0..sliced.height() {
-        let row: Vec<serde_json::Value> = sliced
+    for i in 0..df.height() {
+        let row: Vec<serde_json::Value> = df
             .get_columns()
             .iter()
-            .map(|col| {
-                let val = col.get(i).ok();
-                match val {
-                    Some(AnyValue::Null) => serde_json::Value::Null,
-                    Some(AnyValue::Int8(v)) => serde_json::json!(v),
-                    Some(AnyValue::Int16(v)) => serde_json::json!(v),
-                    Some(AnyValue::Int32(v)) => serde_json::json!(v),
-                    Some(AnyValue::Int64(v)) => serde_json::json!(v),
-                    Some(AnyValue::UInt8(v)) => serde_json::json!(v),
-                    Some(AnyValue::UInt16(v)) => serde_json::json!(v),
-                    Some(AnyValue::UInt32(v)) => serde_json::json!(v),
-                    Some(AnyValue::UInt64(v)) => serde_json::json!(v),
-                    Some(AnyValue::Float32(v)) => serde_json::json!(v),
-                    Some(AnyValue::Float64(v)) => serde_json::json!(v),
-                    Some(AnyValue::Boolean(v)) => serde_json::json!(v),
-                    Some(AnyValue::String(v)) => serde_json::json!(v),
-                    Some(AnyValue::StringOwned(v)) => serde_json::json!(v.to_string()),
-                    Some(AnyValue::Date(d)) => serde_json::json!(d.to_string()),
-                    Some(AnyValue::Datetime(dt, _, _)) => serde_json::json!(dt.to_string()),
-                    Some(v) => serde_json::json!(v.to_string()),
-                    None => serde_json::Value::Null,
-                }
+            .map(|col| match col.get(i) {
+                Ok(val) => any_value_to_json(val),
+                Err(_) => serde_json::Value::Null,
             })
             .collect();
         rows.push(row);
@@ -144,104 +181,683 @@ fn df_to_rows(df: &DataFrame, offset: usize, limit: usize) -> Vec<Vec<serde_json
     rows
 }
 
-#[tauri::command]
-pub async fn load_csv(
-    path: String,
-    state: State<'_, AppDataState>,
-) -> Result<DatasetInfo, DataError> {
-    let file_path = std::path::PathBuf::from(&path);
+fn check_file(path: &str) -> Result<(), DataError> {
+    if Path::new(path).exists() {
+        Ok(())
+    } else {
+        Err(DataError::FileNotFound(path.to_string()))
+    }
+}
 
-    if !file_path.exists() {
-        return Err(DataError::FileNotFound(path));
+/// A table as read from a file, with what was learned while reading it.
+pub(crate) struct LoadedTable {
+    table: Table,
+    report: Option<IngestReport>,
+    number_formats: NumberFormats,
+    date_formats: DateFormats,
+    /// Where reading stopped, when later rows can be appended from there.
+    read_offset: Option<u64>,
+    /// Whether the last row came from a line without a newline yet.
+    partial_row: bool,
+}
+
+impl LoadedTable {
+    fn new(table: Table) -> Self {
+        Self {
+            table,
+            report: None,
+            number_formats: NumberFormats::new(),
+            date_formats: DateFormats::new(),
+            read_offset: None,
+            partial_row: false,
+        }
     }
 
-    let metadata = fs::metadata(&path)?;
-    let file_size = metadata.len();
+    /// Marks the table as read from a file that can have rows appended,
+    /// from the `append_position` of its contents.
+    fn appendable(self, position: Option<(u64, bool)>) -> Self {
+        match position {
+            Some((read_offset, partial_row)) => Self {
+                read_offset: Some(read_offset),
+                partial_row,
+                ..self
+            },
+            None => self,
+        }
+    }
+}
+
+/// Where appending to a file read as `contents` starts: after its last
+/// complete line, so a line still being written is read again once it is
+/// finished. Also says whether such a line was read as a row.
+fn append_position(contents: &[u8]) -> Option<(u64, bool)> {
+    let last_newline = contents.iter().rposition(|&b| b == b'\n')?;
+    let rest = &contents[last_newline + 1..];
+    Some((
+        last_newline as u64 + 1,
+        rest.iter().any(|b| !b.is_ascii_whitespace()),
+    ))
+}
+
+/// Describes the active table and the source it was read from.
+pub(crate) fn dataset_info(
+    data_state: &DataState,
+    report: Option<IngestReport>,
+) -> Result<DatasetInfo, DataError> {
+    let table_name = data_state.active_table.clone().ok_or(DataError::NoData)?;
+    let table = data_state.get_active_table().ok_or(DataError::NoData)?;
+    let source = data_state.active_source();
+    let path = source.map(|s| s.path.clone()).unwrap_or_default();
+
+    let file_path = Path::new(&path);
     let file_name = file_path
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("unknown")
         .to_string();
+    let file_size = fs::metadata(file_path).map(|m| m.len()).unwrap_or(0);
 
-    let df = CsvReadOptions::default()
-        .with_has_header(true)
-        .with_infer_schema_length(Some(1000))
-        .with_ignore_errors(true)
-        .try_into_reader_with_file_path(Some(file_path.clone()))?
-        .finish()?;
+    let mut columns = table.columns()?;
+    semantic::apply_number_formats(&mut columns, &data_state.view_number_formats(&table_name));
+    for field in data_state.get_calculated_fields(&table_name) {
+        if let Some(column) = columns.iter_mut().find(|c| c.name == field.name) {
+            column.formula = Some(field.formula.clone());
+        }
+    }
 
-    // Try to detect and parse date columns
-    let df = try_parse_dates(df);
+    Ok(DatasetInfo {
+        source: source.map(|s| s.name.clone()).unwrap_or_default(),
+        file_name,
+        file_path: path,
+        file_size,
+        row_count: table.row_count()?,
+        columns,
+        tables: data_state.get_tables(),
+        active_table: table_name,
+        report,
+    })
+}
 
-    let columns = df_to_columns(&df);
-    let row_count = df.height();
+pub(crate) fn register_single_table(
+    state: &AppDataState,
+    loaded: LoadedTable,
+    path: String,
+    load: LoadOptions,
+    schema_overrides: SchemaOverrides,
+) -> Result<DatasetInfo, DataError> {
+    let tables = vec![("default".to_string(), loaded)];
+    register_tables(state, tables, path, load, schema_overrides)
+}
+
+/// Adds the tables read from one file to the workspace as a source and makes
+/// the first of them active. Other sources are kept.
+fn register_tables(
+    state: &AppDataState,
+    tables: Vec<(String, LoadedTable)>,
+    path: String,
+    load: LoadOptions,
+    schema_overrides: SchemaOverrides,
+) -> Result<DatasetInfo, DataError> {
+    if tables.is_empty() {
+        return Err(DataError::NoData);
+    }
+    let report = tables[0].1.report.clone();
+    let (read_offset, partial_row) = match tables.as_slice() {
+        [(_, loaded)] => (loaded.read_offset, loaded.partial_row),
+        _ => (None, false),
+    };
+    let mut date_formats = Vec::with_capacity(tables.len());
+    let tables = tables
+        .into_iter()
+        .map(|(name, loaded)| {
+            date_formats.push(loaded.date_formats);
+            (name, loaded.table, loaded.number_formats)
+        })
+        .collect();
 
     let mut data_state = state
         .lock()
         .map_err(|e| DataError::ParseError(e.to_string()))?;
-    
-    data_state.clear();
-    let table_name = "default".to_string();
-    data_state.add_dataframe(table_name.clone(), df);
-    data_state.set_active_table(table_name.clone()).map_err(|e| DataError::ParseError(e))?;
-    data_state.set_file_path(path.clone());
 
-    Ok(DatasetInfo {
-        file_name,
-        file_path: path,
-        file_size,
-        row_count,
-        columns,
-        tables: vec![table_name],
+    let source = data_state.add_source(path, load, schema_overrides, tables);
+    let source_name = source.name.clone();
+    let table_names = source.tables.clone();
+    for (table, formats) in table_names.iter().zip(date_formats) {
+        data_state.set_date_formats(table.clone(), formats);
+    }
+    let first_table = table_names[0].clone();
+    data_state.set_read_offset(&source_name, read_offset, partial_row);
+    data_state
+        .set_active_table(first_table)
+        .map_err(DataError::ParseError)?;
+
+    dataset_info(&data_state, report)
+}
+
+/// Number of rows returned by `preview_csv`.
+const CSV_PREVIEW_ROWS: usize = 100;
+
+fn ascii_byte(c: char, what: &str) -> Result<u8, DataError> {
+    u8::try_from(c)
+        .ok()
+        .filter(u8::is_ascii)
+        .ok_or_else(|| DataError::ParseError(format!("{} must be an ASCII character", what)))
+}
+
+/// The delimiter as the byte the CSV reader splits on. Other delimiters are
+/// rewritten by `text::delimited_to_csv` before the reader sees the file.
+fn separator(options: &CsvImportOptions) -> Result<u8, DataError> {
+    match options.delimiter.as_bytes() {
+        [b] if b.is_ascii() => Ok(*b),
+        _ => Err(DataError::ParseError(
+            "Delimiter must be a single ASCII character".to_string(),
+        )),
+    }
+}
+
+fn csv_read_options(options: &CsvImportOptions) -> Result<CsvReadOptions, DataError> {
+    let separator = separator(options)?;
+    let quote_char = options
+        .quote_char
+        .map(|q| ascii_byte(q, "Quote character"))
+        .transpose()?;
+
+    Ok(CsvReadOptions::default()
+        .with_has_header(options.has_header)
+        .with_skip_rows(options.skip_rows)
+        .with_infer_schema_length(Some(1000))
+        .with_ignore_errors(true)
+        .map_parse_options(|parse| {
+            parse
+                .with_separator(separator)
+                .with_quote_char(quote_char)
+                .with_truncate_ragged_lines(true)
+        }))
+}
+
+/// Reads a CSV file into memory. Rows with nulls are read again as text, and
+/// the whole text is streamed, so that values lost to type inference and rows
+/// with the wrong field count can be reported. `contents` holds the file's
+/// bytes when they did not come straight from `path`, as for decompressed
+/// files and archive members.
+fn read_csv(
+    path: &str,
+    contents: Option<Vec<u8>>,
+    options: &CsvImportOptions,
+    schema_overrides: &SchemaOverrides,
+    number_locale: Option<NumberLocale>,
+) -> Result<LoadedTable, DataError> {
+    if !text::is_native_delimiter(&options.delimiter) {
+        let bytes = match contents {
+            Some(bytes) => bytes,
+            None => fs::read(path)?,
+        };
+        let text = sniff::decode(&bytes, options.encoding);
+        let (csv, csv_options) = text::delimited_to_csv(&text, options)?;
+        return read_csv(
+            path,
+            Some(csv),
+            &csv_options,
+            schema_overrides,
+            number_locale,
+        );
+    }
+
+    let read_options = csv_read_options(options)?
+        .with_schema_overwrite(Some(Arc::new(overrides::text_schema(schema_overrides))));
+
+    let decoded = match (contents, options.encoding) {
+        (contents, TextEncoding::Utf8) => contents,
+        (contents, encoding) => {
+            let bytes = match contents {
+                Some(bytes) => bytes,
+                None => fs::read(path)?,
+            };
+            Some(sniff::decode(&bytes, encoding).into_owned().into_bytes())
+        }
+    };
+
+    let typed = match &decoded {
+        Some(bytes) => read_options
+            .into_reader_with_file_handle(Cursor::new(bytes.as_slice()))
+            .finish()?,
+        None => read_options
+            .try_into_reader_with_file_path(Some(path.into()))?
+            .finish()?,
+    };
+    overrides::check_columns(schema_overrides, &typed.schema())?;
+
+    // Try to detect and parse date columns
+    let (typed, date_formats) = detect_and_parse_dates(typed, schema_overrides);
+    let number_formats = numeric::detect_formats(&typed, number_locale, schema_overrides);
+    let typed = numeric::parse_numbers(typed, &number_formats)?;
+    let typed = overrides::apply(typed, schema_overrides)?;
+
+    let separator = separator(options)?;
+    let quote_char = options
+        .quote_char
+        .map(|q| ascii_byte(q, "Quote character"))
+        .transpose()?;
+    let text = || -> Result<Box<dyn Read + '_>, DataError> {
+        Ok(match &decoded {
+            Some(bytes) => Box::new(bytes.as_slice()),
+            None => Box::new(fs::File::open(path)?),
+        })
+    };
+
+    let null_rows = report::rows_with_nulls(&typed);
+    let column_issues = if null_rows.is_empty() {
+        Vec::new()
+    } else {
+        let raw = report::raw_rows(
+            text()?,
+            separator,
+            quote_char,
+            options.skip_rows,
+            options.has_header,
+            &null_rows,
+            &typed.get_column_names_owned(),
+        )?;
+        report::coercion_issues_in_rows(&raw, &null_rows, &typed)?
+    };
+    let malformed = report::malformed_rows(text()?, separator, quote_char, options.skip_rows)?;
+
+    let report = report::build_report(typed.height(), column_issues, malformed);
+    Ok(LoadedTable {
+        report: Some(report),
+        number_formats,
+        date_formats,
+        ..LoadedTable::new(Table::Materialized(typed))
     })
 }
 
+fn scan_csv(
+    path: &str,
+    options: &CsvImportOptions,
+    schema_overrides: &SchemaOverrides,
+    number_locale: Option<NumberLocale>,
+) -> Result<LoadedTable, DataError> {
+    if options.encoding != TextEncoding::Utf8 {
+        return Err(DataError::UnsupportedFormat(
+            "Scanning requires a UTF-8 file; load it into memory instead".to_string(),
+        ));
+    }
+    if archive::is_compressed(path) {
+        return Err(DataError::UnsupportedFormat(
+            "Scanning requires an uncompressed file; load it into memory instead".to_string(),
+        ));
+    }
+    if !text::is_native_delimiter(&options.delimiter) {
+        return Err(DataError::UnsupportedFormat(
+            "Scanning requires a single-character delimiter; load it into memory instead"
+                .to_string(),
+        ));
+    }
+
+    let quote_char = options
+        .quote_char
+        .map(|q| ascii_byte(q, "Quote character"))
+        .transpose()?;
+
+    let lf = LazyCsvReader::new(path)
+        .with_has_header(options.has_header)
+        .with_skip_rows(options.skip_rows)
+        .with_separator(separator(options)?)
+        .with_quote_char(quote_char)
+        .with_infer_schema_length(Some(1000))
+        .with_ignore_errors(true)
+        .with_dtype_overwrite(Some(Arc::new(overrides::text_schema(schema_overrides))))
+        .finish()?;
+    overrides::check_columns(schema_overrides, lf.clone().collect_schema()?.as_ref())?;
+
+    let lf = try_parse_dates_lazy(lf, schema_overrides)?;
+    let sample = lf.clone().limit(100).collect()?;
+    let number_formats = numeric::detect_formats(&sample, number_locale, schema_overrides);
+    let lf = numeric::parse_numbers_lazy(lf, &number_formats);
+    let lf = overrides::apply_lazy(lf, schema_overrides)?;
+
+    Ok(LoadedTable {
+        number_formats,
+        ..LoadedTable::new(Table::scan(lf))
+    })
+}
+
+/// Decodes a file sample, dropping a last line that was cut off.
+fn sample_text(sample: &[u8], encoding: TextEncoding) -> String {
+    let mut text = sniff::decode(sample, encoding).into_owned();
+    if sample.len() >= sniff::SAMPLE_BYTES {
+        if let Some(last_newline) = text.rfind('\n') {
+            text.truncate(last_newline + 1);
+        }
+    }
+    text
+}
+
+/// Sniffs the dialect and encoding of a CSV file and parses its first rows.
+/// Compressed files are sampled after decompression.
+/// Passing `options` re-renders the preview with user-adjusted settings.
 #[tauri::command]
-pub async fn load_json(
+pub async fn preview_csv(
+    path: String,
+    options: Option<CsvImportOptions>,
+) -> Result<CsvPreview, DataError> {
+    check_file(&path)?;
+
+    let sample = sniff::read_sample(&path)?;
+    let options = options.unwrap_or_else(|| sniff::sniff_options(&sample));
+    let text = sample_text(&sample, options.encoding);
+
+    let (bytes, read_options) = if text::is_native_delimiter(&options.delimiter) {
+        (text.into_bytes(), csv_read_options(&options)?)
+    } else {
+        let (csv, csv_options) = text::delimited_to_csv(&text, &options)?;
+        (csv, csv_read_options(&csv_options)?)
+    };
+    let df = read_options
+        .with_n_rows(Some(CSV_PREVIEW_ROWS))
+        .into_reader_with_file_handle(Cursor::new(bytes))
+        .finish()?;
+
+    Ok(CsvPreview {
+        options,
+        columns: state::frame_columns(&df),
+        rows: df_to_rows(&df),
+    })
+}
+
+/// Loads a CSV file, which may be compressed, sniffing its dialect unless
+/// `options` are given. With `scan` set, queries stream over the file instead.
+#[tauri::command]
+pub async fn load_csv(
     path: String,
+    options: Option<CsvImportOptions>,
+    scan: Option<bool>,
+    schema_overrides: Option<SchemaOverrides>,
+    number_locale: Option<NumberLocale>,
+    app: AppHandle,
     state: State<'_, AppDataState>,
 ) -> Result<DatasetInfo, DataError> {
-    let file_path = Path::new(&path);
+    check_file(&path)?;
+    let schema_overrides = schema_overrides.unwrap_or_default();
+    let scan = scan.unwrap_or(false);
+    let load = LoadOptions::Csv {
+        options: options.clone(),
+        scan,
+        number_locale,
+    };
 
-    if !file_path.exists() {
-        return Err(DataError::FileNotFound(path));
+    let worker_path = path.clone();
+    let worker_overrides = schema_overrides.clone();
+    let loaded = progress::run(&app, &path, move |progress| {
+        read_csv_file(
+            &worker_path,
+            options,
+            scan,
+            &worker_overrides,
+            number_locale,
+            progress,
+        )
+    })
+    .await?;
+
+    register_single_table(&state, loaded, path, load, schema_overrides)
+}
+
+/// Reads or scans a CSV file for `load_csv`, on the thread `progress` reports from.
+pub(crate) fn read_csv_file(
+    path: &str,
+    options: Option<CsvImportOptions>,
+    scan: bool,
+    schema_overrides: &SchemaOverrides,
+    number_locale: Option<NumberLocale>,
+    progress: &progress::Progress,
+) -> Result<LoadedTable, DataError> {
+    let options = match options {
+        Some(options) => options,
+        None => sniff::sniff_options(&sniff::read_sample(path)?),
+    };
+    if scan {
+        return scan_csv(path, &options, schema_overrides, number_locale);
     }
 
-    let metadata = fs::metadata(&path)?;
-    let file_size = metadata.len();
-    let file_name = file_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown")
-        .to_string();
+    let contents = progress.read_file(path)?;
+    // Rows appended to a plain UTF-8 file later can be read from here on.
+    let position = match options.encoding {
+        TextEncoding::Utf8 if !archive::is_compressed(path) => append_position(&contents),
+        _ => None,
+    };
+    let loaded = read_csv(
+        path,
+        Some(contents),
+        &options,
+        schema_overrides,
+        number_locale,
+    )?;
+    progress.rows(loaded.table.row_count()?);
+
+    Ok(loaded.appendable(position))
+}
+
+/// Options for a fixed-width file, with the encoding detected from `sample`
+/// when none were given.
+fn fixed_width_options(options: Option<FixedWidthOptions>, sample: &[u8]) -> FixedWidthOptions {
+    options.unwrap_or_else(|| FixedWidthOptions {
+        encoding: sniff::detect_encoding(sample),
+        ..Default::default()
+    })
+}
+
+/// Reads fixed-width text by cutting it into columns and reading the result
+/// as CSV, so values are typed and reported on as in `read_csv`.
+fn read_fixed_width(
+    path: &str,
+    contents: Vec<u8>,
+    options: &FixedWidthOptions,
+    schema_overrides: &SchemaOverrides,
+    number_locale: Option<NumberLocale>,
+) -> Result<LoadedTable, DataError> {
+    let text = sniff::decode(&contents, options.encoding);
+    let layout = text::resolve_layout(&text, options)?;
+    let csv = text::fixed_width_to_csv(&text, &layout)?;
+    read_csv(
+        path,
+        Some(csv),
+        &CsvImportOptions::default(),
+        schema_overrides,
+        number_locale,
+    )
+}
+
+/// Detects the column layout of a fixed-width file and parses its first
+/// rows. Passing `options` re-renders the preview with user-adjusted column
+/// positions; the returned options can be passed on to `load_fixed_width`.
+#[tauri::command]
+pub async fn preview_fixed_width(
+    path: String,
+    options: Option<FixedWidthOptions>,
+) -> Result<FixedWidthPreview, DataError> {
+    check_file(&path)?;
 
-    let file = fs::File::open(&path)?;
-    let df = JsonReader::new(file).finish()?;
+    let sample = sniff::read_sample(&path)?;
+    let options = fixed_width_options(options, &sample);
+    let text = sample_text(&sample, options.encoding);
+
+    let layout = text::resolve_layout(&text, &options)?;
+    let csv = text::fixed_width_to_csv(&text, &layout)?;
+    let df = csv_read_options(&CsvImportOptions::default())?
+        .with_n_rows(Some(CSV_PREVIEW_ROWS))
+        .into_reader_with_file_handle(Cursor::new(csv))
+        .finish()?;
+
+    Ok(FixedWidthPreview {
+        options: layout,
+        columns: state::frame_columns(&df),
+        rows: df_to_rows(&df),
+    })
+}
+
+/// Loads a fixed-width text file, which may be compressed, detecting column
+/// positions unless `options` gives them.
+#[tauri::command]
+pub async fn load_fixed_width(
+    path: String,
+    options: Option<FixedWidthOptions>,
+    schema_overrides: Option<SchemaOverrides>,
+    number_locale: Option<NumberLocale>,
+    app: AppHandle,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    check_file(&path)?;
+    let schema_overrides = schema_overrides.unwrap_or_default();
+    let options = fixed_width_options(options, &sniff::read_sample(&path)?);
+
+    let worker_path = path.clone();
+    let worker_options = options.clone();
+    let worker_overrides = schema_overrides.clone();
+    let loaded = progress::run(&app, &path, move |progress| {
+        let path = worker_path;
+        let contents = progress.read_file(&path)?;
+        let loaded = read_fixed_width(
+            &path,
+            contents,
+            &worker_options,
+            &worker_overrides,
+            number_locale,
+        )?;
+        progress.rows(loaded.table.row_count()?);
+        Ok(loaded)
+    })
+    .await?;
+
+    let load = LoadOptions::FixedWidth {
+        options,
+        number_locale,
+    };
+    register_single_table(&state, loaded, path, load, schema_overrides)
+}
+
+/// Flattens JSON records and types their columns.
+fn json_table(
+    df: DataFrame,
+    options: &JsonImportOptions,
+    schema_overrides: &SchemaOverrides,
+) -> Result<LoadedTable, DataError> {
+    let df = json::normalize(df, options)?;
+    overrides::check_columns(schema_overrides, &df.schema())?;
 
     // Try to detect and parse date columns
-    let df = try_parse_dates(df);
+    let (df, date_formats) = detect_and_parse_dates(df, schema_overrides);
+    let df = overrides::apply(df, schema_overrides)?;
 
-    let columns = df_to_columns(&df);
-    let row_count = df.height();
+    Ok(LoadedTable {
+        date_formats,
+        ..LoadedTable::new(Table::Materialized(df))
+    })
+}
 
-    let mut data_state = state
-        .lock()
-        .map_err(|e| DataError::ParseError(e.to_string()))?;
-    
-    data_state.clear();
-    let table_name = "default".to_string();
-    data_state.add_dataframe(table_name.clone(), df);
-    data_state.set_active_table(table_name.clone()).map_err(|e| DataError::ParseError(e))?;
-    data_state.set_file_path(path.clone());
+/// Loads a JSON file, which may be compressed. `.ndjson` and `.jsonl` files
+/// hold one record per line.
+#[tauri::command]
+pub async fn load_json(
+    path: String,
+    options: Option<JsonImportOptions>,
+    schema_overrides: Option<SchemaOverrides>,
+    app: AppHandle,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    check_file(&path)?;
+    let options = options.unwrap_or_default();
+    let schema_overrides = schema_overrides.unwrap_or_default();
 
-    Ok(DatasetInfo {
-        file_name,
-        file_path: path,
-        file_size,
-        row_count,
-        columns,
-        tables: vec![table_name],
+    let worker_path = path.clone();
+    let worker_options = options.clone();
+    let worker_overrides = schema_overrides.clone();
+    let loaded = progress::run(&app, &path, move |progress| {
+        let path = worker_path;
+        let lines = json::is_json_lines(&path);
+        let contents = progress.read_file(&path)?;
+        // NDJSON files can have records appended and read later from here on.
+        let position = if lines && !archive::is_compressed(&path) {
+            append_position(&contents)
+        } else {
+            None
+        };
+
+        let df = if lines {
+            json::read_lines(Cursor::new(contents))?
+        } else {
+            json::read_records(Cursor::new(contents), &worker_options)?
+        };
+        progress.check()?;
+        let loaded = json_table(df, &worker_options, &worker_overrides)?;
+        progress.rows(loaded.table.row_count()?);
+
+        Ok(loaded.appendable(position))
     })
+    .await?;
+
+    let load = LoadOptions::Json { options };
+    register_single_table(&state, loaded, path, load, schema_overrides)
+}
+
+/// Adds a Parquet file to the workspace, as `load_parquet` does.
+pub(crate) fn register_parquet(
+    state: &AppDataState,
+    path: String,
+    scan: bool,
+) -> Result<DatasetInfo, DataError> {
+    check_file(&path)?;
+
+    let table = if scan {
+        Table::scan(LazyFrame::scan_parquet(&path, ScanArgsParquet::default())?)
+    } else {
+        let file = fs::File::open(&path)?;
+        Table::Materialized(ParquetReader::new(file).finish()?)
+    };
+
+    let loaded = LoadedTable::new(table);
+    let load = LoadOptions::Parquet { scan };
+    register_single_table(state, loaded, path, load, SchemaOverrides::new())
+}
+
+/// Parquet files carry their own schema, so no date sniffing is applied.
+#[tauri::command]
+pub async fn load_parquet(
+    path: String,
+    scan: Option<bool>,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    register_parquet(&state, path, scan.unwrap_or(false))
+}
+
+/// Adds an Arrow IPC file to the workspace, as `load_arrow` does.
+pub(crate) fn register_arrow(
+    state: &AppDataState,
+    path: String,
+    scan: bool,
+) -> Result<DatasetInfo, DataError> {
+    check_file(&path)?;
+
+    let table = if scan {
+        Table::scan(LazyFrame::scan_ipc(&path, ScanArgsIpc::default())?)
+    } else {
+        let file = fs::File::open(&path)?;
+        Table::Materialized(IpcReader::new(file).finish()?)
+    };
+
+    let loaded = LoadedTable::new(table);
+    let load = LoadOptions::Arrow { scan };
+    register_single_table(state, loaded, path, load, SchemaOverrides::new())
+}
+
+/// Loads an Arrow IPC (Feather v2) file with its schema intact.
+#[tauri::command]
+pub async fn load_arrow(
+    path: String,
+    scan: Option<bool>,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    register_arrow(&state, path, scan.unwrap_or(false))
 }
 
 #[tauri::command]
@@ -252,143 +868,633 @@ pub async fn list_excel_sheets(path: String) -> Result<Vec<String>, DataError> {
         return Err(DataError::FileNotFound(path));
     }
 
-    let workbook: Xlsx<_> = open_workbook(&path)?;
+    let workbook = excel::open(&path)?;
     let sheets = workbook.sheet_names().to_vec();
 
     Ok(sheets)
 }
 
+/// Names of the Excel Tables and defined names that `load_excel` can load
+/// through `ExcelImportOptions::table`.
+#[tauri::command]
+pub async fn list_excel_tables(path: String) -> Result<Vec<String>, DataError> {
+    check_file(&path)?;
+
+    let mut workbook = excel::open(&path)?;
+    excel::table_names(&mut workbook)
+}
+
+/// Reads and types the tables of a workbook, named by sheet or table. Sheets
+/// that could not be read are listed in the report of the first table.
+/// `on_sheet` is passed on to `excel::read_tables`.
+fn workbook_tables<RS: Read + Seek>(
+    workbook: &mut Sheets<RS>,
+    options: &ExcelImportOptions,
+    schema_overrides: &SchemaOverrides,
+    number_locale: Option<NumberLocale>,
+    on_sheet: impl FnMut(&str) -> Result<(), DataError>,
+) -> Result<Vec<(String, LoadedTable)>, DataError> {
+    if workbook.sheet_names().is_empty() {
+        return Err(DataError::NoData);
+    }
+    let (tables, skipped) = excel::read_tables(workbook, options, schema_overrides, on_sheet)?;
+
+    let mut loaded_tables = Vec::with_capacity(tables.len());
+    for (sheet_name, df) in tables {
+        // Try to detect and parse date columns
+        let df = try_parse_dates(df, schema_overrides);
+        let number_formats = numeric::detect_formats(&df, number_locale, schema_overrides);
+        let df = numeric::parse_numbers(df, &number_formats)?;
+        let df = overrides::apply(df, schema_overrides)?;
+
+        let loaded = LoadedTable {
+            number_formats,
+            ..LoadedTable::new(Table::Materialized(df))
+        };
+        loaded_tables.push((sheet_name, loaded));
+    }
+
+    report_skipped(&mut loaded_tables, skipped)?;
+    Ok(loaded_tables)
+}
+
+/// Adds tables that were left out of a load to the report of the first table,
+/// which is the report the frontend is shown.
+fn report_skipped(
+    loaded_tables: &mut [(String, LoadedTable)],
+    skipped: Vec<SkippedTable>,
+) -> Result<(), DataError> {
+    if skipped.is_empty() {
+        return Ok(());
+    }
+    if let Some((_, first)) = loaded_tables.first_mut() {
+        let report = match &mut first.report {
+            Some(report) => report,
+            None => first.report.insert(IngestReport {
+                total_rows: first.table.row_count()?,
+                ..Default::default()
+            }),
+        };
+        report.skipped_tables.extend(skipped);
+    }
+    Ok(())
+}
+
+/// Loads every sheet of a workbook (.xlsx, .xlsm, .xls, .xlsb or .ods), or
+/// the one sheet, cell range or named table `options` picks.
 #[tauri::command]
 pub async fn load_excel(
     path: String,
+    options: Option<ExcelImportOptions>,
+    schema_overrides: Option<SchemaOverrides>,
+    number_locale: Option<NumberLocale>,
+    app: AppHandle,
     state: State<'_, AppDataState>,
 ) -> Result<DatasetInfo, DataError> {
-    let file_path = Path::new(&path);
+    check_file(&path)?;
+    let options = options.unwrap_or_default();
+    let schema_overrides = schema_overrides.unwrap_or_default();
 
-    if !file_path.exists() {
-        return Err(DataError::FileNotFound(path));
+    let worker_path = path.clone();
+    let worker_options = options.clone();
+    let worker_overrides = schema_overrides.clone();
+    let loaded_tables = progress::run(&app, &path, move |progress| {
+        let mut workbook = excel::open(&worker_path)?;
+        workbook_tables(
+            &mut workbook,
+            &worker_options,
+            &worker_overrides,
+            number_locale,
+            |sheet| {
+                progress.sheet(sheet);
+                progress.check()
+            },
+        )
+    })
+    .await?;
+
+    let load = LoadOptions::Excel {
+        options,
+        number_locale,
+    };
+    register_tables(&state, loaded_tables, path, load, schema_overrides)
+}
+
+/// Tables and views in a SQLite database file.
+#[tauri::command]
+pub async fn list_sqlite_tables(path: String) -> Result<Vec<SqliteObject>, DataError> {
+    check_file(&path)?;
+
+    let conn = sqlite::open(&path)?;
+    sqlite::list_objects(&conn)
+}
+
+/// Reads the tables `load_sqlite` picks from a database. `on_table` is called
+/// with each table name before it is read, and can stop the read by returning
+/// an error.
+fn sqlite_tables(
+    path: &str,
+    tables: Option<Vec<String>>,
+    query: Option<String>,
+    mut on_table: impl FnMut(&str) -> Result<(), DataError>,
+) -> Result<Vec<(String, LoadedTable)>, DataError> {
+    let conn = sqlite::open(path)?;
+    let names = match (&query, tables) {
+        (Some(_), _) => vec!["query".to_string()],
+        (None, Some(tables)) => tables,
+        (None, None) => sqlite::list_objects(&conn)?
+            .into_iter()
+            .filter(|object| object.kind == "table")
+            .map(|object| object.name)
+            .collect(),
+    };
+
+    let schema_overrides = SchemaOverrides::new();
+    let mut loaded_tables = Vec::with_capacity(names.len());
+    for name in names {
+        on_table(&name)?;
+        let df = match &query {
+            Some(query) => sqlite::read_query(&conn, query)?,
+            None => sqlite::read_table(&conn, &name)?,
+        };
+        // SQLite has no date type, so dates are stored as text
+        let df = try_parse_dates(df, &schema_overrides);
+        loaded_tables.push((name, LoadedTable::new(Table::Materialized(df))));
     }
 
-    let metadata = fs::metadata(&path)?;
-    let file_size = metadata.len();
-    let file_name = file_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown")
-        .to_string();
+    Ok(loaded_tables)
+}
 
-    let mut workbook: Xlsx<_> = open_workbook(&path)?;
-    let sheets = workbook.sheet_names().to_vec();
+/// Loads tables from a SQLite database (.sqlite, .sqlite3 or .db). `tables`
+/// picks tables or views by name, all tables are loaded when it is omitted.
+/// A `query` loads the result of a custom SELECT as a table named `query`
+/// instead.
+#[tauri::command]
+pub async fn load_sqlite(
+    path: String,
+    tables: Option<Vec<String>>,
+    query: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    check_file(&path)?;
 
-    if sheets.is_empty() {
-        return Err(DataError::NoData);
+    let load = LoadOptions::Sqlite {
+        tables: tables.clone(),
+        query: query.clone(),
+    };
+
+    let worker_path = path.clone();
+    let loaded_tables = progress::run(&app, &path, move |progress| {
+        sqlite_tables(&worker_path, tables, query, |table| {
+            progress.sheet(table);
+            progress.check()
+        })
+    })
+    .await?;
+
+    register_tables(&state, loaded_tables, path, load, SchemaOverrides::new())
+}
+
+/// Reads one archive member with default options for its format. Spreadsheets
+/// give one table per sheet, named `member/sheet`.
+fn archive_member_tables(
+    member: archive::ArchiveMember,
+) -> Result<Vec<(String, LoadedTable)>, DataError> {
+    let schema_overrides = SchemaOverrides::new();
+    let table_name = member.table_name().to_string();
+
+    match archive::data_extension(&member.name).as_str() {
+        "csv" | "tsv" => {
+            let sample = &member.contents[..member.contents.len().min(sniff::SAMPLE_BYTES)];
+            let options = sniff::sniff_options(sample);
+            let loaded = read_csv(
+                &member.name,
+                Some(member.contents),
+                &options,
+                &schema_overrides,
+                None,
+            )?;
+            Ok(vec![(table_name, loaded)])
+        }
+        "json" => {
+            let options = JsonImportOptions {
+                flatten: true,
+                ..Default::default()
+            };
+            let df = json::read_records(Cursor::new(member.contents), &options)?;
+            let loaded = json_table(df, &options, &schema_overrides)?;
+            Ok(vec![(table_name, loaded)])
+        }
+        _ => {
+            let mut workbook = excel::open_bytes(member.contents)?;
+            let tables = workbook_tables(
+                &mut workbook,
+                &ExcelImportOptions::default(),
+                &schema_overrides,
+                None,
+                |_| Ok(()),
+            )?;
+            Ok(tables
+                .into_iter()
+                .map(|(sheet, loaded)| (format!("{}/{}", table_name, sheet), loaded))
+                .collect())
+        }
+    }
+}
+
+/// Loads every CSV, JSON and spreadsheet file in a .zip, .tar, .tar.gz or
+/// .tgz archive as a table of one source, named after its path inside the
+/// archive. Each file is read with sniffed or default options.
+#[tauri::command]
+pub async fn load_archive(
+    path: String,
+    app: AppHandle,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    check_file(&path)?;
+
+    let worker_path = path.clone();
+    let loaded_tables = progress::run(&app, &path, move |progress| {
+        let mut loaded_tables: Vec<(String, LoadedTable)> = Vec::new();
+        let mut skipped = Vec::new();
+        for member in archive::read_members(&worker_path)? {
+            let name = member.name.clone();
+            progress.sheet(&name);
+            progress.check()?;
+            let tables = archive_member_tables(member)
+                .map_err(|e| DataError::ParseError(format!("{}: {}", name, e)))?;
+            for (table_name, mut loaded) in tables {
+                if let Some(report) = &mut loaded.report {
+                    skipped.extend(report.skipped_tables.drain(..).map(|table| SkippedTable {
+                        name: format!("{}/{}", name, table.name),
+                        ..table
+                    }));
+                }
+                loaded_tables.push((table_name, loaded));
+            }
+        }
+        report_skipped(&mut loaded_tables, skipped)?;
+        Ok(loaded_tables)
+    })
+    .await?;
+
+    let load = LoadOptions::Archive;
+    register_tables(&state, loaded_tables, path, load, SchemaOverrides::new())
+}
+
+/// Whether a path is a glob pattern rather than a file or directory name.
+pub(crate) fn is_pattern(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+/// Extensions of the files `load_folder` picks up.
+const FOLDER_EXTENSIONS: [&str; 7] = ["csv", "tsv", "txt", "json", "parquet", "arrow", "feather"];
+
+/// Files matched by a glob pattern, or every supported file in a directory,
+/// sorted by path so that dated file names come out in order.
+fn matching_files(pattern: &str) -> Result<Vec<PathBuf>, DataError> {
+    let pattern = if Path::new(pattern).is_dir() {
+        Path::new(pattern).join("*").to_string_lossy().into_owned()
+    } else {
+        pattern.to_string()
+    };
+
+    let mut files: Vec<PathBuf> = glob::glob(&pattern)
+        .map_err(|e| DataError::ParseError(format!("Invalid pattern '{}': {}", pattern, e)))?
+        .filter_map(Result::ok)
+        .filter(|path| {
+            let extension = archive::data_extension(&path.to_string_lossy());
+            path.is_file() && FOLDER_EXTENSIONS.contains(&extension.as_str())
+        })
+        .collect();
+    files.sort();
+
+    if files.is_empty() {
+        return Err(DataError::FileNotFound(pattern));
+    }
+    Ok(files)
+}
+
+/// Reads one file of a folder into memory with sniffed or default options.
+fn read_folder_file(path: &str) -> Result<LoadedTable, DataError> {
+    let schema_overrides = SchemaOverrides::new();
+
+    match archive::data_extension(path).as_str() {
+        "json" => {
+            let options = JsonImportOptions {
+                flatten: true,
+                ..Default::default()
+            };
+            let df = match archive::read_decompressed(path)? {
+                Some(contents) => json::read_records(Cursor::new(contents), &options)?,
+                None => json::read_records(fs::File::open(path)?, &options)?,
+            };
+            json_table(df, &options, &schema_overrides)
+        }
+        "parquet" => {
+            let df = ParquetReader::new(fs::File::open(path)?).finish()?;
+            Ok(LoadedTable::new(Table::Materialized(df)))
+        }
+        "arrow" | "feather" => {
+            let df = IpcReader::new(fs::File::open(path)?).finish()?;
+            Ok(LoadedTable::new(Table::Materialized(df)))
+        }
+        _ => {
+            let options = sniff::sniff_options(&sniff::read_sample(path)?);
+            let contents = archive::read_decompressed(path)?;
+            read_csv(path, contents, &options, &schema_overrides, None)
+        }
+    }
+}
+
+/// Unions every file matched by a directory or glob pattern, such as
+/// `exports/2024-*.csv`, into one table. Columns are matched by name: a column
+/// missing from some files is null there, and differing types are promoted.
+/// A leading `__source_file` column records each row's file name, so files
+/// that have a column of that name are rejected.
+#[tauri::command]
+pub async fn load_folder(
+    path: String,
+    app: AppHandle,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    let files = matching_files(&path)?;
+
+    let loaded = progress::run(&app, &path, move |progress| {
+        let mut frames = Vec::with_capacity(files.len());
+        let mut number_formats = NumberFormats::new();
+        for file in &files {
+            let file_name = file
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            progress.sheet(&file_name);
+            progress.check()?;
+
+            let file_path = file.to_string_lossy();
+            let loaded = read_folder_file(&file_path)
+                .map_err(|e| DataError::ParseError(format!("{}: {}", file_path, e)))?;
+            for (column, format) in loaded.number_formats {
+                number_formats.entry(column).or_insert(format);
+            }
+
+            let df = loaded.table.lazy().collect()?;
+            frames.push(union::with_source_file(df, &file_name)?);
+        }
+
+        Ok(LoadedTable {
+            number_formats,
+            ..LoadedTable::new(Table::Materialized(union::union_frames(&frames)?))
+        })
+    })
+    .await?;
+
+    let load = LoadOptions::Folder;
+    register_single_table(&state, loaded, path, load, SchemaOverrides::new())
+}
+
+/// Reads a file with the loader and options in `load`, as the `load_*`
+/// command for that loader does.
+async fn load_with(
+    path: String,
+    load: LoadOptions,
+    schema_overrides: SchemaOverrides,
+    app: AppHandle,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    let overrides = Some(schema_overrides);
+    match load {
+        LoadOptions::Csv {
+            options,
+            scan,
+            number_locale,
+        } => {
+            load_csv(
+                path,
+                options,
+                Some(scan),
+                overrides,
+                number_locale,
+                app,
+                state,
+            )
+            .await
+        }
+        LoadOptions::FixedWidth {
+            options,
+            number_locale,
+        } => load_fixed_width(path, Some(options), overrides, number_locale, app, state).await,
+        LoadOptions::Excel {
+            options,
+            number_locale,
+        } => load_excel(path, Some(options), overrides, number_locale, app, state).await,
+        LoadOptions::Json { options } => {
+            load_json(path, Some(options), overrides, app, state).await
+        }
+        LoadOptions::Parquet { scan } => load_parquet(path, Some(scan), state).await,
+        LoadOptions::Arrow { scan } => load_arrow(path, Some(scan), state).await,
+        LoadOptions::Sqlite { tables, query } => load_sqlite(path, tables, query, app, state).await,
+        LoadOptions::Archive => load_archive(path, app, state).await,
+        LoadOptions::Folder => load_folder(path, app, state).await,
     }
+}
+
+/// Reads a source's file again with the options it was loaded with. The
+/// source keeps its name, and the active table stays selected if the file
+/// still has it.
+pub(crate) async fn reload_source(
+    name: &str,
+    app: AppHandle,
+    state: State<'_, AppDataState>,
+) -> Result<(), DataError> {
+    let (source, active_table) = {
+        let data_state = state
+            .lock()
+            .map_err(|e| DataError::ParseError(e.to_string()))?;
+        let source = data_state
+            .get_source(name)
+            .cloned()
+            .ok_or_else(|| DataError::ParseError(format!("Source '{}' not found", name)))?;
+        (source, data_state.active_table.clone())
+    };
+
+    load_with(
+        source.path,
+        source.load,
+        source.schema_overrides,
+        app,
+        state.clone(),
+    )
+    .await?;
 
     let mut data_state = state
         .lock()
         .map_err(|e| DataError::ParseError(e.to_string()))?;
-    
-    data_state.clear();
-    data_state.set_file_path(path.clone());
-
-    let mut first_valid_sheet: Option<(String, usize, Vec<ColumnInfo>)> = None;
-    let mut loaded_tables = Vec::new();
-
-    for sheet_name in sheets {
-        let range_result = workbook.worksheet_range(&sheet_name);
-        if let Ok(range) = range_result {
-            let mut headers: Vec<String> = Vec::new();
-            let mut data: Vec<Vec<CalamineData>> = Vec::new();
-
-            for (i, row) in range.rows().enumerate() {
-                if i == 0 {
-                    headers = row.iter().map(|c| c.to_string()).collect();
-                } else {
-                    data.push(row.to_vec());
-                }
-            }
+    if let Some(table) = active_table.filter(|t| data_state.get_table(t).is_some()) {
+        data_state
+            .set_active_table(table)
+            .map_err(DataError::ParseError)?;
+    }
+    Ok(())
+}
 
-            if !headers.is_empty() {
-                let mut columns_data: Vec<Series> = Vec::new();
-                for (idx, header) in headers.iter().enumerate() {
-                    let col_data: Vec<&CalamineData> = data.iter().map(|row| &row[idx]).collect();
-                    
-                    let mut has_string = false;
-                    let mut has_float = false;
-                    let mut has_bool = false;
-                    
-                    for val in &col_data {
-                        match val {
-                            CalamineData::String(_) => has_string = true,
-                            CalamineData::Float(_) | CalamineData::Int(_) => has_float = true,
-                            CalamineData::Bool(_) => has_bool = true,
-                            _ => {}
-                        }
-                    }
+/// Loads a project source with the loader and options it was saved with.
+#[tauri::command]
+pub async fn restore_source(
+    path: String,
+    load: LoadOptions,
+    schema_overrides: Option<SchemaOverrides>,
+    app: AppHandle,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    load_with(path, load, schema_overrides.unwrap_or_default(), app, state).await
+}
 
-                    let series = if has_string {
-                        let values: Vec<Option<String>> = col_data.iter().map(|v| match v {
-                            CalamineData::String(s) => Some(s.clone()),
-                            CalamineData::Int(i) => Some(i.to_string()),
-                            CalamineData::Float(f) => Some(f.to_string()),
-                            CalamineData::Bool(b) => Some(b.to_string()),
-                            CalamineData::DateTime(f) => Some(f.to_string()),
-                            _ => None,
-                        }).collect();
-                        Series::new(header.into(), values)
-                    } else if has_float {
-                        let values: Vec<Option<f64>> = col_data.iter().map(|v| match v {
-                            CalamineData::Int(i) => Some(*i as f64),
-                            CalamineData::Float(f) => Some(*f),
-                            CalamineData::DateTime(dt) => Some(dt.as_f64()),
-                            _ => None,
-                        }).collect();
-                        Series::new(header.into(), values)
-                    } else if has_bool {
-                        let values: Vec<Option<bool>> = col_data.iter().map(|v| match v {
-                            CalamineData::Bool(b) => Some(*b),
-                            _ => None,
-                        }).collect();
-                        Series::new(header.into(), values)
-                    } else {
-                        Series::new_null(header.into(), col_data.len())
-                    };
-
-                    columns_data.push(series);
-                }
+/// Parses rows appended to a source's file into the shape of its table, with
+/// the number and date formats detected when the file was loaded.
+fn parse_appended(
+    source: &DataSource,
+    bytes: Vec<u8>,
+    table: &DataFrame,
+    number_formats: &NumberFormats,
+    date_formats: &DateFormats,
+) -> Result<DataFrame, DataError> {
+    let schema_overrides = &source.schema_overrides;
 
-                if let Ok(df) = DataFrame::new(columns_data) {
-                    // Try to detect and parse date columns
-                    let df = try_parse_dates(df);
-                    
-                    let row_count = df.height();
-                    let columns = df_to_columns(&df);
-                    
-                    data_state.add_dataframe(sheet_name.clone(), df);
-                    loaded_tables.push(sheet_name.clone());
-
-                    if first_valid_sheet.is_none() {
-                        first_valid_sheet = Some((sheet_name.clone(), row_count, columns));
-                    }
-                }
+    match &source.load {
+        LoadOptions::Csv { options, .. } => {
+            let options = match options {
+                Some(options) => options.clone(),
+                None => sniff::sniff_options(&sniff::read_sample(&source.path)?),
+            };
+            let (bytes, options) = if text::is_native_delimiter(&options.delimiter) {
+                (bytes, options)
+            } else {
+                let appended = String::from_utf8_lossy(&bytes);
+                let no_header = CsvImportOptions {
+                    has_header: false,
+                    skip_rows: 0,
+                    ..options
+                };
+                text::delimited_to_csv(&appended, &no_header)?
+            };
+            let mut chunk = csv_read_options(&options)?
+                .with_has_header(false)
+                .with_skip_rows(0)
+                .with_infer_schema_length(Some(0))
+                .into_reader_with_file_handle(Cursor::new(bytes))
+                .finish()?;
+            if chunk.width() != table.width() {
+                return Err(DataError::ParseError(format!(
+                    "Appended rows have {} fields but the table has {} columns; reload the file instead",
+                    chunk.width(),
+                    table.width()
+                )));
             }
+            chunk.set_column_names(table.get_column_names_owned())?;
+
+            let chunk = parse_dates(chunk, date_formats);
+            let chunk = numeric::parse_numbers(chunk, number_formats)?;
+            Ok(overrides::apply(chunk, schema_overrides)?)
         }
+        LoadOptions::Json { options } => {
+            let chunk = json::normalize(json::read_lines(Cursor::new(bytes))?, options)?;
+            let chunk = parse_dates(chunk, date_formats);
+            Ok(overrides::apply(chunk, schema_overrides)?)
+        }
+        _ => Err(DataError::UnsupportedFormat(
+            "Only CSV and NDJSON sources can be appended to".to_string(),
+        )),
     }
+}
 
-    if let Some((first_sheet_name, row_count, columns)) = first_valid_sheet {
-         data_state.set_active_table(first_sheet_name).map_err(|e| DataError::ParseError(e))?;
+/// Appends new rows to a source's table, as `append_source` does.
+pub(crate) fn append_rows(state: &AppDataState, name: &str) -> Result<DatasetInfo, DataError> {
+    let (source, table_name, version, table, number_formats, date_formats) = {
+        let data_state = state
+            .lock()
+            .map_err(|e| DataError::ParseError(e.to_string()))?;
+        let source = data_state
+            .get_source(name)
+            .cloned()
+            .ok_or_else(|| DataError::ParseError(format!("Source '{}' not found", name)))?;
+        let table_name = source.tables[0].clone();
+        let table = match data_state.get_table(&table_name).map(Table::base) {
+            Some(Table::Materialized(df)) if source.read_offset.is_some() => df.clone(),
+            _ => {
+                return Err(DataError::UnsupportedFormat(format!(
+                    "'{}' is not a plain CSV or NDJSON file loaded into memory",
+                    name
+                )))
+            }
+        };
+        let number_formats = data_state
+            .get_number_formats(&table_name)
+            .cloned()
+            .unwrap_or_default();
+        let date_formats = data_state
+            .get_date_formats(&table_name)
+            .cloned()
+            .unwrap_or_default();
+        let version = data_state.table_version(&table_name);
+        (
+            source,
+            table_name,
+            version,
+            table,
+            number_formats,
+            date_formats,
+        )
+    };
 
-         Ok(DatasetInfo {
-            file_name,
-            file_path: path,
-            file_size,
-            row_count,
-            columns,
-            tables: loaded_tables,
-        })
-    } else {
-        Err(DataError::NoData)
+    let offset = source.read_offset.unwrap_or_default();
+    let appended = match tail::read_appended(&source.path, offset)? {
+        Some((bytes, new_offset)) => {
+            let chunk = parse_appended(&source, bytes, &table, &number_formats, &date_formats)?;
+            // The last row was read from an unfinished line, which is now part
+            // of the chunk.
+            let table = if source.partial_row {
+                table.slice(0, table.height().saturating_sub(1))
+            } else {
+                table
+            };
+            Some((tail::append(table, chunk)?, new_offset))
+        }
+        None => None,
+    };
+
+    let mut data_state = state
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+    // A reload or another append while the rows were read replaced the table,
+    // so these rows are dropped. The offset is kept, and the next append reads
+    // any rows the table still lacks.
+    let unchanged = data_state.table_version(&table_name) == version
+        && data_state
+            .get_source(name)
+            .is_some_and(|s| s.read_offset == source.read_offset);
+    if let Some((df, new_offset)) = appended.filter(|_| unchanged) {
+        data_state
+            .replace_table(&table_name, Table::Materialized(df))
+            .map_err(DataError::ParseError)?;
+        data_state.set_read_offset(name, Some(new_offset), false);
     }
+    data_state
+        .set_active_table(table_name)
+        .map_err(DataError::ParseError)?;
+
+    dataset_info(&data_state, None)
+}
+
+/// Adds the rows appended to a CSV or NDJSON source's file since it was last
+/// read to its table, and makes that table active.
+#[tauri::command]
+pub async fn append_source(
+    name: String,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    append_rows(&state, &name)
 }
 
 #[tauri::command]
@@ -400,36 +1506,92 @@ pub async fn set_active_table(
         .lock()
         .map_err(|e| DataError::ParseError(e.to_string()))?;
 
-    data_state.set_active_table(table_name.clone()).map_err(|e| DataError::ParseError(e))?;
-    
-    let df = data_state.get_active_dataframe().ok_or(DataError::NoData)?;
-    let row_count = df.height();
-    let columns = df_to_columns(df);
-    let path = data_state.get_file_path().cloned().unwrap_or_default();
-    
-    // Recalculate basic info
-    let file_path = Path::new(&path);
-    let file_name = file_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown")
-        .to_string();
-    
-    // We assume file size is same for now, or fetch it again
-    let file_size = if file_path.exists() {
-        fs::metadata(&path)?.len()
+    data_state
+        .set_active_table(table_name)
+        .map_err(DataError::ParseError)?;
+    dataset_info(&data_state, None)
+}
+
+#[tauri::command]
+pub async fn list_sources(state: State<'_, AppDataState>) -> Result<Vec<SourceInfo>, DataError> {
+    let data_state = state
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+
+    Ok(data_state.sources.iter().map(|s| s.info()).collect())
+}
+
+/// Loads a file, folder or glob pattern with the loader for its extension and
+/// default options.
+#[tauri::command]
+pub async fn add_source(
+    path: String,
+    app: AppHandle,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    if archive::is_archive(&path) {
+        return load_archive(path, app, state).await;
+    }
+    if Path::new(&path).is_dir() || is_pattern(&path) {
+        return load_folder(path, app, state).await;
+    }
+    let extension = archive::data_extension(&path);
+
+    match extension.as_str() {
+        "csv" | "tsv" | "txt" => load_csv(path, None, None, None, None, app, state).await,
+        "fwf" | "prn" => load_fixed_width(path, None, None, None, app, state).await,
+        "xlsx" | "xlsm" | "xls" | "xlsb" | "ods" => {
+            load_excel(path, None, None, None, app, state).await
+        }
+        "json" | "ndjson" | "jsonl" => load_json(path, None, None, app, state).await,
+        "parquet" => load_parquet(path, None, state).await,
+        "arrow" | "feather" | "ipc" => load_arrow(path, None, state).await,
+        "sqlite" | "sqlite3" | "db" => load_sqlite(path, None, None, app, state).await,
+        _ => Err(DataError::UnsupportedFormat(extension)),
+    }
+}
+
+/// Removes a source and its tables from the workspace, and stops watching its
+/// file. Returns the new active table's info, or `None` when the workspace is
+/// now empty.
+#[tauri::command]
+pub async fn remove_source(
+    name: String,
+    state: State<'_, AppDataState>,
+    watch: State<'_, AppWatchState>,
+) -> Result<Option<DatasetInfo>, DataError> {
+    let mut data_state = state
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+
+    let removed = data_state
+        .remove_source(&name)
+        .map_err(DataError::ParseError)?;
+    if !data_state.sources.iter().any(|s| s.path == removed.path) {
+        watch::stop_watching(&watch, &removed.path)?;
+    }
+    if data_state.has_data() {
+        dataset_info(&data_state, None).map(Some)
     } else {
-        0
-    };
+        Ok(None)
+    }
+}
 
-    Ok(DatasetInfo {
-        file_name,
-        file_path: path,
-        file_size,
-        row_count,
-        columns,
-        tables: data_state.get_tables(),
-    })
+/// Renames a source. Its tables are renamed to match.
+#[tauri::command]
+pub async fn rename_source(
+    name: String,
+    new_name: String,
+    state: State<'_, AppDataState>,
+) -> Result<SourceInfo, DataError> {
+    let mut data_state = state
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+
+    let source = data_state
+        .rename_source(&name, &new_name)
+        .map_err(DataError::ParseError)?;
+    Ok(source.info())
 }
 
 #[tauri::command]
@@ -442,9 +1604,13 @@ pub async fn get_data_page(
         .lock()
         .map_err(|e| DataError::ParseError(e.to_string()))?;
 
-    let df = data_state.get_active_dataframe().ok_or(DataError::NoData)?;
-    let total_rows = df.height();
-    let rows = df_to_rows(df, offset, limit);
+    let table = data_state.get_active_table().ok_or(DataError::NoData)?;
+    let total_rows = table.row_count()?;
+    let page = table
+        .lazy()
+        .slice(offset as i64, limit as IdxSize)
+        .collect()?;
+    let rows = df_to_rows(&page);
 
     Ok(DataPage {
         rows,
@@ -455,10 +1621,16 @@ pub async fn get_data_page(
 }
 
 #[tauri::command]
-pub async fn clear_data(state: State<'_, AppDataState>) -> Result<(), DataError> {
+pub async fn clear_data(
+    state: State<'_, AppDataState>,
+    watch: State<'_, AppWatchState>,
+) -> Result<(), DataError> {
     let mut data_state = state
         .lock()
         .map_err(|e| DataError::ParseError(e.to_string()))?;
+    for source in &data_state.sources {
+        watch::stop_watching(&watch, &source.path)?;
+    }
     data_state.clear();
     Ok(())
-}
\ No newline at end of file
+}
@@ -88,4 +88,233 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_load_parquet_registers_typed_table() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("people.parquet");
+        let mut df = df! {
+            "name" => ["Alice", "Bob"],
+            "age" => [30i32, 25],
+            "joined" => ["2024-01-15", "2023-06-01"],
+        }
+        .unwrap()
+        .lazy()
+        .with_column(col("joined").cast(DataType::Date))
+        .collect()
+        .unwrap();
+
+        ParquetWriter::new(fs::File::create(&path).unwrap())
+            .finish(&mut df)
+            .unwrap();
+
+        let state = state::AppDataState::default();
+        let path = path.to_str().unwrap().to_string();
+        let info = ingest::register_parquet(&state, path, false).unwrap();
+
+        assert_eq!(info.source, "people");
+        assert_eq!(info.active_table, "people");
+        assert_eq!(info.file_name, "people.parquet");
+        assert_eq!(info.row_count, 2);
+        let dtypes: Vec<types::LogicalType> = info.columns.iter().map(|c| c.dtype).collect();
+        assert_eq!(
+            dtypes,
+            [
+                types::LogicalType::String,
+                types::LogicalType::Integer,
+                types::LogicalType::Date
+            ]
+        );
+
+        let data_state = state.lock().unwrap();
+        let table = data_state.get_table("people").unwrap();
+        assert!(!table.is_scan());
+        assert_eq!(table.schema().unwrap().get("age"), Some(&DataType::Int32));
+        assert_eq!(data_state.sources[0].format, types::SourceFormat::Parquet);
+    }
+
+    #[test]
+    fn test_load_arrow_as_scan() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("products.arrow");
+        let mut df = df! {
+            "product" => ["Widget", "Gadget", "Widget"],
+            "price" => [50.0, 75.0, 50.0],
+        }
+        .unwrap();
+
+        IpcWriter::new(fs::File::create(&path).unwrap())
+            .finish(&mut df)
+            .unwrap();
+
+        let state = state::AppDataState::default();
+        let path = path.to_str().unwrap().to_string();
+        let info = ingest::register_arrow(&state, path.clone(), true).unwrap();
+
+        assert_eq!(info.active_table, "products");
+        assert_eq!(info.row_count, 3);
+        assert!(info.columns.iter().all(|c| c.nullable));
+
+        let data_state = state.lock().unwrap();
+        let table = data_state.get_table("products").unwrap();
+        assert!(table.is_scan());
+        assert!(table.collect().unwrap().equals(&df));
+        assert_eq!(data_state.sources[0].format, types::SourceFormat::Arrow);
+
+        // Loading the same path again replaces the source.
+        drop(data_state);
+        ingest::register_arrow(&state, path, false).unwrap();
+        let data_state = state.lock().unwrap();
+        assert_eq!(data_state.sources.len(), 1);
+        assert!(!data_state.get_table("products").unwrap().is_scan());
+    }
+
+    #[test]
+    fn test_csv_scan_table() {
+        let (_dir, path) = create_test_csv();
+        let lf = LazyCsvReader::new(&path).with_has_header(true).finish().unwrap();
+        let table = state::Table::scan(lf);
+
+        assert!(table.is_scan());
+        assert_eq!(table.row_count().unwrap(), 3);
+        assert_eq!(table.schema().unwrap().len(), 3);
+
+        let high_earners = state::count_rows(
+            table.lazy().filter(col("salary").gt(lit(70000))),
+        )
+        .unwrap();
+        assert_eq!(high_earners, 2);
+    }
+
+    #[test]
+    fn test_load_csv_with_scan_counts_rows_once() {
+        let (_dir, path) = create_test_csv();
+        let schema_overrides = types::SchemaOverrides::new();
+        let progress = progress::Progress::new(None, &path);
+        let loaded =
+            ingest::read_csv_file(&path, None, true, &schema_overrides, None, &progress).unwrap();
+        let load = types::LoadOptions::Csv {
+            options: None,
+            scan: true,
+            number_locale: None,
+        };
+
+        let state = state::AppDataState::default();
+        let info =
+            ingest::register_single_table(&state, loaded, path.clone(), load, schema_overrides)
+                .unwrap();
+        assert_eq!(info.active_table, "test");
+        assert_eq!(info.row_count, 3);
+        assert_eq!(info.columns.len(), 3);
+
+        // The count is kept until the source is loaded again.
+        fs::write(&path, "name,age,salary\nAlice,30,75000\nBob,25,65000\n").unwrap();
+        let data_state = state.lock().unwrap();
+        let table = data_state.get_table("test").unwrap();
+        assert!(table.is_scan());
+        assert_eq!(table.clone().row_count().unwrap(), 3);
+        assert_eq!(state::count_rows(table.lazy()).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_append_rereads_unfinished_line_with_load_date_formats() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("visits.csv");
+        let path = path.to_str().unwrap().to_string();
+        // Day-first dates; the last line is still being written.
+        fs::write(&path, "day,visits\n13/01/2024,10\n02/03/2024,2").unwrap();
+
+        let schema_overrides = types::SchemaOverrides::new();
+        let progress = progress::Progress::new(None, &path);
+        let loaded =
+            ingest::read_csv_file(&path, None, false, &schema_overrides, None, &progress).unwrap();
+        let load = types::LoadOptions::Csv {
+            options: None,
+            scan: false,
+            number_locale: None,
+        };
+        let state = state::AppDataState::default();
+        ingest::register_single_table(&state, loaded, path.clone(), load, schema_overrides)
+            .unwrap();
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        std::io::Write::write_all(&mut file, b"0\n").unwrap();
+        let info = ingest::append_rows(&state, "visits").unwrap();
+        assert_eq!(info.row_count, 2);
+
+        let data_state = state.lock().unwrap();
+        let df = data_state.get_table("visits").unwrap().collect().unwrap();
+        let visits: Vec<Option<i64>> = df
+            .column("visits")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(visits, [Some(10), Some(20)]);
+        // Read alone, "02/03/2024" would be taken as month first.
+        let day = df.column("day").unwrap().cast(&DataType::String).unwrap();
+        assert_eq!(day.str().unwrap().get(1), Some("2024-03-02"));
+    }
+
+    #[test]
+    fn test_load_csv_reports_values_lost_to_overrides() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("orders.csv");
+        let path = path.to_str().unwrap().to_string();
+        fs::write(
+            &path,
+            "id,amount,note\n1,10,ok\n\n2,n/a,\"late,\nrefunded\"\n3,,\n4,40,ok\n",
+        )
+        .unwrap();
+
+        let schema_overrides =
+            types::SchemaOverrides::from([("amount".to_string(), types::ColumnType::Integer)]);
+        let progress = progress::Progress::new(None, &path);
+        let loaded =
+            ingest::read_csv_file(&path, None, false, &schema_overrides, None, &progress).unwrap();
+        let load = types::LoadOptions::Csv {
+            options: None,
+            scan: false,
+            number_locale: None,
+        };
+        let state = state::AppDataState::default();
+        let info = ingest::register_single_table(&state, loaded, path, load, schema_overrides)
+            .unwrap();
+
+        // The blank line is read as a row of nulls, and the quoted note spans
+        // two lines; rows read again as text must still line up.
+        let report = info.report.unwrap();
+        assert_eq!(report.total_rows, 5);
+        assert_eq!(report.nulls_introduced, 1);
+        let issue = &report.column_issues[0];
+        assert_eq!(issue.column, "amount");
+        assert_eq!(issue.samples[0].row, 3);
+        assert_eq!(issue.samples[0].value, "n/a");
+        assert!((issue.failed_ratio - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_csv_schema_overrides_keep_leading_zeros() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("customers.csv");
+        fs::write(&path, "zip,product_id,category\n01234,007,1\n90210,120,2\n").unwrap();
+
+        let schema_overrides = types::SchemaOverrides::from([
+            ("zip".to_string(), types::ColumnType::String),
+            ("category".to_string(), types::ColumnType::Categorical),
+        ]);
+        let df = CsvReadOptions::default()
+            .with_schema_overwrite(Some(Arc::new(overrides::text_schema(&schema_overrides))))
+            .try_into_reader_with_file_path(Some(path))
+            .unwrap()
+            .finish()
+            .unwrap();
+        let df = overrides::apply(df, &schema_overrides).unwrap();
+
+        let zip = df.column("zip").unwrap();
+        assert_eq!(zip.str().unwrap().get(0), Some("01234"));
+        assert_eq!(df.column("product_id").unwrap().dtype(), &DataType::Int64);
+        assert_eq!(df.column("category").unwrap().dtype(), &DataType::String);
+    }
 }
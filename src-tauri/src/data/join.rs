@@ -0,0 +1,307 @@
+//! # Table Joins
+//!
+//! Joins two workspace tables on pairs of key columns and registers the
+//! result as a new table. Alongside the result, match statistics show rows
+//! that found no partner and keys repeated on either side, which is how a
+//! join that silently drops or duplicates rows gets noticed.
+
+use crate::data::ingest::dataset_info;
+use crate::data::state::{count_rows, AppDataState, Table};
+use crate::data::types::{Derivation, JoinKey, JoinKind, JoinStats, JoinedTable};
+use crate::data::union;
+use crate::error::DataError;
+use polars::prelude::*;
+use tauri::State;
+
+/// Suffix added to right columns whose names are taken on the left.
+pub const RIGHT_SUFFIX: &str = "_right";
+
+fn join_type(kind: JoinKind) -> JoinType {
+    match kind {
+        JoinKind::Inner => JoinType::Inner,
+        JoinKind::Left => JoinType::Left,
+        JoinKind::Right => JoinType::Right,
+        JoinKind::Outer => JoinType::Full,
+        JoinKind::Semi => JoinType::Semi,
+        JoinKind::Anti => JoinType::Anti,
+    }
+}
+
+/// Key expressions for both sides, cast to a common type where the key
+/// columns differ, such as integer ids matched against text ids.
+fn key_exprs(
+    left: &Schema,
+    right: &Schema,
+    keys: &[JoinKey],
+) -> Result<(Vec<Expr>, Vec<Expr>), DataError> {
+    if keys.is_empty() {
+        return Err(DataError::ParseError(
+            "Choose at least one pair of key columns".to_string(),
+        ));
+    }
+
+    let mut left_on = Vec::with_capacity(keys.len());
+    let mut right_on = Vec::with_capacity(keys.len());
+    for key in keys {
+        let dtype = |schema: &Schema, name: &str, side: &str| {
+            schema.get(name).cloned().ok_or_else(|| {
+                DataError::ParseError(format!("Column '{}' not found in {} table", name, side))
+            })
+        };
+        let left_dtype = dtype(left, &key.left, "left")?;
+        let right_dtype = dtype(right, &key.right, "right")?;
+
+        if left_dtype == right_dtype {
+            left_on.push(col(&key.left));
+            right_on.push(col(&key.right));
+        } else {
+            let common = union::promote(&left_dtype, &right_dtype);
+            left_on.push(col(&key.left).cast(common.clone()));
+            right_on.push(col(&key.right).cast(common));
+        }
+    }
+    Ok((left_on, right_on))
+}
+
+fn join_args(kind: JoinKind) -> JoinArgs {
+    let args = JoinArgs::new(join_type(kind)).with_suffix(Some(RIGHT_SUFFIX.into()));
+    match kind {
+        // Keep a single key column rather than one per side.
+        JoinKind::Outer => args.with_coalesce(JoinCoalesce::CoalesceColumns),
+        _ => args,
+    }
+}
+
+/// Row count of each distinct key, ignoring rows with a null key part.
+fn key_counts(lf: LazyFrame, keys: &[Expr]) -> LazyFrame {
+    let not_null = keys
+        .iter()
+        .map(|key| key.clone().is_not_null())
+        .reduce(|a, b| a.and(b))
+        .unwrap_or(lit(true));
+    let names: Vec<Expr> = (0..keys.len())
+        .map(|i| col(format!("__key_{}", i)))
+        .collect();
+    let aliased: Vec<Expr> = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| key.clone().alias(format!("__key_{}", i)))
+        .collect();
+
+    lf.filter(not_null)
+        .select(aliased)
+        .group_by(names)
+        .agg([len().alias("__rows")])
+}
+
+fn match_stats(
+    left: LazyFrame,
+    right: LazyFrame,
+    left_on: &[Expr],
+    right_on: &[Expr],
+) -> PolarsResult<JoinStats> {
+    let unmatched = |from: &LazyFrame, other: &LazyFrame, on: &[Expr], other_on: &[Expr]| {
+        count_rows(
+            from.clone()
+                .join(other.clone(), on, other_on, JoinArgs::new(JoinType::Anti)),
+        )
+    };
+    let duplicates = |counts: LazyFrame| count_rows(counts.filter(col("__rows").gt(lit(1))));
+
+    let left_counts = key_counts(left.clone(), left_on);
+    let right_counts = key_counts(right.clone(), right_on);
+    let key_names: Vec<Expr> = (0..left_on.len())
+        .map(|i| col(format!("__key_{}", i)))
+        .collect();
+    let fan_out = right_counts
+        .clone()
+        .join(
+            left_counts.clone(),
+            &key_names,
+            &key_names,
+            JoinArgs::new(JoinType::Semi),
+        )
+        .select([col("__rows").max()])
+        .collect()?;
+    let max_fan_out = fan_out.get_columns()[0]
+        .cast(&DataType::UInt64)?
+        .u64()?
+        .get(0)
+        .unwrap_or(0) as usize;
+
+    Ok(JoinStats {
+        left_rows: count_rows(left.clone())?,
+        right_rows: count_rows(right.clone())?,
+        result_rows: 0,
+        unmatched_left: unmatched(&left, &right, left_on, right_on)?,
+        unmatched_right: unmatched(&right, &left, right_on, left_on)?,
+        duplicate_left_keys: duplicates(left_counts)?,
+        duplicate_right_keys: duplicates(right_counts)?,
+        max_fan_out,
+    })
+}
+
+/// Joins two tables and reports how well their keys matched.
+pub fn join(
+    left: &Table,
+    right: &Table,
+    keys: &[JoinKey],
+    kind: JoinKind,
+) -> Result<(DataFrame, JoinStats), DataError> {
+    let (left_schema, right_schema) = (left.schema()?, right.schema()?);
+    let (left_on, right_on) = key_exprs(&left_schema, &right_schema, keys)?;
+
+    let df = left
+        .lazy()
+        .join(right.lazy(), &left_on, &right_on, join_args(kind))
+        .collect()?;
+    let stats = JoinStats {
+        result_rows: df.height(),
+        ..match_stats(left.lazy(), right.lazy(), &left_on, &right_on)?
+    };
+
+    Ok((df, stats))
+}
+
+/// Joins table `left` to table `right` on pairs of key columns and registers
+/// the result as table `name`, which becomes the active table. Key columns of
+/// different types are compared as a common type. Right columns whose names
+/// are taken on the left get a `_right` suffix.
+#[tauri::command]
+pub async fn join_tables(
+    left: String,
+    right: String,
+    keys: Vec<JoinKey>,
+    kind: JoinKind,
+    name: String,
+    state: State<'_, AppDataState>,
+) -> Result<JoinedTable, DataError> {
+    let (left_table, right_table) = {
+        let data_state = state
+            .lock()
+            .map_err(|e| DataError::ParseError(e.to_string()))?;
+        let table = |name: &str| {
+            data_state
+                .get_table(name)
+                .cloned()
+                .ok_or_else(|| DataError::ParseError(format!("Table '{}' not found", name)))
+        };
+        (table(&left)?, table(&right)?)
+    };
+
+    let (df, stats) = join(&left_table, &right_table, &keys, kind)?;
+    let derivation = Derivation::Join {
+        left,
+        right,
+        keys,
+        kind,
+    };
+
+    let mut data_state = state
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+    data_state
+        .add_derived_table(&name, Table::Materialized(df), derivation)
+        .map_err(DataError::ParseError)?;
+    data_state
+        .set_active_table(name.trim().to_string())
+        .map_err(DataError::ParseError)?;
+
+    Ok(JoinedTable {
+        dataset: dataset_info(&data_state, None)?,
+        stats,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(left: &str, right: &str) -> Vec<JoinKey> {
+        vec![JoinKey {
+            left: left.to_string(),
+            right: right.to_string(),
+        }]
+    }
+
+    fn orders() -> Table {
+        Table::Materialized(
+            df!(
+                "order_id" => [1, 2, 3, 4],
+                "customer" => [Some(10i64), Some(10), Some(20), None],
+                "amount" => [5.0, 7.5, 3.0, 1.0],
+            )
+            .unwrap(),
+        )
+    }
+
+    fn customers() -> Table {
+        Table::Materialized(
+            df!(
+                "id" => ["10", "30", "30"],
+                "amount" => [100, 200, 300],
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_left_join_reports_unmatched_and_duplicate_keys() {
+        let (df, stats) = join(
+            &orders(),
+            &customers(),
+            &key("customer", "id"),
+            JoinKind::Left,
+        )
+        .unwrap();
+
+        assert_eq!(df.height(), 4);
+        assert!(df.column("amount_right").is_ok());
+        assert_eq!(
+            stats,
+            JoinStats {
+                left_rows: 4,
+                right_rows: 3,
+                result_rows: 4,
+                unmatched_left: 2,
+                unmatched_right: 2,
+                duplicate_left_keys: 1,
+                duplicate_right_keys: 1,
+                max_fan_out: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_semi_and_anti_joins_keep_left_columns() {
+        let (matched, _) = join(
+            &orders(),
+            &customers(),
+            &key("customer", "id"),
+            JoinKind::Semi,
+        )
+        .unwrap();
+        let (unmatched, _) = join(
+            &orders(),
+            &customers(),
+            &key("customer", "id"),
+            JoinKind::Anti,
+        )
+        .unwrap();
+
+        assert_eq!(matched.height(), 2);
+        assert_eq!(unmatched.height(), 2);
+        assert_eq!(matched.width(), 3);
+    }
+
+    #[test]
+    fn test_missing_key_column_is_an_error() {
+        let err = join(
+            &orders(),
+            &customers(),
+            &key("client", "id"),
+            JoinKind::Inner,
+        );
+        assert!(err.is_err());
+    }
+}
@@ -0,0 +1,178 @@
+//! # JSON Reading
+//!
+//! Reads JSON record arrays, optionally picked out of an API response
+//! envelope with a JSON pointer, and flattens nested objects and arrays into
+//! plain columns that can be charted.
+
+use crate::data::archive;
+use crate::data::types::JsonImportOptions;
+use crate::error::DataError;
+use polars::io::mmap::MmapBytesReader;
+use polars::prelude::*;
+use std::io::Cursor;
+
+/// Reads the records of a JSON document from a file or an in-memory buffer.
+/// With a `record_path` the whole document is parsed and only the array at
+/// that pointer is handed to Polars.
+pub fn read_records<R: MmapBytesReader>(
+    reader: R,
+    options: &JsonImportOptions,
+) -> Result<DataFrame, DataError> {
+    let Some(pointer) = options.record_path.as_deref().filter(|p| !p.is_empty()) else {
+        return Ok(JsonReader::new(reader).finish()?);
+    };
+
+    let document: serde_json::Value =
+        serde_json::from_reader(reader).map_err(|e| DataError::ParseError(e.to_string()))?;
+    let records = match document.pointer(pointer) {
+        Some(serde_json::Value::Array(records)) => serde_json::Value::Array(records.clone()),
+        Some(record @ serde_json::Value::Object(_)) => {
+            serde_json::Value::Array(vec![record.clone()])
+        }
+        Some(_) => {
+            return Err(DataError::ParseError(format!(
+                "'{}' does not point to an array of records",
+                pointer
+            )))
+        }
+        None => {
+            return Err(DataError::ParseError(format!(
+                "Nothing found at JSON path '{}'",
+                pointer
+            )))
+        }
+    };
+
+    let bytes = serde_json::to_vec(&records).map_err(|e| DataError::ParseError(e.to_string()))?;
+    Ok(JsonReader::new(Cursor::new(bytes)).finish()?)
+}
+
+/// Whether a file holds one JSON record per line, judged by its extension.
+pub fn is_json_lines(path: &str) -> bool {
+    matches!(archive::data_extension(path).as_str(), "ndjson" | "jsonl")
+}
+
+/// Reads newline-delimited JSON, one record per line.
+pub fn read_lines<R: MmapBytesReader>(reader: R) -> Result<DataFrame, DataError> {
+    Ok(JsonReader::new(reader)
+        .with_json_format(JsonFormat::JsonLines)
+        .finish()?)
+}
+
+/// Replaces each struct column with one column per field, named
+/// `parent.field`, keeping the column order.
+fn unnest_structs(df: &DataFrame) -> PolarsResult<DataFrame> {
+    let mut columns = Vec::with_capacity(df.width());
+
+    for column in df.get_columns() {
+        if matches!(column.dtype(), DataType::Struct(_)) {
+            for field in column.struct_()?.fields_as_series() {
+                let name = format!("{}.{}", column.name(), field.name());
+                columns.push(field.with_name(name.into()));
+            }
+        } else {
+            columns.push(column.clone());
+        }
+    }
+
+    DataFrame::new(columns)
+}
+
+/// Explodes the array columns named in `options.explode` into one row per
+/// element and, in flatten mode, unnests objects into dotted column names.
+/// Exploded arrays of objects are unnested too, so exploding `items` yields
+/// `items.sku`, `items.qty` and so on.
+pub fn normalize(mut df: DataFrame, options: &JsonImportOptions) -> PolarsResult<DataFrame> {
+    loop {
+        let explodable: Vec<String> = df
+            .get_columns()
+            .iter()
+            .filter(|c| matches!(c.dtype(), DataType::List(_)))
+            .map(|c| c.name().to_string())
+            .filter(|name| options.explode.contains(name))
+            .collect();
+        // One at a time, so arrays of different lengths multiply out rather
+        // than having to line up.
+        for name in &explodable {
+            df = df.explode([name.as_str()])?;
+        }
+
+        let has_structs = df
+            .get_columns()
+            .iter()
+            .any(|c| matches!(c.dtype(), DataType::Struct(_)));
+        if options.flatten && has_structs {
+            df = unnest_structs(&df)?;
+        } else if explodable.is_empty() {
+            return Ok(df);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write_json(content: &str) -> (TempDir, String) {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("response.json");
+        fs::write(&path, content).unwrap();
+        (dir, path.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn test_record_path_selects_nested_array() {
+        let (_dir, path) =
+            write_json(r#"{"status": "ok", "data": {"items": [{"id": 1}, {"id": 2}, {"id": 3}]}}"#);
+        let options = JsonImportOptions {
+            record_path: Some("/data/items".to_string()),
+            ..Default::default()
+        };
+
+        let df = read_records(fs::File::open(&path).unwrap(), &options).unwrap();
+        assert_eq!(df.height(), 3);
+        assert_eq!(df.get_column_names(), ["id"]);
+
+        let options = JsonImportOptions {
+            record_path: Some("/status".to_string()),
+            ..Default::default()
+        };
+        assert!(read_records(fs::File::open(&path).unwrap(), &options).is_err());
+    }
+
+    #[test]
+    fn test_flatten_unnests_objects_and_explodes_arrays() {
+        let (_dir, path) = write_json(
+            r#"[
+                {"order": 1, "customer": {"name": "Ana", "address": {"city": "Lisbon"}},
+                 "items": [{"sku": "A", "qty": 2}, {"sku": "B", "qty": 1}]},
+                {"order": 2, "customer": {"name": "Ben", "address": {"city": "Porto"}},
+                 "items": [{"sku": "C", "qty": 5}]}
+            ]"#,
+        );
+        let options = JsonImportOptions {
+            flatten: true,
+            explode: vec!["items".to_string()],
+            ..Default::default()
+        };
+
+        let df = read_records(fs::File::open(&path).unwrap(), &options).unwrap();
+        let df = normalize(df, &options).unwrap();
+
+        assert_eq!(df.height(), 3);
+        assert_eq!(
+            df.get_column_names(),
+            [
+                "order",
+                "customer.name",
+                "customer.address.city",
+                "items.sku",
+                "items.qty"
+            ]
+        );
+        let cities = df.column("customer.address.city").unwrap();
+        assert_eq!(cities.str().unwrap().get(2), Some("Porto"));
+    }
+}
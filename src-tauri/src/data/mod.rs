@@ -1,10 +1,29 @@
+pub mod archive;
+pub mod columns;
+pub mod excel;
+pub mod formula;
 pub mod ingest;
+pub mod join;
+pub mod json;
+pub mod numeric;
+pub mod overrides;
 pub mod planner;
+pub mod profile;
+pub mod progress;
 pub mod query;
+pub mod report;
 pub mod safety;
 pub mod sampling;
+pub mod semantic;
+pub mod sniff;
+pub mod sqlite;
 pub mod state;
+pub mod tail;
+pub mod text;
+pub mod transform;
 pub mod types;
+pub mod union;
+pub mod watch;
 
 pub use query::{execute_visualization_query, execute_scatter_query, execute_table_query, execute_progressive_query};
 
@@ -0,0 +1,318 @@
+//! # Formatted Number Parsing
+//!
+//! Finds text columns holding formatted numbers such as `$1,234.50`, `12.5%`,
+//! `(300)` or `1.234,56` and converts them to floats. The original format is
+//! kept so exports can write the values back the way they were read.
+
+use crate::data::types::{NumberFormat, NumberFormats, NumberLocale, SchemaOverrides};
+use polars::prelude::*;
+
+pub(crate) const CURRENCY_SYMBOLS: [char; 7] = ['$', '€', '£', '¥', '₹', '₩', '₽'];
+
+/// Grouping characters accepted in every locale.
+const GROUPING_CHARS: [char; 4] = [' ', '\u{a0}', '\u{202f}', '\''];
+
+const SAMPLE_SIZE: usize = 100;
+
+/// A single parsed value and the formatting it was written with.
+struct ParsedNumber {
+    value: f64,
+    prefix: String,
+    suffix: String,
+    thousands_separator: Option<char>,
+    decimals: usize,
+    parentheses: bool,
+}
+
+impl ParsedNumber {
+    fn is_formatted(&self, locale: NumberLocale) -> bool {
+        !self.prefix.is_empty()
+            || !self.suffix.is_empty()
+            || self.thousands_separator.is_some()
+            || self.parentheses
+            || (locale == NumberLocale::DecimalComma && self.decimals > 0)
+    }
+}
+
+fn is_affix_char(c: char) -> bool {
+    CURRENCY_SYMBOLS.contains(&c) || c == '%' || c.is_whitespace()
+}
+
+fn parse_number(raw: &str, locale: NumberLocale) -> Option<ParsedNumber> {
+    let mut text = raw.trim();
+    let mut negative = false;
+
+    let parentheses = text.len() > 2 && text.starts_with('(') && text.ends_with(')');
+    if parentheses {
+        negative = true;
+        text = text[1..text.len() - 1].trim();
+    }
+    if let Some(rest) = text.strip_prefix('-') {
+        negative = !negative;
+        text = rest;
+    }
+
+    let body_start = text.find(|c: char| !is_affix_char(c))?;
+    let body_end = text
+        .rfind(|c: char| !is_affix_char(c))
+        .map(|i| i + text[i..].chars().next().map_or(1, char::len_utf8))?;
+    let prefix = text[..body_start].trim_start();
+    let suffix = text[body_end..].trim_end();
+    let mut body = &text[body_start..body_end];
+
+    if prefix.chars().any(|c| c == '%') || prefix.chars().filter(|c| !c.is_whitespace()).count() > 1
+    {
+        return None;
+    }
+    if let Some(rest) = body.strip_prefix('-') {
+        negative = !negative;
+        body = rest;
+    }
+
+    let decimal_sep = locale.decimal_separator();
+    let (int_part, frac_part) = match body.split_once(decimal_sep) {
+        Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+        None => (body, None),
+    };
+
+    let grouping: Vec<char> = int_part
+        .chars()
+        .filter(|c| *c == locale.thousands_separator() || GROUPING_CHARS.contains(c))
+        .collect();
+    let thousands_separator = grouping.first().copied();
+    if grouping.iter().any(|c| Some(*c) != thousands_separator) {
+        return None;
+    }
+
+    let groups: Vec<&str> = match thousands_separator {
+        Some(sep) => int_part.split(sep).collect(),
+        None => vec![int_part],
+    };
+    let groups_valid = groups.iter().enumerate().all(|(i, group)| {
+        let digits = group.chars().all(|c| c.is_ascii_digit());
+        match (i, thousands_separator) {
+            (_, None) => digits,
+            (0, Some(_)) => digits && (1..=3).contains(&group.len()),
+            (_, Some(_)) => digits && group.len() == 3,
+        }
+    });
+    let frac_valid = frac_part.is_none_or(|f| f.chars().all(|c| c.is_ascii_digit()));
+    if !groups_valid || !frac_valid || (int_part.is_empty() && frac_part.is_none_or(str::is_empty))
+    {
+        return None;
+    }
+
+    let digits = format!("{}.{}", groups.concat(), frac_part.unwrap_or(""));
+    let value: f64 = digits.trim_end_matches('.').parse().ok()?;
+
+    Some(ParsedNumber {
+        value: if negative { -value } else { value },
+        prefix: prefix.to_string(),
+        suffix: suffix.to_string(),
+        thousands_separator,
+        decimals: frac_part.map_or(0, str::len),
+        parentheses,
+    })
+}
+
+/// Picks the locale that parses the most sampled values, preferring a decimal
+/// point when both parse equally well (as with `1,234`).
+pub(crate) fn detect_column(values: &[&str], locale: Option<NumberLocale>) -> Option<NumberFormat> {
+    let candidates = match locale {
+        Some(locale) => vec![locale],
+        None => vec![NumberLocale::DecimalPoint, NumberLocale::DecimalComma],
+    };
+
+    let mut best: Option<(NumberLocale, Vec<ParsedNumber>)> = None;
+    for candidate in candidates {
+        let parsed: Vec<ParsedNumber> = values
+            .iter()
+            .filter_map(|v| parse_number(v, candidate))
+            .collect();
+        if best.as_ref().is_none_or(|(_, b)| parsed.len() > b.len()) {
+            best = Some((candidate, parsed));
+        }
+    }
+
+    let (locale, parsed) = best?;
+    if parsed.is_empty()
+        || (parsed.len() as f64 / values.len() as f64) <= 0.8
+        || !parsed.iter().any(|p| p.is_formatted(locale))
+    {
+        return None;
+    }
+
+    let first_non_empty = |f: fn(&ParsedNumber) -> &str| {
+        parsed
+            .iter()
+            .map(f)
+            .find(|s| !s.is_empty())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    Some(NumberFormat {
+        locale,
+        prefix: first_non_empty(|p| p.prefix.as_str()),
+        suffix: first_non_empty(|p| p.suffix.as_str()),
+        thousands_separator: parsed.iter().find_map(|p| p.thousands_separator),
+        decimals: parsed.iter().map(|p| p.decimals).max().unwrap_or(0),
+        parentheses_negative: parsed.iter().any(|p| p.parentheses),
+    })
+}
+
+/// Finds string columns written as formatted numbers. Columns with a user
+/// override are left alone. Without a `locale`, each column is detected
+/// separately.
+pub fn detect_formats(
+    df: &DataFrame,
+    locale: Option<NumberLocale>,
+    overrides: &SchemaOverrides,
+) -> NumberFormats {
+    let mut formats = NumberFormats::new();
+
+    for col in df.get_columns() {
+        if overrides.contains_key(col.name().as_str()) {
+            continue;
+        }
+        let Ok(ca) = col.str() else {
+            continue;
+        };
+
+        let values: Vec<&str> = ca
+            .into_iter()
+            .flatten()
+            .filter(|v| !v.trim().is_empty())
+            .take(SAMPLE_SIZE)
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+
+        if let Some(format) = detect_column(&values, locale) {
+            formats.insert(col.name().to_string(), format);
+        }
+    }
+
+    formats
+}
+
+fn parse_series(series: &Series, locale: NumberLocale) -> PolarsResult<Series> {
+    let parsed: Float64Chunked = series
+        .str()?
+        .into_iter()
+        .map(|v| v.and_then(|v| parse_number(v, locale)).map(|p| p.value))
+        .collect();
+    Ok(parsed.with_name(series.name().clone()).into_series())
+}
+
+/// Converts the detected columns to floats. Values that do not parse become null.
+pub fn parse_numbers(mut df: DataFrame, formats: &NumberFormats) -> PolarsResult<DataFrame> {
+    for (name, format) in formats {
+        let parsed = parse_series(df.column(name)?, format.locale)?;
+        df.replace(name, parsed)?;
+    }
+    Ok(df)
+}
+
+pub fn parse_numbers_lazy(lf: LazyFrame, formats: &NumberFormats) -> LazyFrame {
+    if formats.is_empty() {
+        return lf;
+    }
+
+    let conversions: Vec<Expr> = formats
+        .iter()
+        .map(|(name, format)| {
+            let locale = format.locale;
+            col(name).map(
+                move |s| parse_series(&s, locale).map(Some),
+                GetOutput::from_type(DataType::Float64),
+            )
+        })
+        .collect();
+    lf.with_columns(conversions)
+}
+
+/// Writes a value the way the source file did.
+pub fn format_number(value: f64, format: &NumberFormat) -> String {
+    let formatted = format!("{:.*}", format.decimals, value.abs());
+    let (int_part, frac_part) = formatted.split_once('.').unwrap_or((&formatted, ""));
+
+    let mut grouped = String::with_capacity(int_part.len() + int_part.len() / 3);
+    for (i, c) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i) % 3 == 0 {
+            if let Some(sep) = format.thousands_separator {
+                grouped.push(sep);
+            }
+        }
+        grouped.push(c);
+    }
+    if !frac_part.is_empty() {
+        grouped.push(format.locale.decimal_separator());
+        grouped.push_str(frac_part);
+    }
+
+    let body = format!("{}{}{}", format.prefix, grouped, format.suffix);
+    if value < 0.0 && format.parentheses_negative {
+        format!("({})", body)
+    } else if value < 0.0 {
+        format!("-{}", body)
+    } else {
+        body
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number_formats() {
+        let point = NumberLocale::DecimalPoint;
+        let comma = NumberLocale::DecimalComma;
+
+        assert_eq!(parse_number("$1,234.50", point).unwrap().value, 1234.5);
+        assert_eq!(parse_number("12.5%", point).unwrap().value, 12.5);
+        assert_eq!(parse_number("(300)", point).unwrap().value, -300.0);
+        assert_eq!(parse_number("1.234,56", comma).unwrap().value, 1234.56);
+        assert_eq!(parse_number("1 234,56 €", comma).unwrap().value, 1234.56);
+        assert_eq!(parse_number("-$5", point).unwrap().value, -5.0);
+
+        assert!(parse_number("1.234,56", point).is_none());
+        assert!(parse_number("12,34,5", point).is_none());
+        assert!(parse_number("Item 1", point).is_none());
+        assert!(parse_number("$", point).is_none());
+    }
+
+    #[test]
+    fn test_detect_formats_and_round_trip() {
+        let df = df! {
+            "revenue" => ["$1,234.50", "$99.00", "($300.25)", ""],
+            "growth" => ["12.5%", "-3%", "0.25%", "7%"],
+            "amount_eu" => ["1.234,56", "12,00", "7,5", "1.000.000,00"],
+            "plain" => ["1", "2", "3", "4"],
+            "label" => ["a", "b", "c", "d"],
+        }
+        .unwrap();
+
+        let formats = detect_formats(&df, None, &SchemaOverrides::new());
+
+        assert_eq!(formats.len(), 3);
+        assert_eq!(formats["amount_eu"].locale, NumberLocale::DecimalComma);
+        assert_eq!(formats["growth"].suffix, "%");
+
+        let revenue = &formats["revenue"];
+        assert_eq!(revenue.prefix, "$");
+        assert!(revenue.parentheses_negative);
+        assert_eq!(format_number(-1234.5, revenue), "($1,234.50)");
+        assert_eq!(
+            format_number(1234567.0, &formats["amount_eu"]),
+            "1.234.567,00"
+        );
+
+        let parsed = parse_numbers(df, &formats).unwrap();
+        let revenue = parsed.column("revenue").unwrap().f64().unwrap();
+        assert_eq!(revenue.get(2), Some(-300.25));
+        assert_eq!(revenue.get(3), None);
+    }
+}
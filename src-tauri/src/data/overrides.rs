@@ -0,0 +1,176 @@
+//! # Schema Overrides
+//!
+//! Applies user-chosen column types in place of Polars inference and date
+//! sniffing. Overridden columns are read as text first, so nothing (such as
+//! leading zeros in zip codes) is lost before the conversion runs.
+
+use crate::data::types::{ColumnType, SchemaOverrides};
+use crate::error::DataError;
+use polars::prelude::*;
+
+const TRUE_VALUES: [&str; 5] = ["true", "t", "yes", "y", "1"];
+const FALSE_VALUES: [&str; 5] = ["false", "f", "no", "n", "0"];
+
+/// Schema that makes a reader keep every overridden column as text.
+pub fn text_schema(overrides: &SchemaOverrides) -> Schema {
+    overrides
+        .keys()
+        .map(|name| Field::new(name.as_str().into(), DataType::String))
+        .collect()
+}
+
+/// Fails if an override names a column the source does not have.
+pub fn check_columns(overrides: &SchemaOverrides, schema: &Schema) -> Result<(), DataError> {
+    for name in overrides.keys() {
+        if schema.get(name).is_none() {
+            return Err(DataError::ColumnNotFound {
+                column: name.clone(),
+                available: schema
+                    .iter_names()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Converts overridden columns to their target types. Values that cannot be
+/// converted become null. Overrides for columns not in the frame are skipped.
+pub fn apply_lazy(lf: LazyFrame, overrides: &SchemaOverrides) -> Result<LazyFrame, DataError> {
+    if overrides.is_empty() {
+        return Ok(lf);
+    }
+
+    let schema = lf.clone().collect_schema()?;
+    let conversions: Vec<Expr> = overrides
+        .iter()
+        .filter_map(|(name, column_type)| {
+            schema
+                .get(name)
+                .map(|source| convert(name, column_type, source))
+        })
+        .collect();
+
+    Ok(lf.with_columns(conversions))
+}
+
+pub fn apply(df: DataFrame, overrides: &SchemaOverrides) -> Result<DataFrame, DataError> {
+    if overrides.is_empty() {
+        return Ok(df);
+    }
+    Ok(apply_lazy(df.lazy(), overrides)?.collect()?)
+}
+
+fn convert(name: &str, column_type: &ColumnType, source: &DataType) -> Expr {
+    let column = col(name);
+
+    if !matches!(source, DataType::String) {
+        return match column_type {
+            ColumnType::Date {
+                format: Some(format),
+            } => parse_date(column.cast(DataType::String), format),
+            _ => column.cast(target_dtype(column_type)),
+        };
+    }
+
+    let text = column.str().strip_chars(lit(NULL));
+    match column_type {
+        ColumnType::Integer | ColumnType::Float => text.cast(target_dtype(column_type)),
+        ColumnType::Date {
+            format: Some(format),
+        } => parse_date(text, format),
+        ColumnType::Date { format: None } => text.str().to_date(StrptimeOptions {
+            strict: false,
+            ..Default::default()
+        }),
+        ColumnType::Boolean => {
+            let lower = text.str().to_lowercase();
+            when(matches_any(&lower, &TRUE_VALUES))
+                .then(lit(true))
+                .when(matches_any(&lower, &FALSE_VALUES))
+                .then(lit(false))
+                .otherwise(lit(NULL).cast(DataType::Boolean))
+        }
+        ColumnType::Categorical | ColumnType::String => col(name),
+    }
+    .alias(name)
+}
+
+fn parse_date(text: Expr, format: &str) -> Expr {
+    text.str().to_date(StrptimeOptions {
+        format: Some(format.into()),
+        strict: false,
+        ..Default::default()
+    })
+}
+
+fn matches_any(value: &Expr, candidates: &[&str]) -> Expr {
+    candidates
+        .iter()
+        .map(|c| value.clone().eq(lit(*c)))
+        .reduce(|a, b| a.or(b))
+        .unwrap_or(lit(false))
+}
+
+fn target_dtype(column_type: &ColumnType) -> DataType {
+    match column_type {
+        ColumnType::Integer => DataType::Int64,
+        ColumnType::Float => DataType::Float64,
+        ColumnType::Date { .. } => DataType::Date,
+        ColumnType::Boolean => DataType::Boolean,
+        ColumnType::Categorical | ColumnType::String => DataType::String,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_converts_text_columns() {
+        let df = df! {
+            "active" => ["Yes", "no", " TRUE ", "maybe"],
+            "joined" => ["01.02.2024", "15.03.2024", "", "31.12.2023"],
+            "qty" => ["1", "2", "x", "4"],
+        }
+        .unwrap();
+        let overrides = SchemaOverrides::from([
+            ("active".to_string(), ColumnType::Boolean),
+            (
+                "joined".to_string(),
+                ColumnType::Date {
+                    format: Some("%d.%m.%Y".to_string()),
+                },
+            ),
+            ("qty".to_string(), ColumnType::Integer),
+        ]);
+
+        let df = apply(df, &overrides).unwrap();
+
+        let active: Vec<Option<bool>> = df
+            .column("active")
+            .unwrap()
+            .bool()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(active, vec![Some(true), Some(false), Some(true), None]);
+        assert_eq!(df.column("joined").unwrap().dtype(), &DataType::Date);
+        assert_eq!(df.column("joined").unwrap().null_count(), 1);
+        assert_eq!(df.column("qty").unwrap().dtype(), &DataType::Int64);
+        assert_eq!(df.column("qty").unwrap().null_count(), 1);
+    }
+
+    #[test]
+    fn test_check_columns_rejects_unknown_column() {
+        let schema = Schema::from_iter([Field::new("zip".into(), DataType::String)]);
+        let overrides = SchemaOverrides::from([("zipcode".to_string(), ColumnType::String)]);
+
+        assert!(matches!(
+            check_columns(&overrides, &schema),
+            Err(DataError::ColumnNotFound { .. })
+        ));
+    }
+}
@@ -263,20 +263,21 @@ impl QueryPlanner {
         }
 
         // 3e: Sampling for scatter plots or as last resort
-        if safety_config.allows_sampling && !needs_aggregation {
-            if row_count > safety_config.max_points {
-                let target = self
-                    .zoom_context
-                    .calculate_point_limit(safety_config.max_points);
-                let ratio = target as f64 / row_count as f64;
-
-                transformations.push(Transformation::Sample {
-                    target_rows: target,
-                    seed: SAMPLING_SEED,
-                });
+        if safety_config.allows_sampling
+            && !needs_aggregation
+            && row_count > safety_config.max_points
+        {
+            let target = self
+                .zoom_context
+                .calculate_point_limit(safety_config.max_points);
+            let ratio = target as f64 / row_count as f64;
+
+            transformations.push(Transformation::Sample {
+                target_rows: target,
+                seed: SAMPLING_SEED,
+            });
 
-                reduction_metadata = ReductionMetadata::sampled(row_count, target, ratio);
-            }
+            reduction_metadata = ReductionMetadata::sampled(row_count, target, ratio);
         }
 
         // 3f: Sorting
@@ -586,13 +587,14 @@ impl PlanExecutor {
         measure: &str,
         aggregation: &AggregationType,
     ) -> Result<LazyFrame, DataError> {
+        // Means and medians of decimals come out null, so they average as floats.
         let agg_expr = match aggregation {
             AggregationType::Sum => col(measure).sum().alias("value"),
-            AggregationType::Avg => col(measure).mean().alias("value"),
+            AggregationType::Avg => col(measure).cast(DataType::Float64).mean().alias("value"),
             AggregationType::Count => col(measure).count().alias("value"),
             AggregationType::Min => col(measure).min().alias("value"),
             AggregationType::Max => col(measure).max().alias("value"),
-            AggregationType::Median => col(measure).median().alias("value"),
+            AggregationType::Median => col(measure).cast(DataType::Float64).median().alias("value"),
         };
 
         Ok(df.group_by([col(group_by)]).agg([agg_expr]))
@@ -707,6 +709,8 @@ mod tests {
             x_field: "category".to_string(),
             y_field: "value".to_string(),
             aggregation: AggregationType::Sum,
+            x_date_binning: None,
+            y_date_binning: None,
             group_by: None,
             sort_by: SortField::Y,
             sort_order: SortOrder::Desc,
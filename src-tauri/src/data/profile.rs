@@ -0,0 +1,419 @@
+//! # Column Profiling
+//!
+//! Per-column statistics of a table: null and distinct counts, ranges,
+//! moments, quantiles, frequent values, histograms, text lengths and the span
+//! of date columns. Statistics are computed with lazy queries, so scanned
+//! tables are streamed rather than loaded, and a profile is cached until its
+//! table's data changes.
+
+use crate::data::ingest::{any_value_to_json, detect_date_formats};
+use crate::data::state::{AppDataState, Table};
+use crate::data::types::{
+    ColumnProfile, DateRange, HistogramBin, LengthStats, LogicalType, QuantileValue,
+    SchemaOverrides, TableProfile, ValueCount,
+};
+use crate::error::DataError;
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::State;
+
+/// Quantiles reported for numeric columns.
+const QUANTILES: [f64; 5] = [0.05, 0.25, 0.5, 0.75, 0.95];
+
+/// Number of most frequent values reported per column.
+const TOP_VALUES: IdxSize = 10;
+
+const HISTOGRAM_BINS: usize = 20;
+
+/// Rows sampled to find text columns that hold dates.
+const DATE_SAMPLE_ROWS: IdxSize = 100;
+
+/// Profiles by table name, with the table version they were computed for.
+#[derive(Default)]
+pub struct ProfileCache {
+    profiles: HashMap<String, (u64, TableProfile)>,
+}
+
+pub type AppProfileCache = Mutex<ProfileCache>;
+
+/// What can be computed for a column.
+enum Kind {
+    Numeric,
+    /// Dates, and columns such as booleans that only have a range.
+    Ordered,
+    Text {
+        date_format: Option<&'static str>,
+    },
+    Other,
+}
+
+fn kind_of(dtype: &DataType, date_format: Option<&'static str>) -> Kind {
+    match dtype {
+        dtype if dtype.is_numeric() => Kind::Numeric,
+        DataType::String => Kind::Text { date_format },
+        DataType::Boolean | DataType::Date | DataType::Datetime(..) | DataType::Duration(_) => {
+            Kind::Ordered
+        }
+        _ => Kind::Other,
+    }
+}
+
+/// Output name of one statistic of the column at `index`. Columns are
+/// referred to by position so that any column name is safe.
+fn stat(index: usize, name: &str) -> String {
+    format!("{}#{}", index, name)
+}
+
+/// Aggregations computing the single-value statistics of one column.
+fn stat_exprs(index: usize, name: &str, kind: &Kind) -> Vec<Expr> {
+    let mut exprs = vec![
+        col(name).null_count().alias(stat(index, "nulls")),
+        col(name).approx_n_unique().alias(stat(index, "distinct")),
+    ];
+
+    if !matches!(kind, Kind::Other) {
+        exprs.push(col(name).min().alias(stat(index, "min")));
+        exprs.push(col(name).max().alias(stat(index, "max")));
+    }
+
+    match kind {
+        Kind::Numeric => {
+            let values = || col(name).cast(DataType::Float64);
+            exprs.push(values().mean().alias(stat(index, "mean")));
+            exprs.push(values().std(1).alias(stat(index, "std")));
+            for (i, q) in QUANTILES.iter().enumerate() {
+                exprs.push(
+                    values()
+                        .quantile(lit(*q), QuantileInterpolOptions::Linear)
+                        .alias(stat(index, &format!("q{}", i))),
+                );
+            }
+        }
+        Kind::Text { date_format } => {
+            let lengths = || col(name).str().len_chars();
+            exprs.push(lengths().min().alias(stat(index, "len_min")));
+            exprs.push(lengths().max().alias(stat(index, "len_max")));
+            exprs.push(lengths().mean().alias(stat(index, "len_mean")));
+
+            if let Some(format) = date_format {
+                let dates = || {
+                    col(name).str().to_date(StrptimeOptions {
+                        format: Some((*format).into()),
+                        strict: false,
+                        ..Default::default()
+                    })
+                };
+                exprs.push(dates().min().alias(stat(index, "date_min")));
+                exprs.push(dates().max().alias(stat(index, "date_max")));
+            }
+        }
+        Kind::Ordered | Kind::Other => {}
+    }
+
+    exprs
+}
+
+fn value<'a>(stats: &'a DataFrame, name: &str) -> AnyValue<'a> {
+    stats
+        .column(name)
+        .ok()
+        .and_then(|column| column.get(0).ok())
+        .unwrap_or(AnyValue::Null)
+}
+
+fn float(stats: &DataFrame, name: &str) -> Option<f64> {
+    value(stats, name)
+        .extract::<f64>()
+        .filter(|v| v.is_finite())
+}
+
+fn count(stats: &DataFrame, name: &str) -> usize {
+    value(stats, name).extract::<usize>().unwrap_or(0)
+}
+
+fn json(stats: &DataFrame, name: &str) -> Option<serde_json::Value> {
+    match value(stats, name) {
+        AnyValue::Null => None,
+        v => Some(any_value_to_json(v)),
+    }
+}
+
+fn date_range(stats: &DataFrame, start: &str, end: &str) -> Option<DateRange> {
+    match (value(stats, start), value(stats, end)) {
+        (AnyValue::Null, _) | (_, AnyValue::Null) => None,
+        (start, end) => Some(DateRange {
+            start: start.to_string(),
+            end: end.to_string(),
+        }),
+    }
+}
+
+/// The most frequent non-null values of a column, ties broken by value.
+fn top_values(lf: LazyFrame, name: &str) -> PolarsResult<Vec<ValueCount>> {
+    let counts = lf
+        .select([col(name)])
+        .filter(col(name).is_not_null())
+        .group_by([col(name)])
+        .agg([len().alias("#count")])
+        .sort_by_exprs(
+            [col("#count"), col(name)],
+            SortMultipleOptions::default().with_order_descending_multi([true, false]),
+        )
+        .limit(TOP_VALUES)
+        .collect()?;
+
+    let values = counts.column(name)?;
+    let frequencies = counts.column("#count")?;
+    (0..counts.height())
+        .map(|i| {
+            Ok(ValueCount {
+                value: any_value_to_json(values.get(i)?),
+                count: frequencies.get(i)?.extract::<usize>().unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+/// Counts of a numeric column's values in equal-width bins from `min` to
+/// `max`. The last bin includes `max`.
+fn histogram(lf: LazyFrame, name: &str, min: f64, max: f64) -> PolarsResult<Vec<HistogramBin>> {
+    let width = (max - min) / HISTOGRAM_BINS as f64;
+    let last = (HISTOGRAM_BINS - 1) as i64;
+
+    let bin = ((col(name).cast(DataType::Float64) - lit(min)) / lit(width)).cast(DataType::Int64);
+    let counts = lf
+        .select([when(bin.clone().gt(lit(last)))
+            .then(lit(last))
+            .otherwise(bin)
+            .alias("#bin")])
+        .filter(col("#bin").is_not_null())
+        .group_by([col("#bin")])
+        .agg([len().alias("#count")])
+        .collect()?;
+
+    let mut bins: Vec<HistogramBin> = (0..HISTOGRAM_BINS)
+        .map(|i| HistogramBin {
+            start: min + width * i as f64,
+            end: min + width * (i + 1) as f64,
+            count: 0,
+        })
+        .collect();
+    let indexes = counts.column("#bin")?.i64()?;
+    let frequencies = counts.column("#count")?;
+    for (i, index) in indexes.into_iter().enumerate() {
+        if let Some(bin) = index.and_then(|b| bins.get_mut(b as usize)) {
+            bin.count = frequencies.get(i)?.extract::<usize>().unwrap_or(0);
+        }
+    }
+
+    Ok(bins)
+}
+
+/// Computes the statistics of every column of a table.
+pub fn profile(name: &str, table: &Table) -> Result<TableProfile, DataError> {
+    let lf = table.lazy();
+    let schema = table.schema()?;
+    let row_count = table.row_count()?;
+
+    let sample = lf.clone().limit(DATE_SAMPLE_ROWS).collect()?;
+    let date_formats: HashMap<String, &'static str> =
+        detect_date_formats(&sample, &SchemaOverrides::new())
+            .into_iter()
+            .collect();
+
+    let columns: Vec<(&PlSmallStr, &DataType, Kind)> = schema
+        .iter()
+        .map(|(name, dtype)| {
+            let date_format = date_formats.get(name.as_str()).copied();
+            (name, dtype, kind_of(dtype, date_format))
+        })
+        .collect();
+    if columns.is_empty() {
+        return Ok(TableProfile {
+            table: name.to_string(),
+            row_count,
+            columns: Vec::new(),
+        });
+    }
+
+    let exprs: Vec<Expr> = columns
+        .iter()
+        .enumerate()
+        .flat_map(|(i, (name, _, kind))| stat_exprs(i, name, kind))
+        .collect();
+    let stats = lf.clone().select(exprs).collect()?;
+
+    let mut profiles = Vec::with_capacity(columns.len());
+    for (i, (name, dtype, kind)) in columns.iter().enumerate() {
+        let mut profile = ColumnProfile {
+            name: name.to_string(),
+            dtype: LogicalType::from(*dtype),
+            null_count: count(&stats, &stat(i, "nulls")),
+            distinct_count: count(&stats, &stat(i, "distinct")),
+            min: json(&stats, &stat(i, "min")),
+            max: json(&stats, &stat(i, "max")),
+            mean: None,
+            std: None,
+            quantiles: Vec::new(),
+            top_values: Vec::new(),
+            histogram: Vec::new(),
+            lengths: None,
+            date_range: None,
+        };
+        if !matches!(kind, Kind::Other) {
+            profile.top_values = top_values(lf.clone(), name)?;
+        }
+
+        match kind {
+            Kind::Numeric => {
+                profile.mean = float(&stats, &stat(i, "mean"));
+                profile.std = float(&stats, &stat(i, "std"));
+                profile.quantiles = QUANTILES
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(q, quantile)| {
+                        let value = float(&stats, &stat(i, &format!("q{}", q)))?;
+                        Some(QuantileValue {
+                            quantile: *quantile,
+                            value,
+                        })
+                    })
+                    .collect();
+
+                let min = float(&stats, &stat(i, "min"));
+                let max = float(&stats, &stat(i, "max"));
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min < max {
+                        profile.histogram = histogram(lf.clone(), name, min, max)?;
+                    }
+                }
+            }
+            Kind::Ordered if dtype.is_temporal() && !matches!(dtype, DataType::Duration(_)) => {
+                profile.date_range = date_range(&stats, &stat(i, "min"), &stat(i, "max"));
+            }
+            Kind::Text { date_format } => {
+                if let Some(mean) = float(&stats, &stat(i, "len_mean")) {
+                    profile.lengths = Some(LengthStats {
+                        min: count(&stats, &stat(i, "len_min")),
+                        max: count(&stats, &stat(i, "len_max")),
+                        mean,
+                    });
+                }
+                if date_format.is_some() {
+                    profile.date_range =
+                        date_range(&stats, &stat(i, "date_min"), &stat(i, "date_max"));
+                }
+            }
+            Kind::Ordered | Kind::Other => {}
+        }
+
+        profiles.push(profile);
+    }
+
+    Ok(TableProfile {
+        table: name.to_string(),
+        row_count,
+        columns: profiles,
+    })
+}
+
+/// Statistics for every column of a table, the active table by default. A
+/// profile is computed once per version of the table and then served from
+/// cache until the table is reloaded or appended to.
+#[tauri::command]
+pub async fn profile_table(
+    table: Option<String>,
+    state: State<'_, AppDataState>,
+    cache: State<'_, AppProfileCache>,
+) -> Result<TableProfile, DataError> {
+    let (name, version, data) = {
+        let data_state = state
+            .lock()
+            .map_err(|e| DataError::ParseError(e.to_string()))?;
+        let name = match table {
+            Some(name) => name,
+            None => data_state.active_table.clone().ok_or(DataError::NoData)?,
+        };
+        let data = data_state
+            .get_table(&name)
+            .cloned()
+            .ok_or_else(|| DataError::ParseError(format!("Table '{}' not found", name)))?;
+        let version = data_state.table_version(&name).unwrap_or_default();
+        (name, version, data)
+    };
+
+    {
+        let cache = cache
+            .lock()
+            .map_err(|e| DataError::ParseError(e.to_string()))?;
+        if let Some((cached_version, profile)) = cache.profiles.get(&name) {
+            if *cached_version == version {
+                return Ok(profile.clone());
+            }
+        }
+    }
+
+    let profile = profile(&name, &data)?;
+    cache
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?
+        .profiles
+        .insert(name, (version, profile.clone()));
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column<'a>(profile: &'a TableProfile, name: &str) -> &'a ColumnProfile {
+        profile.columns.iter().find(|c| c.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_profile_numeric_column() {
+        let df = df!(
+            "amount" => [Some(1.0f64), Some(2.0), Some(2.0), Some(5.0), None],
+        )
+        .unwrap();
+        let profile = profile("sales", &Table::Materialized(df)).unwrap();
+        let amount = column(&profile, "amount");
+
+        assert_eq!(profile.row_count, 5);
+        assert_eq!(amount.null_count, 1);
+        assert_eq!(amount.min, Some(serde_json::json!(1.0)));
+        assert_eq!(amount.max, Some(serde_json::json!(5.0)));
+        assert_eq!(amount.mean, Some(2.5));
+        let median = amount.quantiles.iter().find(|q| q.quantile == 0.5).unwrap();
+        assert_eq!(median.value, 2.0);
+
+        assert_eq!(amount.top_values[0].value, serde_json::json!(2.0));
+        assert_eq!(amount.top_values[0].count, 2);
+        assert_eq!(amount.histogram.len(), HISTOGRAM_BINS);
+        assert_eq!(amount.histogram.iter().map(|b| b.count).sum::<usize>(), 4);
+        assert_eq!(amount.histogram[HISTOGRAM_BINS - 1].count, 1);
+    }
+
+    #[test]
+    fn test_profile_text_columns() {
+        let df = df!(
+            "region" => ["North", "South", "North", "East"],
+            "ordered" => ["2024-03-01", "2024-01-15", "2024-02-10", "2024-05-30"],
+        )
+        .unwrap();
+        let profile = profile("sales", &Table::Materialized(df)).unwrap();
+
+        let region = column(&profile, "region");
+        assert_eq!(region.distinct_count, 3);
+        assert_eq!(region.top_values[0].value, serde_json::json!("North"));
+        let lengths = region.lengths.as_ref().unwrap();
+        assert_eq!((lengths.min, lengths.max), (4, 5));
+        assert!(region.date_range.is_none());
+
+        let ordered = column(&profile, "ordered");
+        let range = ordered.date_range.as_ref().unwrap();
+        assert_eq!(range.start, "2024-01-15");
+        assert_eq!(range.end, "2024-05-30");
+    }
+}
@@ -0,0 +1,228 @@
+//! # Load Progress
+//!
+//! Large files are loaded on a blocking worker thread so the app stays
+//! responsive. Readers report how far they have got through `load-progress`
+//! events, and a running load can be stopped with `cancel_load`, which makes
+//! it fail with `DataError::QueryCancelled`.
+
+use crate::data::archive;
+use crate::data::types::LoadProgress;
+use crate::error::DataError;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Event emitted as a load makes progress.
+pub const PROGRESS_EVENT: &str = "load-progress";
+
+/// Bytes read between two progress events.
+const REPORT_EVERY: u64 = 4 * 1024 * 1024;
+
+/// Cancellation flags of the running loads, by path.
+#[derive(Default)]
+pub struct LoadTasks {
+    running: HashMap<String, Arc<AtomicBool>>,
+}
+
+pub type AppLoadState = Mutex<LoadTasks>;
+
+/// Handle a load reports its progress through and checks for cancellation.
+#[derive(Clone)]
+pub struct Progress {
+    app: Option<AppHandle>,
+    path: String,
+    total_bytes: u64,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Progress {
+    pub(crate) fn new(app: Option<AppHandle>, path: &str) -> Self {
+        Self {
+            app,
+            path: path.to_string(),
+            total_bytes: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn emit(&self, bytes_read: u64, rows: Option<usize>, sheet: Option<&str>) {
+        if let Some(app) = &self.app {
+            let update = LoadProgress {
+                path: self.path.clone(),
+                bytes_read,
+                total_bytes: self.total_bytes,
+                rows,
+                sheet: sheet.map(str::to_string),
+            };
+            let _ = app.emit(PROGRESS_EVENT, update);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    fn cancelled_error(&self) -> DataError {
+        DataError::QueryCancelled {
+            reason: format!("loading {} was cancelled", self.path),
+        }
+    }
+
+    /// Fails once the load has been cancelled. Called between steps that
+    /// cannot be interrupted themselves.
+    pub fn check(&self) -> Result<(), DataError> {
+        if self.is_cancelled() {
+            Err(self.cancelled_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Reports the number of rows parsed.
+    pub fn rows(&self, rows: usize) {
+        self.emit(self.total_bytes, Some(rows), None);
+    }
+
+    /// Reports the workbook sheet, database table, archive member or folder
+    /// file about to be read.
+    pub fn sheet(&self, sheet: &str) {
+        self.emit(0, None, Some(sheet));
+    }
+
+    /// Reads a whole file into memory, decompressing it if its extension says
+    /// so, and reports the bytes read as it goes.
+    pub fn read_file(&self, path: &str) -> Result<Vec<u8>, DataError> {
+        let file = ProgressReader {
+            inner: BufReader::new(File::open(path)?),
+            progress: self,
+            read: 0,
+            reported: 0,
+        };
+        let mut reader: Box<dyn Read + '_> = match archive::Compression::from_name(path) {
+            Some(compression) if archive::is_compressed(path) => compression.decoder(file)?,
+            _ => Box::new(file),
+        };
+
+        let mut contents = Vec::new();
+        let read = reader.read_to_end(&mut contents);
+        self.check()?;
+        read?;
+        self.emit(self.total_bytes, None, None);
+        Ok(contents)
+    }
+}
+
+/// Counts the bytes read from a file and stops reading once cancelled.
+struct ProgressReader<'a, R> {
+    inner: R,
+    progress: &'a Progress,
+    read: u64,
+    reported: u64,
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.progress.is_cancelled() {
+            return Err(io::Error::other("Loading cancelled"));
+        }
+
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        if self.read - self.reported >= REPORT_EVERY {
+            self.reported = self.read;
+            self.progress.emit(self.read, None, None);
+        }
+        Ok(n)
+    }
+}
+
+/// Runs a load of `path` on a blocking worker. While it runs, `cancel_load`
+/// can stop it; a cancelled load fails with `DataError::QueryCancelled`
+/// whatever error its reader ran into.
+pub async fn run<T, F>(app: &AppHandle, path: &str, load: F) -> Result<T, DataError>
+where
+    T: Send + 'static,
+    F: FnOnce(&Progress) -> Result<T, DataError> + Send + 'static,
+{
+    let progress = Progress::new(Some(app.clone()), path);
+    let loads = app.state::<AppLoadState>();
+    if let Ok(mut tasks) = loads.lock() {
+        tasks
+            .running
+            .insert(path.to_string(), progress.cancelled.clone());
+    }
+
+    let worker = progress.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || load(&worker)).await;
+
+    if let Ok(mut tasks) = loads.lock() {
+        // A later load of the same path may have taken over the entry.
+        if tasks
+            .running
+            .get(path)
+            .is_some_and(|flag| Arc::ptr_eq(flag, &progress.cancelled))
+        {
+            tasks.running.remove(path);
+        }
+    }
+
+    match result {
+        _ if progress.is_cancelled() => Err(progress.cancelled_error()),
+        Ok(result) => result,
+        Err(e) => Err(DataError::ReadError(e.to_string())),
+    }
+}
+
+/// Cancels the running load of `path`. Returns whether one was running.
+#[tauri::command]
+pub async fn cancel_load(path: String, loads: State<'_, AppLoadState>) -> Result<bool, DataError> {
+    let tasks = loads
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+
+    match tasks.running.get(&path) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_file_decompresses() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("events.csv.gz");
+        let mut encoder =
+            flate2::write::GzEncoder::new(File::create(&path).unwrap(), Default::default());
+        encoder.write_all(b"id,kind\n1,click\n").unwrap();
+        encoder.finish().unwrap();
+
+        let progress = Progress::new(None, path.to_str().unwrap());
+        let contents = progress.read_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(contents, b"id,kind\n1,click\n");
+    }
+
+    #[test]
+    fn test_cancelled_read_fails_with_query_cancelled() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("events.csv");
+        fs::write(&path, "id,kind\n1,click\n").unwrap();
+
+        let progress = Progress::new(None, path.to_str().unwrap());
+        progress.cancelled.store(true, Ordering::Relaxed);
+
+        let err = progress.read_file(path.to_str().unwrap()).unwrap_err();
+        assert!(matches!(err, DataError::QueryCancelled { .. }));
+        assert!(progress.check().is_err());
+    }
+}
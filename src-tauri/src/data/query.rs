@@ -21,7 +21,7 @@ use crate::ai::types::{
 };
 use crate::data::safety::{ZoomContext, MAX_VISUAL_POINTS, DateBinGranularity};
 use crate::data::sampling::scatter_sample;
-use crate::data::state::AppDataState;
+use crate::data::state::{count_rows, AppDataState, Table};
 use crate::error::DataError;
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -113,7 +113,7 @@ fn default_reduction_reason() -> String {
 
 /// Apply a single filter to the LazyFrame with error handling.
 /// Filters are pushed down to minimize data processed.
-fn apply_filter(df: LazyFrame, filter: &FilterSpec) -> Result<LazyFrame, DataError> {
+pub(crate) fn apply_filter(df: LazyFrame, filter: &FilterSpec) -> Result<LazyFrame, DataError> {
     let col_expr = col(&filter.column);
 
     let predicate = match filter.operator {
@@ -221,13 +221,14 @@ fn apply_aggregation(
     y_field: &str,
     aggregation: &AggregationType,
 ) -> Result<LazyFrame, DataError> {
+    // Means and medians of decimals come out null, so they average as floats.
     let agg_expr = match aggregation {
         AggregationType::Sum => col(y_field).sum().alias("value"),
-        AggregationType::Avg => col(y_field).mean().alias("value"),
+        AggregationType::Avg => col(y_field).cast(DataType::Float64).mean().alias("value"),
         AggregationType::Count => col(y_field).count().alias("value"),
         AggregationType::Min => col(y_field).min().alias("value"),
         AggregationType::Max => col(y_field).max().alias("value"),
-        AggregationType::Median => col(y_field).median().alias("value"),
+        AggregationType::Median => col(y_field).cast(DataType::Float64).median().alias("value"),
     };
 
     Ok(df.group_by([col(x_field)]).agg([agg_expr]))
@@ -474,52 +475,55 @@ fn is_numeric_dtype(dtype: &DataType) -> bool {
             | DataType::UInt64
             | DataType::Float32
             | DataType::Float64
+            | DataType::Decimal(_, _)
     )
 }
 
-async fn execute_visualization_query_internal(
-    spec: VisualizationSpec,
-    state: State<'_, AppDataState>,
-) -> Result<ChartData, DataError> {
+/// A copy of the active table. Copies of scans only hold the query plan, so
+/// the lock is released before a query counts or reads the file.
+fn active_table(state: &AppDataState) -> Result<Table, DataError> {
     let data_state = state
         .lock()
         .map_err(|e| DataError::ParseError(format!("Failed to acquire data lock: {}", e)))?;
+    data_state
+        .get_active_table()
+        .cloned()
+        .ok_or(DataError::NoData)
+}
 
-    let df = data_state.get_active_dataframe().ok_or(DataError::NoData)?.clone();
-    let total_records = df.height();
-
-    for field_name in [&spec.x_field, &spec.y_field] {
-        if df.column(field_name).is_err() {
-            return Err(DataError::ColumnNotFound {
-                column: field_name.clone(),
-                available: df
-                    .get_column_names()
-                    .iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<_>>()
-                    .join(", "),
-            });
-        }
-    }
+async fn execute_visualization_query_internal(
+    spec: VisualizationSpec,
+    state: State<'_, AppDataState>,
+) -> Result<ChartData, DataError> {
+    let table = active_table(&state)?;
+    let schema = table.schema()?;
+    let total_records = table.row_count()?;
+
+    let dtype_of = |field_name: &String| {
+        schema.get(field_name).cloned().ok_or_else(|| DataError::ColumnNotFound {
+            column: field_name.clone(),
+            available: schema
+                .iter_names()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+    };
 
-    let x_dtype = df.column(&spec.x_field).unwrap().dtype().clone();
-    let y_dtype = df.column(&spec.y_field).unwrap().dtype().clone();
+    let x_dtype = dtype_of(&spec.x_field)?;
+    let y_dtype = dtype_of(&spec.y_field)?;
 
     let x_is_numeric = is_numeric_dtype(&x_dtype);
     let y_is_numeric = is_numeric_dtype(&y_dtype);
 
     let (category_field, value_field, swapped) = if x_is_numeric && !y_is_numeric {
         (spec.y_field.clone(), spec.x_field.clone(), true)
-    } else if !x_is_numeric && y_is_numeric {
-        (spec.x_field.clone(), spec.y_field.clone(), false)
-    } else if !x_is_numeric && !y_is_numeric {
-        (spec.x_field.clone(), spec.y_field.clone(), false)
     } else {
         (spec.x_field.clone(), spec.y_field.clone(), false)
     };
 
-    let value_dtype = df.column(&value_field).unwrap().dtype().clone();
-    let value_is_numeric = is_numeric_dtype(&value_dtype);
+    let value_dtype = if swapped { &x_dtype } else { &y_dtype };
+    let value_is_numeric = is_numeric_dtype(value_dtype);
     let effective_aggregation = if value_is_numeric {
         spec.aggregation.clone()
     } else {
@@ -534,10 +538,13 @@ async fn execute_visualization_query_internal(
         category_field, value_field, swapped, x_dtype, y_dtype
     );
 
-    let category_series = df
-        .column(&category_field)
-        .map_err(|e| DataError::ParseError(e.to_string()))?;
-    let _cardinality = estimate_cardinality(category_series, total_records);
+    // Scans are never pulled into memory just to estimate cardinality.
+    if let Table::Materialized(df) = &table {
+        let category_series = df
+            .column(&category_field)
+            .map_err(|e| DataError::ParseError(e.to_string()))?;
+        let _cardinality = estimate_cardinality(category_series, total_records);
+    }
 
     let chart_type_str = format!("{:?}", spec.chart_type).to_lowercase();
     let max_points = get_max_points_for_chart(&chart_type_str);
@@ -548,7 +555,7 @@ async fn execute_visualization_query_internal(
     let mut top_n_value: Option<usize> = None;
     let mut warning_message: Option<String> = None;
 
-    let mut lazy_df = df.lazy();
+    let mut lazy_df = table.lazy();
 
     for filter in &spec.filters {
         lazy_df = apply_filter(lazy_df, filter)?;
@@ -681,50 +688,32 @@ async fn execute_scatter_query_internal(
     spec: VisualizationSpec,
     state: State<'_, AppDataState>,
 ) -> Result<ChartData, DataError> {
-    let data_state = state
-        .lock()
-        .map_err(|e| DataError::ParseError(format!("Failed to acquire data lock: {}", e)))?;
-
-    let df = data_state.get_active_dataframe().ok_or(DataError::NoData)?.clone();
-    let total_records = df.height();
+    let table = active_table(&state)?;
+    let schema = table.schema()?;
+    let total_records = table.row_count()?;
 
     // Validate columns
-    if df.column(&spec.x_field).is_err() {
-        return Err(DataError::ColumnNotFound {
-            column: spec.x_field.clone(),
-            available: df
-                .get_column_names()
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>()
-                .join(", "),
-        });
-    }
-    if df.column(&spec.y_field).is_err() {
-        return Err(DataError::ColumnNotFound {
-            column: spec.y_field.clone(),
-            available: df
-                .get_column_names()
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>()
-                .join(", "),
-        });
+    for field_name in [&spec.x_field, &spec.y_field] {
+        if schema.get(field_name).is_none() {
+            return Err(DataError::ColumnNotFound {
+                column: field_name.clone(),
+                available: schema
+                    .iter_names()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            });
+        }
     }
 
-    let mut lazy_df = df.lazy();
+    let mut lazy_df = table.lazy();
 
     // Apply filters
     for filter in &spec.filters {
         lazy_df = apply_filter(lazy_df, filter)?;
     }
 
-    // Collect filtered data
-    let filtered_df = lazy_df
-        .collect()
-        .map_err(|e| DataError::ParseError(e.to_string()))?;
-
-    let filtered_count = filtered_df.height();
+    let filtered_count = count_rows(lazy_df.clone())?;
 
     // Track reduction metadata
     let mut reduced = false;
@@ -747,11 +736,11 @@ async fn execute_scatter_query_internal(
         ));
 
         // Use deterministic sampling
-        let sample_result = scatter_sample(filtered_df.lazy(), filtered_count, SCATTER_MAX_POINTS)?;
+        let sample_result = scatter_sample(lazy_df, filtered_count, SCATTER_MAX_POINTS)?;
 
         (sample_result.data, sample_ratio)
     } else {
-        (filtered_df, None)
+        (lazy_df.collect()?, None)
     };
 
     let returned_points = result_df.height();
@@ -828,17 +817,13 @@ pub async fn execute_table_query(
     filters: Vec<FilterSpec>,
     state: State<'_, AppDataState>,
 ) -> Result<TableData, DataError> {
-    let data_state = state
-        .lock()
-        .map_err(|e| DataError::ParseError(format!("Failed to acquire data lock: {}", e)))?;
-
-    let df = data_state.get_active_dataframe().ok_or(DataError::NoData)?.clone();
-    let total_records = df.height();
+    let table = active_table(&state)?;
+    let total_records = table.row_count()?;
 
     // SAFETY: Cap page size at 1000 rows
     let safe_page_size = page_size.min(1000);
 
-    let mut lazy_df = df.lazy();
+    let mut lazy_df = table.lazy();
 
     // Apply filters
     for filter in &filters {
@@ -854,28 +839,21 @@ pub async fn execute_table_query(
     }
 
     // Count total after filters
-    let filtered_df = lazy_df
-        .clone()
-        .collect()
-        .map_err(|e| DataError::ParseError(e.to_string()))?;
-
-    let total_filtered = filtered_df.height();
+    let total_filtered = count_rows(lazy_df.clone())?;
 
     // Apply pagination
     let offset = page * safe_page_size;
-    let paginated = filtered_df.slice(offset as i64, safe_page_size);
+    let mut paginated = lazy_df.slice(offset as i64, safe_page_size as IdxSize);
 
     // Select requested columns (or all if empty)
-    let result = if columns.is_empty() {
-        paginated
-    } else {
-        let column_exprs: Vec<Expr> = columns.iter().map(|c| col(c)).collect();
-        paginated
-            .lazy()
-            .select(column_exprs)
-            .collect()
-            .map_err(|e| DataError::ParseError(e.to_string()))?
-    };
+    if !columns.is_empty() {
+        let column_exprs: Vec<Expr> = columns.iter().map(col).collect();
+        paginated = paginated.select(column_exprs);
+    }
+
+    let result = paginated
+        .collect()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
 
     // Convert to rows
     let rows = df_to_rows(&result);
@@ -885,7 +863,7 @@ pub async fn execute_table_query(
         total_rows: total_filtered,
         page,
         page_size: safe_page_size,
-        total_pages: (total_filtered + safe_page_size - 1) / safe_page_size,
+        total_pages: total_filtered.div_ceil(safe_page_size),
         warning: if total_records > 100_000 {
             Some(format!(
                 "Large dataset ({} rows). Using pagination for performance.",
@@ -1033,6 +1011,32 @@ mod tests {
         assert!(result.column("value").is_ok());
     }
 
+    #[test]
+    fn test_aggregate_decimal_column() {
+        let df = create_test_df()
+            .lazy()
+            .with_column(col("value").cast(DataType::Decimal(Some(10), Some(2))))
+            .collect()
+            .unwrap();
+        assert!(is_numeric_dtype(df.column("value").unwrap().dtype()));
+
+        let expected = [
+            (AggregationType::Sum, 55.0),
+            (AggregationType::Avg, 13.75),
+            (AggregationType::Median, 13.5),
+        ];
+        for (aggregation, expected) in expected {
+            let result = apply_aggregation(df.clone().lazy(), "category", "value", &aggregation)
+                .unwrap()
+                .filter(col("category").eq(lit("A")))
+                .collect()
+                .unwrap();
+            let (labels, values) = extract_chart_data(result, "category").unwrap();
+            assert_eq!(labels, ["A"]);
+            assert!((values[0] - expected).abs() < 1e-9);
+        }
+    }
+
     #[test]
     fn test_top_n_with_others() {
         let df = create_test_df();
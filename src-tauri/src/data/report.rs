@@ -0,0 +1,261 @@
+//! # Ingest Reports
+//!
+//! Compares what a file contained with what ended up in the typed DataFrame,
+//! so coercion failures and malformed rows are reported rather than dropped.
+
+use crate::data::types::{ColumnIssue, IngestReport, LogicalType, MalformedRow, RejectedValue};
+use crate::error::DataError;
+use polars::prelude::*;
+use std::io::Read;
+
+/// Rejected values kept per column for display.
+pub(crate) const MAX_SAMPLES_PER_COLUMN: usize = 5;
+
+/// Malformed rows kept for display. All of them are still counted.
+const MAX_MALFORMED_ROWS: usize = 100;
+
+/// Finds values that were non-empty in `raw` (an all-string read of the same
+/// source) but are null in `typed`. Both frames must have the same rows.
+pub fn coercion_issues(raw: &DataFrame, typed: &DataFrame) -> Result<Vec<ColumnIssue>, DataError> {
+    let rows: Vec<usize> = (0..raw.height()).collect();
+    coercion_issues_in_rows(raw, &rows, typed)
+}
+
+/// As `coercion_issues`, with `raw` holding only some rows of the source:
+/// row `i` of `raw` is row `rows[i]` of `typed`. Rows left out must not be
+/// null in `typed` outside text columns, as with `rows_with_nulls`.
+pub fn coercion_issues_in_rows(
+    raw: &DataFrame,
+    rows: &[usize],
+    typed: &DataFrame,
+) -> Result<Vec<ColumnIssue>, DataError> {
+    let mut issues = Vec::new();
+
+    for typed_col in typed.get_columns() {
+        if matches!(typed_col.dtype(), DataType::String) {
+            continue;
+        }
+        let Ok(raw_col) = raw.column(typed_col.name()) else {
+            continue;
+        };
+        let Ok(raw_values) = raw_col.str() else {
+            continue;
+        };
+
+        let nulls = typed_col.is_null();
+        let mut failed_count = 0;
+        let mut samples = Vec::new();
+
+        for (raw_value, &row) in raw_values.into_iter().zip(rows) {
+            let Some(value) = raw_value.map(str::trim).filter(|v| !v.is_empty()) else {
+                continue;
+            };
+
+            if nulls.get(row).unwrap_or(false) {
+                failed_count += 1;
+                if samples.len() < MAX_SAMPLES_PER_COLUMN {
+                    samples.push(RejectedValue {
+                        row: row + 1,
+                        value: value.to_string(),
+                    });
+                }
+            }
+        }
+
+        if failed_count > 0 {
+            // Typed values are only present where the text was.
+            let present = typed_col.len() - typed_col.null_count() + failed_count;
+            issues.push(ColumnIssue {
+                column: typed_col.name().to_string(),
+                target_type: LogicalType::from(typed_col.dtype()),
+                failed_count,
+                failed_ratio: failed_count as f64 / present as f64,
+                samples,
+            });
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Rows of `typed` with a null outside its text columns, in order. Only these
+/// can hold values that were lost while typing.
+pub fn rows_with_nulls(typed: &DataFrame) -> Vec<usize> {
+    let masks: Vec<BooleanChunked> = typed
+        .get_columns()
+        .iter()
+        .filter(|column| !matches!(column.dtype(), DataType::String))
+        .filter(|column| column.null_count() > 0)
+        .map(|column| column.is_null())
+        .collect();
+
+    (0..typed.height())
+        .filter(|&row| masks.iter().any(|mask| mask.get(row).unwrap_or(false)))
+        .collect()
+}
+
+/// Streams delimited text and reads the given data rows, counted from 0 after
+/// `skip_rows` and the header, as text. Fields are named by position with
+/// `names`, so the result lines up with the typed read of the same text.
+pub fn raw_rows<R: Read>(
+    reader: R,
+    delimiter: u8,
+    quote_char: Option<u8>,
+    skip_rows: usize,
+    has_header: bool,
+    rows: &[usize],
+    names: &[PlSmallStr],
+) -> Result<DataFrame, DataError> {
+    let mut csv_reader = csv_reader(reader, delimiter, quote_char);
+    let mut values: Vec<Vec<Option<String>>> = vec![Vec::with_capacity(rows.len()); names.len()];
+    let mut wanted = rows.iter().copied().peekable();
+    let mut record = csv::ByteRecord::new();
+    let mut records = 0;
+    let mut row = 0;
+
+    while wanted.peek().is_some() {
+        let start = csv_reader.position().line();
+        let read = csv_reader
+            .read_byte_record(&mut record)
+            .map_err(|e| DataError::ReadError(e.to_string()))?;
+        if !read {
+            break;
+        }
+        records += 1;
+        if records <= skip_rows + usize::from(has_header) {
+            continue;
+        }
+
+        // Blank lines before the record are skipped here, but are rows of
+        // nulls in the typed read.
+        let lines = csv_reader.position().line() - start;
+        let newlines = record.as_slice().iter().filter(|&&b| b == b'\n').count() as u64;
+        row += lines.saturating_sub(newlines + 1) as usize;
+        while wanted.next_if(|&wanted_row| wanted_row < row).is_some() {
+            values.iter_mut().for_each(|column| column.push(None));
+        }
+
+        if wanted.next_if_eq(&row).is_some() {
+            for (field, column) in values.iter_mut().enumerate() {
+                let value = record
+                    .get(field)
+                    .map(|f| String::from_utf8_lossy(f).into_owned());
+                column.push(value);
+            }
+        }
+        row += 1;
+    }
+
+    let columns = names
+        .iter()
+        .zip(values)
+        .map(|(name, mut column)| {
+            // Rows past the end of the text stay empty.
+            column.resize(rows.len(), None);
+            Series::new(name.clone(), column)
+        })
+        .collect();
+    Ok(DataFrame::new(columns)?)
+}
+
+fn csv_reader<R: Read>(reader: R, delimiter: u8, quote_char: Option<u8>) -> csv::Reader<R> {
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .quoting(quote_char.is_some())
+        .quote(quote_char.unwrap_or(b'"'))
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(reader)
+}
+
+/// Streams delimited text and collects rows whose field count differs from the
+/// first row after `skip_rows`.
+pub fn malformed_rows<R: Read>(
+    reader: R,
+    delimiter: u8,
+    quote_char: Option<u8>,
+    skip_rows: usize,
+) -> Result<(usize, Vec<MalformedRow>), DataError> {
+    let mut csv_reader = csv_reader(reader, delimiter, quote_char);
+
+    let mut expected_fields = None;
+    let mut count = 0;
+    let mut rows = Vec::new();
+
+    for record in csv_reader.byte_records().skip(skip_rows) {
+        let record = record.map_err(|e| DataError::ReadError(e.to_string()))?;
+        let expected = *expected_fields.get_or_insert(record.len());
+
+        if record.len() != expected {
+            count += 1;
+            if rows.len() < MAX_MALFORMED_ROWS {
+                let raw = record
+                    .iter()
+                    .map(String::from_utf8_lossy)
+                    .collect::<Vec<_>>()
+                    .join(&(delimiter as char).to_string());
+                rows.push(MalformedRow {
+                    line: record.position().map(|p| p.line()).unwrap_or_default(),
+                    expected_fields: expected,
+                    found_fields: record.len(),
+                    raw,
+                });
+            }
+        }
+    }
+
+    Ok((count, rows))
+}
+
+pub fn build_report(
+    total_rows: usize,
+    column_issues: Vec<ColumnIssue>,
+    malformed: (usize, Vec<MalformedRow>),
+) -> IngestReport {
+    let (malformed_row_count, malformed_rows) = malformed;
+
+    IngestReport {
+        total_rows,
+        nulls_introduced: column_issues.iter().map(|i| i.failed_count).sum(),
+        column_issues,
+        malformed_row_count,
+        malformed_rows,
+        skipped_tables: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coercion_issues_reports_dropped_values() {
+        let raw = df! {
+            "revenue" => [Some("100"), Some("n/a"), Some(""), None, Some("250")],
+        }
+        .unwrap();
+        let typed = df! {
+            "revenue" => [Some(100i64), None, None, None, Some(250)],
+        }
+        .unwrap();
+
+        let issues = coercion_issues(&raw, &typed).unwrap();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].failed_count, 1);
+        assert_eq!(issues[0].samples[0].row, 2);
+        assert_eq!(issues[0].samples[0].value, "n/a");
+        assert!((issues[0].failed_ratio - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_malformed_rows_reports_line_numbers() {
+        let text = "title line\na,b,c\n1,2,3\n4,5\n6,7,8,9\n";
+        let (count, rows) = malformed_rows(text.as_bytes(), b',', Some(b'"'), 1).unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(rows[0].line, 4);
+        assert_eq!(rows[0].found_fields, 2);
+        assert_eq!(rows[1].raw, "6,7,8,9");
+    }
+}
@@ -0,0 +1,413 @@
+//! # Semantic Types
+//!
+//! Tags columns with what their values mean, such as emails, country codes,
+//! coordinates or identifiers. A sample of each column is matched against
+//! patterns and lists of known values, with the column name breaking ties
+//! where values alone are ambiguous (a five digit number may be a postal
+//! code or a quantity).
+
+use crate::data::numeric;
+use crate::data::types::{ColumnInfo, NumberFormat, NumberFormats, SemanticType};
+use polars::prelude::*;
+use std::collections::HashSet;
+
+/// Non-null values looked at per column.
+pub const SAMPLE_SIZE: usize = 500;
+
+/// Share of sampled values that must match for a column to be tagged.
+const MIN_MATCH_SHARE: f64 = 0.9;
+
+/// Average number of words from which text is considered free text.
+const FREE_TEXT_WORDS: f64 = 5.0;
+
+const US_STATES: [(&str, &str); 51] = [
+    ("AL", "alabama"),
+    ("AK", "alaska"),
+    ("AZ", "arizona"),
+    ("AR", "arkansas"),
+    ("CA", "california"),
+    ("CO", "colorado"),
+    ("CT", "connecticut"),
+    ("DE", "delaware"),
+    ("DC", "district of columbia"),
+    ("FL", "florida"),
+    ("GA", "georgia"),
+    ("HI", "hawaii"),
+    ("ID", "idaho"),
+    ("IL", "illinois"),
+    ("IN", "indiana"),
+    ("IA", "iowa"),
+    ("KS", "kansas"),
+    ("KY", "kentucky"),
+    ("LA", "louisiana"),
+    ("ME", "maine"),
+    ("MD", "maryland"),
+    ("MA", "massachusetts"),
+    ("MI", "michigan"),
+    ("MN", "minnesota"),
+    ("MS", "mississippi"),
+    ("MO", "missouri"),
+    ("MT", "montana"),
+    ("NE", "nebraska"),
+    ("NV", "nevada"),
+    ("NH", "new hampshire"),
+    ("NJ", "new jersey"),
+    ("NM", "new mexico"),
+    ("NY", "new york"),
+    ("NC", "north carolina"),
+    ("ND", "north dakota"),
+    ("OH", "ohio"),
+    ("OK", "oklahoma"),
+    ("OR", "oregon"),
+    ("PA", "pennsylvania"),
+    ("RI", "rhode island"),
+    ("SC", "south carolina"),
+    ("SD", "south dakota"),
+    ("TN", "tennessee"),
+    ("TX", "texas"),
+    ("UT", "utah"),
+    ("VT", "vermont"),
+    ("VA", "virginia"),
+    ("WA", "washington"),
+    ("WV", "west virginia"),
+    ("WI", "wisconsin"),
+    ("WY", "wyoming"),
+];
+
+/// ISO 3166-1 alpha-2 codes.
+const COUNTRY_CODES_2: &str = "AD AE AF AG AI AL AM AO AQ AR AS AT AU AW AX AZ BA BB BD BE BF BG \
+    BH BI BJ BL BM BN BO BQ BR BS BT BV BW BY BZ CA CC CD CF CG CH CI CK CL CM CN CO CR CU CV CW \
+    CX CY CZ DE DJ DK DM DO DZ EC EE EG EH ER ES ET FI FJ FK FM FO FR GA GB GD GE GF GG GH GI GL \
+    GM GN GP GQ GR GS GT GU GW GY HK HM HN HR HT HU ID IE IL IM IN IO IQ IR IS IT JE JM JO JP KE \
+    KG KH KI KM KN KP KR KW KY KZ LA LB LC LI LK LR LS LT LU LV LY MA MC MD ME MF MG MH MK ML MM \
+    MN MO MP MQ MR MS MT MU MV MW MX MY MZ NA NC NE NF NG NI NL NO NP NR NU NZ OM PA PE PF PG PH \
+    PK PL PM PN PR PS PT PW PY QA RE RO RS RU RW SA SB SC SD SE SG SH SI SJ SK SL SM SN SO SR SS \
+    ST SV SX SY SZ TC TD TF TG TH TJ TK TL TM TN TO TR TT TV TW TZ UA UG UM US UY UZ VA VC VE VG \
+    VI VN VU WF WS YE YT ZA ZM ZW UK";
+
+/// ISO 3166-1 alpha-3 codes.
+const COUNTRY_CODES_3: &str = "ABW AFG AGO AIA ALA ALB AND ARE ARG ARM ASM ATA ATF ATG AUS AUT \
+    AZE BDI BEL BEN BES BFA BGD BGR BHR BHS BIH BLM BLR BLZ BMU BOL BRA BRB BRN BTN BVT BWA CAF \
+    CAN CCK CHE CHL CHN CIV CMR COD COG COK COL COM CPV CRI CUB CUW CXR CYM CYP CZE DEU DJI DMA \
+    DNK DOM DZA ECU EGY ERI ESH ESP EST ETH FIN FJI FLK FRA FRO FSM GAB GBR GEO GGY GHA GIB GIN \
+    GLP GMB GNB GNQ GRC GRD GRL GTM GUF GUM GUY HKG HMD HND HRV HTI HUN IDN IMN IND IOT IRL IRN \
+    IRQ ISL ISR ITA JAM JEY JOR JPN KAZ KEN KGZ KHM KIR KNA KOR KWT LAO LBN LBR LBY LCA LIE LKA \
+    LSO LTU LUX LVA MAC MAF MAR MCO MDA MDG MDV MEX MHL MKD MLI MLT MMR MNE MNG MNP MOZ MRT MSR \
+    MTQ MUS MWI MYS MYT NAM NCL NER NFK NGA NIC NIU NLD NOR NPL NRU NZL OMN PAK PAN PCN PER PHL \
+    PLW PNG POL PRI PRK PRT PRY PSE PYF QAT REU ROU RUS RWA SAU SDN SEN SGP SGS SHN SJM SLB SLE \
+    SLV SMR SOM SPM SRB SSD STP SUR SVK SVN SWE SWZ SXM SYC SYR TCA TCD TGO THA TJK TKL TKM TLS \
+    TON TTO TUN TUR TUV TWN TZA UGA UKR UMI URY USA UZB VAT VCT VEN VGB VIR VNM VUT WLF WSM YEM \
+    ZAF ZMB ZWE";
+
+/// Last words of column names that hold keys, such as `order_id` or `sku`.
+const IDENTIFIER_WORDS: [&str; 9] = [
+    "id", "uuid", "guid", "key", "code", "sku", "number", "no", "num",
+];
+
+const POSTAL_WORDS: [&str; 5] = ["zip", "zipcode", "postal", "postcode", "plz"];
+const COUNTRY_WORDS: [&str; 3] = ["country", "nation", "iso"];
+const PERCENT_WORDS: [&str; 3] = ["pct", "percent", "percentage"];
+const LATITUDE_WORDS: [&str; 2] = ["lat", "latitude"];
+const LONGITUDE_WORDS: [&str; 4] = ["lon", "lng", "long", "longitude"];
+
+/// Lowercase words of a column name, split at separators and camelCase
+/// boundaries: `orderID` and `order_id` both give `["order", "id"]`.
+fn name_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn has_word(words: &[String], candidates: &[&str]) -> bool {
+    words.iter().any(|w| candidates.contains(&w.as_str()))
+}
+
+fn is_identifier_name(words: &[String]) -> bool {
+    words
+        .last()
+        .is_some_and(|w| IDENTIFIER_WORDS.contains(&w.as_str()))
+}
+
+fn share(values: &[&str], matches: impl Fn(&str) -> bool) -> f64 {
+    values.iter().filter(|v| matches(v)).count() as f64 / values.len() as f64
+}
+
+fn is_email(value: &str) -> bool {
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !value.contains(char::is_whitespace)
+                && !domain.contains('@')
+                && domain
+                    .split_once('.')
+                    .is_some_and(|(host, tld)| !host.is_empty() && !tld.is_empty())
+        }
+        None => false,
+    }
+}
+
+fn is_url(value: &str) -> bool {
+    let lower = value.to_ascii_lowercase();
+    ["http://", "https://", "www."]
+        .iter()
+        .any(|prefix| lower.starts_with(prefix) && lower.len() > prefix.len())
+        && !value.contains(char::is_whitespace)
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn is_us_state(value: &str) -> bool {
+    let lower = value.to_lowercase();
+    US_STATES
+        .iter()
+        .any(|(code, name)| value == *code || lower == *name)
+}
+
+fn is_country_code(value: &str) -> bool {
+    match value.len() {
+        2 => COUNTRY_CODES_2.split_whitespace().any(|code| code == value),
+        3 => COUNTRY_CODES_3.split_whitespace().any(|code| code == value),
+        _ => false,
+    }
+}
+
+/// Postal codes around the world are short runs of digits and capitals,
+/// sometimes split by a space or dash, as in `10115`, `SW1A 1AA` or `02139-4307`.
+fn is_postal_code(value: &str) -> bool {
+    (3..=10).contains(&value.len())
+        && value.chars().any(|c| c.is_ascii_digit())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase() || c == ' ' || c == '-')
+}
+
+fn word_count(value: &str) -> usize {
+    value.split_whitespace().count()
+}
+
+/// The tag implied by the format a column's numbers were written in, for
+/// columns such as `$1,234.50` or `12.5%` parsed into floats.
+pub fn from_number_format(format: &NumberFormat) -> Option<SemanticType> {
+    let affixes = format!("{}{}", format.prefix, format.suffix);
+    if affixes.contains('%') {
+        Some(SemanticType::Percentage)
+    } else if affixes.contains(numeric::CURRENCY_SYMBOLS)
+        || (affixes.trim().len() == 3 && affixes.trim().chars().all(|c| c.is_ascii_uppercase()))
+    {
+        Some(SemanticType::Currency)
+    } else {
+        None
+    }
+}
+
+/// Tags the columns of a table that were parsed from formatted numbers.
+pub fn apply_number_formats(columns: &mut [ColumnInfo], formats: &NumberFormats) {
+    for column in columns {
+        if let Some(semantic) = formats.get(&column.name).and_then(from_number_format) {
+            column.semantic_type = Some(semantic);
+        }
+    }
+}
+
+fn detect_numeric(words: &[String], series: &Series) -> Option<SemanticType> {
+    let values = series.cast(&DataType::Float64).ok()?;
+    let values = values.f64().ok()?;
+    let within = |limit: f64| values.into_iter().flatten().all(|v| v.abs() <= limit);
+    let integer = series.dtype().is_integer();
+
+    if has_word(words, &LATITUDE_WORDS) && within(90.0) {
+        Some(SemanticType::Latitude)
+    } else if has_word(words, &LONGITUDE_WORDS) && within(180.0) {
+        Some(SemanticType::Longitude)
+    } else if has_word(words, &PERCENT_WORDS) {
+        Some(SemanticType::Percentage)
+    } else if integer && has_word(words, &POSTAL_WORDS) {
+        Some(SemanticType::PostalCode)
+    } else if integer && is_identifier_name(words) {
+        Some(SemanticType::Identifier)
+    } else {
+        None
+    }
+}
+
+fn detect_text(words: &[String], values: &[&str]) -> Option<SemanticType> {
+    let matches = |test: fn(&str) -> bool| share(values, test) >= MIN_MATCH_SHARE;
+    let distinct: HashSet<&str> = values.iter().copied().collect();
+
+    if matches(is_email) {
+        return Some(SemanticType::Email);
+    }
+    if matches(is_url) {
+        return Some(SemanticType::Url);
+    }
+    if matches(is_uuid) {
+        return Some(SemanticType::Identifier);
+    }
+    if let Some(semantic) = numeric::detect_column(values, None)
+        .as_ref()
+        .and_then(from_number_format)
+    {
+        return Some(semantic);
+    }
+    if has_word(words, &POSTAL_WORDS) && matches(is_postal_code) {
+        return Some(SemanticType::PostalCode);
+    }
+
+    // Most state abbreviations are also country codes; the name decides.
+    let country_name = has_word(words, &COUNTRY_WORDS);
+    if !country_name && matches(is_us_state) {
+        return Some(SemanticType::UsState);
+    }
+    if matches(is_country_code) && (country_name || distinct.len() >= 3) {
+        return Some(SemanticType::CountryCode);
+    }
+
+    if is_identifier_name(words) {
+        return Some(SemanticType::Identifier);
+    }
+    // Unique codes such as `INV-00042`: no spaces, with digits.
+    if values.len() >= 10
+        && distinct.len() == values.len()
+        && values.iter().all(|v| {
+            v.len() <= 40
+                && !v.contains(char::is_whitespace)
+                && v.contains(|c: char| c.is_ascii_digit())
+        })
+    {
+        return Some(SemanticType::Identifier);
+    }
+
+    let mean_words =
+        values.iter().map(|v| word_count(v)).sum::<usize>() as f64 / values.len() as f64;
+    if mean_words >= FREE_TEXT_WORDS {
+        return Some(SemanticType::FreeText);
+    }
+
+    None
+}
+
+/// Detects what a column's values mean from its first `SAMPLE_SIZE` non-null
+/// values. Returns `None` when nothing matches well enough.
+pub fn detect(series: &Series) -> Option<SemanticType> {
+    let words = name_words(series.name());
+    let sample = series.drop_nulls().head(Some(SAMPLE_SIZE));
+    if sample.is_empty() {
+        return None;
+    }
+
+    match sample.dtype() {
+        dtype if dtype.is_numeric() => detect_numeric(&words, &sample),
+        DataType::String => {
+            let ca = sample.str().ok()?;
+            let values: Vec<&str> = ca
+                .into_iter()
+                .flatten()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .collect();
+            if values.is_empty() {
+                None
+            } else {
+                detect_text(&words, &values)
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect_text_column(name: &str, values: &[&str]) -> Option<SemanticType> {
+        detect(&Series::new(name.into(), values))
+    }
+
+    #[test]
+    fn test_name_words_split_case_and_separators() {
+        assert_eq!(name_words("orderID"), ["order", "id"]);
+        assert_eq!(name_words("customer_zip-code"), ["customer", "zip", "code"]);
+        assert_eq!(name_words("Lat"), ["lat"]);
+    }
+
+    #[test]
+    fn test_text_columns_are_tagged_by_pattern_and_dictionary() {
+        assert_eq!(
+            detect_text_column("contact", &["ana@example.com", "li@example.org"]),
+            Some(SemanticType::Email)
+        );
+        assert_eq!(
+            detect_text_column("site", &["https://example.com", "www.example.org/a"]),
+            Some(SemanticType::Url)
+        );
+        assert_eq!(
+            detect_text_column("state", &["CA", "NY", "Texas", "WA"]),
+            Some(SemanticType::UsState)
+        );
+        assert_eq!(
+            detect_text_column("country", &["CA", "DE", "FR"]),
+            Some(SemanticType::CountryCode)
+        );
+        assert_eq!(
+            detect_text_column("zip", &["02139", "SW1A 1AA", "10115"]),
+            Some(SemanticType::PostalCode)
+        );
+        assert_eq!(
+            detect_text_column("price", &["$12.50", "$1,200.00"]),
+            Some(SemanticType::Currency)
+        );
+        assert_eq!(
+            detect_text_column(
+                "notes",
+                &[
+                    "Customer asked for a refund after delivery",
+                    "Left at the front door as asked"
+                ]
+            ),
+            Some(SemanticType::FreeText)
+        );
+        assert_eq!(
+            detect_text_column("region", &["North", "South", "East"]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_numeric_ids_and_coordinates_are_tagged_by_name() {
+        let order_id = Series::new("order_id".into(), &[1001i64, 1002, 1002]);
+        let lat = Series::new("lat".into(), &[48.85, -33.86]);
+        let lng = Series::new("lng".into(), &[2.35, 151.2]);
+        let amount = Series::new("amount".into(), &[10i64, 20]);
+
+        assert_eq!(detect(&order_id), Some(SemanticType::Identifier));
+        assert_eq!(detect(&lat), Some(SemanticType::Latitude));
+        assert_eq!(detect(&lng), Some(SemanticType::Longitude));
+        assert_eq!(detect(&amount), None);
+        assert!(!SemanticType::Identifier.is_additive());
+    }
+}
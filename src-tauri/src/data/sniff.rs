@@ -0,0 +1,286 @@
+//! # CSV Dialect Sniffing
+//!
+//! Detects the encoding, delimiter, quote character, header presence and
+//! leading junk rows of a delimited text file from a sample of its first bytes.
+
+use crate::data::archive;
+use crate::data::types::{CsvImportOptions, TextEncoding};
+use encoding_rs::{UTF_16BE, UTF_16LE, WINDOWS_1252};
+use std::borrow::Cow;
+use std::io::{self, Read};
+
+/// Bytes read from the start of a file for sniffing and previews.
+pub const SAMPLE_BYTES: usize = 64 * 1024;
+
+/// Maximum number of lines inspected when sniffing.
+const SAMPLE_LINES: usize = 200;
+
+const CANDIDATE_DELIMITERS: [char; 4] = [',', ';', '\t', '|'];
+
+const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+
+/// Reads the first bytes of a file, decompressing compressed files.
+pub fn read_sample(path: &str) -> io::Result<Vec<u8>> {
+    let mut sample = Vec::with_capacity(SAMPLE_BYTES);
+    archive::open(path)?
+        .take(SAMPLE_BYTES as u64)
+        .read_to_end(&mut sample)?;
+    Ok(sample)
+}
+
+pub fn detect_encoding(bytes: &[u8]) -> TextEncoding {
+    if bytes.starts_with(&[0xFF, 0xFE]) {
+        return TextEncoding::Utf16Le;
+    }
+    if bytes.starts_with(&[0xFE, 0xFF]) {
+        return TextEncoding::Utf16Be;
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(_) => TextEncoding::Utf8,
+        // The sample may end in the middle of a multi-byte character.
+        Err(e) if e.error_len().is_none() => TextEncoding::Utf8,
+        Err(_) => TextEncoding::Windows1252,
+    }
+}
+
+/// Decodes text in the given encoding, dropping any byte-order mark.
+pub fn decode(bytes: &[u8], encoding: TextEncoding) -> Cow<'_, str> {
+    match encoding {
+        TextEncoding::Utf8 => {
+            String::from_utf8_lossy(bytes.strip_prefix(&UTF8_BOM).unwrap_or(bytes))
+        }
+        TextEncoding::Utf16Le => UTF_16LE.decode_with_bom_removal(bytes).0,
+        TextEncoding::Utf16Be => UTF_16BE.decode_with_bom_removal(bytes).0,
+        TextEncoding::Windows1252 => WINDOWS_1252.decode_without_bom_handling(bytes).0,
+    }
+}
+
+/// Sniffs import options from the first bytes of a file.
+pub fn sniff_options(sample: &[u8]) -> CsvImportOptions {
+    let encoding = detect_encoding(sample);
+    let text = decode(sample, encoding);
+    let lines = sample_lines(&text, sample.len() >= SAMPLE_BYTES);
+
+    if lines.is_empty() {
+        return CsvImportOptions {
+            encoding,
+            ..Default::default()
+        };
+    }
+
+    let quote_char = detect_quote_char(&lines);
+    let delimiter = detect_delimiter(&lines, quote_char);
+
+    let rows: Vec<Vec<String>> = lines
+        .iter()
+        .map(|line| split_fields(line, &delimiter, quote_char))
+        .collect();
+    let skip_rows = detect_skip_rows(&rows);
+    let has_header = detect_header(&rows[skip_rows..]);
+
+    CsvImportOptions {
+        delimiter,
+        quote_char,
+        has_header,
+        skip_rows,
+        encoding,
+    }
+}
+
+/// Splits sample text into non-empty lines. When the sample was cut off, the
+/// last line is likely incomplete and is dropped.
+pub(crate) fn sample_lines(text: &str, truncated: bool) -> Vec<&str> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    if truncated && lines.len() > 1 && !text.ends_with('\n') {
+        lines.pop();
+    }
+    lines
+        .into_iter()
+        .filter(|line| !line.trim().is_empty())
+        .take(SAMPLE_LINES)
+        .collect()
+}
+
+/// Splits a line into fields, honouring quoted sections and doubled quotes.
+/// The delimiter may be several characters long, as in `||`.
+pub fn split_fields(line: &str, delimiter: &str, quote_char: Option<char>) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if Some(c) == quote_char {
+            if in_quotes && chars.peek().map(|&(_, next)| next) == Some(c) {
+                current.push(c);
+                chars.next();
+            } else {
+                in_quotes = !in_quotes;
+            }
+        } else if !in_quotes && !delimiter.is_empty() && line[i..].starts_with(delimiter) {
+            fields.push(std::mem::take(&mut current));
+            // Skip the rest of the delimiter.
+            while chars.peek().is_some_and(|&(j, _)| j < i + delimiter.len()) {
+                chars.next();
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    fields.push(current);
+
+    fields
+}
+
+fn detect_quote_char(lines: &[&str]) -> Option<char> {
+    let quoted_fields = |quote: char| {
+        lines
+            .iter()
+            .flat_map(|line| line.split(|c| CANDIDATE_DELIMITERS.contains(&c)))
+            .filter(|field| {
+                let field = field.trim();
+                field.len() >= 2 && field.starts_with(quote) && field.ends_with(quote)
+            })
+            .count()
+    };
+
+    if quoted_fields('\'') > quoted_fields('"') {
+        Some('\'')
+    } else {
+        Some('"')
+    }
+}
+
+/// Picks the delimiter that splits the most lines into the same number of
+/// fields. Files with no candidate delimiter are treated as a single column.
+fn detect_delimiter(lines: &[&str], quote_char: Option<char>) -> String {
+    let mut best = (',', 0.0, 0);
+
+    for delimiter in CANDIDATE_DELIMITERS {
+        let counts: Vec<usize> = lines
+            .iter()
+            .map(|line| split_fields(line, &delimiter.to_string(), quote_char).len())
+            .collect();
+        let (mode, frequency) = mode(&counts);
+        if mode < 2 {
+            continue;
+        }
+
+        let consistency = frequency as f64 / counts.len() as f64;
+        if consistency > best.1 || (consistency == best.1 && mode > best.2) {
+            best = (delimiter, consistency, mode);
+        }
+    }
+
+    best.0.to_string()
+}
+
+/// Title and blank-ish lines before the real header have a different field
+/// count than the table body. Skip until the first line matching the body.
+fn detect_skip_rows(rows: &[Vec<String>]) -> usize {
+    let counts: Vec<usize> = rows.iter().map(|row| row.len()).collect();
+    let (body_width, _) = mode(&counts);
+
+    counts
+        .iter()
+        .position(|&count| count == body_width)
+        .unwrap_or(0)
+}
+
+/// A header is assumed unless the first row looks like data: a header cell is
+/// text where the values below it are numeric.
+pub(crate) fn detect_header(rows: &[Vec<String>]) -> bool {
+    let Some((first, body)) = rows.split_first() else {
+        return true;
+    };
+    if body.is_empty() {
+        return true;
+    }
+
+    let mut votes = 0i32;
+    for (idx, cell) in first.iter().enumerate() {
+        let values: Vec<&str> = body
+            .iter()
+            .filter_map(|row| row.get(idx))
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .collect();
+        if values.is_empty() || !values.iter().all(|v| is_numeric(v)) {
+            continue;
+        }
+
+        if is_numeric(cell.trim()) {
+            votes -= 1;
+        } else {
+            votes += 1;
+        }
+    }
+
+    votes >= 0
+}
+
+fn is_numeric(value: &str) -> bool {
+    value.parse::<f64>().is_ok()
+}
+
+/// Most common value and how often it occurs. Ties favour the larger value.
+pub(crate) fn mode(values: &[usize]) -> (usize, usize) {
+    let mut counts = std::collections::HashMap::new();
+    for &v in values {
+        *counts.entry(v).or_insert(0usize) += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(value, frequency)| (frequency, value))
+        .unwrap_or((0, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_semicolon_delimiter() {
+        let sample = b"name;amount;city\nAnna;1,50;Berlin\nJonas;2,75;Hamburg\n";
+        let options = sniff_options(sample);
+
+        assert_eq!(options.delimiter, ";");
+        assert!(options.has_header);
+        assert_eq!(options.skip_rows, 0);
+        assert_eq!(options.encoding, TextEncoding::Utf8);
+    }
+
+    #[test]
+    fn test_detects_title_rows_and_missing_header() {
+        let sample = b"Quarterly report\nGenerated 2024-01-01\n1\t10.5\t3\n2\t11.0\t4\n3\t9.5\t5\n";
+        let options = sniff_options(sample);
+
+        assert_eq!(options.delimiter, "\t");
+        assert_eq!(options.skip_rows, 2);
+        assert!(!options.has_header);
+    }
+
+    #[test]
+    fn test_detects_encodings() {
+        assert_eq!(detect_encoding(b"caf\xe9,1\n"), TextEncoding::Windows1252);
+        assert_eq!(detect_encoding("café,1\n".as_bytes()), TextEncoding::Utf8);
+
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend("a,b\n1,2\n".encode_utf16().flat_map(|u| u.to_le_bytes()));
+        assert_eq!(detect_encoding(&utf16), TextEncoding::Utf16Le);
+        assert_eq!(decode(&utf16, TextEncoding::Utf16Le), "a,b\n1,2\n");
+    }
+
+    #[test]
+    fn test_split_fields_respects_quotes() {
+        let fields = split_fields(r#"1,"Smith, John","He said ""hi""""#, ",", Some('"'));
+        assert_eq!(fields, vec!["1", "Smith, John", r#"He said "hi""#]);
+    }
+
+    #[test]
+    fn test_split_fields_with_multi_character_delimiter() {
+        let fields = split_fields(r#"1||"a||b"||c|d"#, "||", Some('"'));
+        assert_eq!(fields, vec!["1", "a||b", "c|d"]);
+    }
+}
@@ -0,0 +1,210 @@
+//! # SQLite Reading
+//!
+//! Reads tables, views and custom SELECT queries from SQLite database files.
+//! Databases are opened read-only. Column types come from the stored values,
+//! with the declared type used to recognise boolean columns.
+
+use crate::data::excel;
+use crate::data::types::SqliteObject;
+use crate::error::DataError;
+use polars::prelude::*;
+use rusqlite::types::Value;
+use rusqlite::{Connection, OpenFlags};
+
+pub fn open(path: &str) -> Result<Connection, DataError> {
+    Ok(Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?)
+}
+
+/// Tables and views in the database, excluding SQLite's internal tables.
+pub fn list_objects(conn: &Connection) -> Result<Vec<SqliteObject>, DataError> {
+    let mut stmt = conn.prepare(
+        "SELECT name, type FROM sqlite_master \
+         WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' \
+         ORDER BY type, name",
+    )?;
+    let objects = stmt
+        .query_map([], |row| {
+            Ok(SqliteObject {
+                name: row.get(0)?,
+                kind: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(objects)
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Reads a whole table or view. The name must be one listed by `list_objects`.
+pub fn read_table(conn: &Connection, name: &str) -> Result<DataFrame, DataError> {
+    if !list_objects(conn)?.iter().any(|o| o.name == name) {
+        return Err(DataError::ParseError(format!(
+            "Table '{}' not found in database",
+            name
+        )));
+    }
+
+    read_query(conn, &format!("SELECT * FROM {}", quote_identifier(name)))
+}
+
+fn is_boolean_decl(decl_type: Option<&str>) -> bool {
+    decl_type.is_some_and(|t| t.to_ascii_uppercase().contains("BOOL"))
+}
+
+/// Types a result column from its values. Any text makes the column text;
+/// integers and reals together make it float.
+fn column_series(name: &str, decl_type: Option<&str>, values: &[Value]) -> Series {
+    let name: PlSmallStr = name.into();
+    let present = || values.iter().filter(|v| !matches!(v, Value::Null));
+
+    if present().next().is_none() {
+        return Series::new_null(name, values.len());
+    }
+
+    if present().any(|v| matches!(v, Value::Text(_) | Value::Blob(_))) {
+        let text: Vec<Option<String>> = values
+            .iter()
+            .map(|v| match v {
+                Value::Integer(i) => Some(i.to_string()),
+                Value::Real(f) => Some(f.to_string()),
+                Value::Text(s) => Some(s.clone()),
+                Value::Blob(b) => String::from_utf8(b.clone()).ok(),
+                Value::Null => None,
+            })
+            .collect();
+        return Series::new(name, text);
+    }
+
+    if present().all(|v| matches!(v, Value::Integer(_))) {
+        let booleans = is_boolean_decl(decl_type)
+            && present().all(|v| matches!(v, Value::Integer(0) | Value::Integer(1)));
+        if booleans {
+            let flags: Vec<Option<bool>> = values
+                .iter()
+                .map(|v| match v {
+                    Value::Integer(i) => Some(*i != 0),
+                    _ => None,
+                })
+                .collect();
+            return Series::new(name, flags);
+        }
+
+        let integers: Vec<Option<i64>> = values
+            .iter()
+            .map(|v| match v {
+                Value::Integer(i) => Some(*i),
+                _ => None,
+            })
+            .collect();
+        return Series::new(name, integers);
+    }
+
+    let floats: Vec<Option<f64>> = values
+        .iter()
+        .map(|v| match v {
+            Value::Integer(i) => Some(*i as f64),
+            Value::Real(f) => Some(*f),
+            _ => None,
+        })
+        .collect();
+    Series::new(name, floats)
+}
+
+/// Runs a read-only query and collects its result. Statements that would
+/// modify the database are rejected before they run. Repeated column names,
+/// as in a join selecting `id` from both sides, get `_2`, `_3`, ... suffixes.
+pub fn read_query(conn: &Connection, sql: &str) -> Result<DataFrame, DataError> {
+    let mut stmt = conn.prepare(sql)?;
+    if !stmt.readonly() {
+        return Err(DataError::ParseError(
+            "Only SELECT queries can be used to load data".to_string(),
+        ));
+    }
+
+    let names = excel::unique_headers(stmt.column_names().into_iter().map(String::from).collect());
+    let columns: Vec<(String, Option<String>)> = names
+        .into_iter()
+        .zip(stmt.columns())
+        .map(|(name, c)| (name, c.decl_type().map(str::to_string)))
+        .collect();
+    let mut values: Vec<Vec<Value>> = vec![Vec::new(); columns.len()];
+
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        for (idx, column) in values.iter_mut().enumerate() {
+            column.push(row.get::<_, Value>(idx)?);
+        }
+    }
+
+    let series = columns
+        .iter()
+        .zip(&values)
+        .map(|((name, decl_type), values)| column_series(name, decl_type.as_deref(), values))
+        .collect();
+
+    Ok(DataFrame::new(series)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_test_db() -> (TempDir, String) {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("orders.db");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE orders (id INTEGER, region TEXT, amount REAL, paid BOOLEAN);
+             INSERT INTO orders VALUES (1, 'North', 12.5, 1), (2, 'South', 30, 0), (3, NULL, NULL, 1);
+             CREATE VIEW big_orders AS SELECT * FROM orders WHERE amount > 20;",
+        )
+        .unwrap();
+        (dir, path.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn test_lists_tables_and_views() {
+        let (_dir, path) = create_test_db();
+        let conn = open(&path).unwrap();
+
+        let objects = list_objects(&conn).unwrap();
+        let names: Vec<(&str, &str)> = objects
+            .iter()
+            .map(|o| (o.name.as_str(), o.kind.as_str()))
+            .collect();
+        assert_eq!(names, [("orders", "table"), ("big_orders", "view")]);
+    }
+
+    #[test]
+    fn test_read_table_keeps_column_types() {
+        let (_dir, path) = create_test_db();
+        let conn = open(&path).unwrap();
+
+        let df = read_table(&conn, "orders").unwrap();
+        assert_eq!(df.height(), 3);
+        assert_eq!(df.column("id").unwrap().dtype(), &DataType::Int64);
+        assert_eq!(df.column("region").unwrap().dtype(), &DataType::String);
+        assert_eq!(df.column("amount").unwrap().dtype(), &DataType::Float64);
+        assert_eq!(df.column("paid").unwrap().dtype(), &DataType::Boolean);
+
+        let df = read_query(&conn, "SELECT region, amount FROM big_orders").unwrap();
+        assert_eq!(df.height(), 1);
+
+        let df = read_query(
+            &conn,
+            "SELECT o.id, b.id, o.amount FROM orders o JOIN big_orders b ON o.id = b.id",
+        )
+        .unwrap();
+        assert_eq!(df.get_column_names(), ["id", "id_2", "amount"]);
+
+        assert!(read_table(&conn, "missing").is_err());
+        assert!(read_query(&conn, "DELETE FROM orders").is_err());
+    }
+}
@@ -1,12 +1,280 @@
+use crate::data::types::{
+    CalculatedField, ColumnInfo, DateFormats, Derivation, DerivedTable, LoadOptions, NumberFormats,
+    SchemaOverrides, SourceFormat, SourceInfo, TransformStep,
+};
+use crate::data::{semantic, transform};
+use crate::error::DataError;
 use polars::prelude::*;
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+/// Row count of a table that is not in memory, counted the first time it is
+/// asked for. Copies of a table share it. A table is replaced rather than
+/// changed when its data changes, so a count belongs to one table version.
+#[derive(Clone, Default)]
+pub struct RowCount(Arc<OnceLock<usize>>);
+
+impl RowCount {
+    fn counted(rows: usize) -> Self {
+        Self(Arc::new(OnceLock::from(rows)))
+    }
+
+    fn get_or_count(&self, lf: LazyFrame) -> PolarsResult<usize> {
+        if let Some(rows) = self.0.get() {
+            return Ok(*rows);
+        }
+        let rows = count_rows(lf)?;
+        Ok(*self.0.get_or_init(|| rows))
+    }
+}
+
+/// A registered table. Scans stay on disk and are only read when a query
+/// collects them, so files larger than memory can still be charted.
+#[derive(Clone)]
+pub enum Table {
+    Materialized(DataFrame),
+    Scan {
+        lf: Box<LazyFrame>,
+        rows: RowCount,
+    },
+    /// Source data with transformation steps and calculated fields applied,
+    /// built by `transform::build_view`. Views of data in memory are
+    /// collected once by `materialize`, so `plan` then scans the result.
+    Transformed {
+        base: Box<Table>,
+        plan: Box<LazyFrame>,
+        rows: RowCount,
+    },
+}
+
+impl Table {
+    pub fn scan(lf: LazyFrame) -> Self {
+        Table::Scan {
+            lf: Box::new(lf),
+            rows: RowCount::default(),
+        }
+    }
+
+    pub fn lazy(&self) -> LazyFrame {
+        match self {
+            Table::Materialized(df) => df.clone().lazy(),
+            Table::Scan { lf, .. } => lf.as_ref().clone().with_streaming(true),
+            Table::Transformed { plan, .. } => plan.as_ref().clone(),
+        }
+    }
+
+    pub fn schema(&self) -> PolarsResult<SchemaRef> {
+        match self {
+            Table::Materialized(df) => Ok(Arc::new(df.schema())),
+            Table::Scan { lf, .. } => lf.as_ref().clone().collect_schema(),
+            Table::Transformed { plan, .. } => plan.as_ref().clone().collect_schema(),
+        }
+    }
+
+    /// Runs the steps and fields of a view of in-memory data, so they are not
+    /// run again each time the table is read. Views of scans stay lazy, and a
+    /// view that cannot be collected is kept as it is.
+    pub fn materialize(self) -> Table {
+        match self {
+            Table::Transformed { base, plan, rows } if !base.is_scan() => {
+                match plan.as_ref().clone().collect() {
+                    Ok(df) => Table::Transformed {
+                        base,
+                        rows: RowCount::counted(df.height()),
+                        plan: Box::new(df.lazy()),
+                    },
+                    Err(_) => Table::Transformed { base, plan, rows },
+                }
+            }
+            table => table,
+        }
+    }
+
+    /// The table's source data, without steps or calculated fields.
+    pub fn base(&self) -> &Table {
+        match self {
+            Table::Transformed { base, .. } => base,
+            _ => self,
+        }
+    }
+
+    /// Descriptions of the table's columns. Scans are not read to look for
+    /// nulls, so their columns are all reported as nullable, and semantic
+    /// types are detected from their first rows only.
+    pub fn columns(&self) -> PolarsResult<Vec<ColumnInfo>> {
+        match self {
+            Table::Materialized(df) => Ok(frame_columns(df)),
+            Table::Transformed { base, .. } if !base.is_scan() => {
+                Ok(frame_columns(&self.collect()?))
+            }
+            _ => {
+                let sample = self
+                    .lazy()
+                    .limit(semantic::SAMPLE_SIZE as IdxSize)
+                    .collect()?;
+                Ok(frame_columns(&sample)
+                    .into_iter()
+                    .map(|column| ColumnInfo {
+                        nullable: true,
+                        ..column
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Number of rows. Tables not in memory are counted once, so for scans
+    /// the file is only read through the first time.
+    pub fn row_count(&self) -> PolarsResult<usize> {
+        match self {
+            Table::Materialized(df) => Ok(df.height()),
+            Table::Scan { rows, .. } | Table::Transformed { rows, .. } => {
+                rows.get_or_count(self.lazy())
+            }
+        }
+    }
+
+    /// Materializes the table. For scans this reads the whole file.
+    pub fn collect(&self) -> PolarsResult<DataFrame> {
+        match self {
+            Table::Materialized(df) => Ok(df.clone()),
+            _ => self.lazy().collect(),
+        }
+    }
+
+    pub fn is_scan(&self) -> bool {
+        matches!(self.base(), Table::Scan { .. })
+    }
+}
+
+/// Descriptions of a frame's columns, nullable where a column holds nulls
+/// and tagged with the semantic type detected from their values.
+pub fn frame_columns(df: &DataFrame) -> Vec<ColumnInfo> {
+    df.get_columns()
+        .iter()
+        .map(|column| {
+            let mut info = ColumnInfo::new(column.name(), column.dtype(), column.null_count() > 0);
+            info.semantic_type = semantic::detect(column);
+            info
+        })
+        .collect()
+}
+
+/// Counts the rows a lazy query would produce without materializing them.
+pub fn count_rows(lf: LazyFrame) -> PolarsResult<usize> {
+    let counted = lf.select([len()]).collect()?;
+    counted.get_columns()[0]
+        .get(0)?
+        .extract::<usize>()
+        .ok_or_else(|| polars_err!(ComputeError: "row count is not an integer"))
+}
+
+/// A file loaded into the workspace. Its tables are registered in
+/// `DataState::tables` under names qualified by the source name.
+#[derive(Debug, Clone)]
+pub struct DataSource {
+    pub name: String,
+    pub path: String,
+    pub format: SourceFormat,
+    /// Qualified names of the tables read from the file, in file order.
+    pub tables: Vec<String>,
+    /// Type overrides the file was loaded with, by file column. Loaders apply
+    /// them before any step, so a column renamed by a step keeps its type.
+    pub schema_overrides: SchemaOverrides,
+    /// Loader options, for reading the file again.
+    pub load: LoadOptions,
+    /// End of the last complete line read so far, for CSV and NDJSON files
+    /// that can have rows appended to them.
+    pub read_offset: Option<u64>,
+    /// Whether the last row of the table was read from a line past
+    /// `read_offset` that had no newline yet. The next append reads that line
+    /// again, whole, and replaces the row.
+    pub partial_row: bool,
+}
+
+impl DataSource {
+    pub fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            format: self.format,
+            tables: self.tables.clone(),
+        }
+    }
+}
+
+/// Separates the source name from the table name in qualified table names,
+/// as in `budget/Q1` for sheet `Q1` of `budget.xlsx`.
+pub const TABLE_SEPARATOR: char = '/';
+
+/// Name under which a table of a source is registered. Files with a single
+/// table register it under the source name alone.
+pub fn qualified_table_name(source: &str, table: &str, single: bool) -> String {
+    if single {
+        source.to_string()
+    } else {
+        format!("{}{}{}", source, TABLE_SEPARATOR, table)
+    }
+}
+
+/// Number of changes to a table's steps that can be undone.
+const UNDO_LIMIT: usize = 100;
+
+/// A table's transformation steps, with the step lists undo and redo return
+/// to, most recent last.
+#[derive(Debug, Clone, Default)]
+pub struct Pipeline {
+    pub steps: Vec<TransformStep>,
+    undo: Vec<Vec<TransformStep>>,
+    redo: Vec<Vec<TransformStep>>,
+}
+
+impl Pipeline {
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+/// The first field failing in `after` that did not fail in `before`.
+fn newly_failed<'a>(
+    before: &[(String, DataError)],
+    after: &'a [(String, DataError)],
+) -> Option<&'a str> {
+    after
+        .iter()
+        .map(|(field, _)| field.as_str())
+        .find(|field| !before.iter().any(|(f, _)| f == field))
+}
 
 pub struct DataState {
-    pub tables: HashMap<String, DataFrame>,
+    pub tables: HashMap<String, Table>,
     pub table_order: Vec<String>,
     pub active_table: Option<String>,
-    pub file_path: Option<String>,
+    /// Loaded files, in load order.
+    pub sources: Vec<DataSource>,
+    /// Original formats of numeric columns parsed from text, by table and
+    /// source column. See `view_number_formats`.
+    pub number_formats: HashMap<String, NumberFormats>,
+    /// Formats of date columns parsed from text, by table, so rows appended
+    /// later are parsed the same way.
+    pub date_formats: HashMap<String, DateFormats>,
+    /// Calculated fields by table, in definition order. They are kept when a
+    /// table is reloaded and applied again to its new data, as are steps.
+    pub calculated_fields: HashMap<String, Vec<CalculatedField>>,
+    /// Transformation steps by table.
+    pub pipelines: HashMap<String, Pipeline>,
+    /// How each derived table was computed, in the order they were, so later
+    /// ones can be computed from earlier ones again.
+    pub derived_tables: Vec<DerivedTable>,
+    /// Version of each table's data, changed whenever the data is replaced so
+    /// results derived from it, such as column profiles, can be cached.
+    versions: HashMap<String, u64>,
+    next_version: u64,
 }
 
 impl DataState {
@@ -15,15 +283,245 @@ impl DataState {
             tables: HashMap::new(),
             table_order: Vec::new(),
             active_table: None,
-            file_path: None,
+            sources: Vec::new(),
+            number_formats: HashMap::new(),
+            date_formats: HashMap::new(),
+            calculated_fields: HashMap::new(),
+            pipelines: HashMap::new(),
+            derived_tables: Vec::new(),
+            versions: HashMap::new(),
+            next_version: 0,
         }
     }
 
     pub fn add_dataframe(&mut self, name: String, df: DataFrame) {
+        self.add_table(name, Table::Materialized(df));
+    }
+
+    pub fn add_scan(&mut self, name: String, lf: LazyFrame) {
+        self.add_table(name, Table::scan(lf));
+    }
+
+    fn add_table(&mut self, name: String, table: Table) {
         if !self.tables.contains_key(&name) {
             self.table_order.push(name.clone());
         }
-        self.tables.insert(name, df);
+        let table = self.view(&name, table);
+        self.bump_version(&name);
+        self.tables.insert(name, table);
+    }
+
+    /// Applies a table's steps and calculated fields to its source data.
+    /// Those that no longer apply, say because a reload dropped a column they
+    /// use, are left out but kept, so they return when the column does.
+    fn view(&self, name: &str, base: Table) -> Table {
+        let steps = self.get_steps(name);
+        transform::build_view(base, steps, self.get_calculated_fields(name))
+            .table
+            .materialize()
+    }
+
+    fn bump_version(&mut self, name: &str) {
+        self.next_version += 1;
+        self.versions.insert(name.to_string(), self.next_version);
+    }
+
+    fn remove_table(&mut self, name: &str) {
+        self.tables.remove(name);
+        self.table_order.retain(|t| t != name);
+        self.number_formats.remove(name);
+        self.date_formats.remove(name);
+        self.derived_tables.retain(|t| t.name != name);
+        self.versions.remove(name);
+        if self.active_table.as_deref() == Some(name) {
+            self.active_table = None;
+        }
+    }
+
+    /// Source name derived from a file name, made unique with a numeric
+    /// suffix. Names containing the table separator are not allowed.
+    fn unique_source_name(&self, stem: &str) -> String {
+        let base = match stem.replace(TABLE_SEPARATOR, "_").trim() {
+            "" => "source".to_string(),
+            trimmed => trimmed.to_string(),
+        };
+        let taken = |name: &str| {
+            self.sources.iter().any(|s| s.name == name) || self.tables.contains_key(name)
+        };
+
+        let mut name = base.clone();
+        let mut n = 2;
+        while taken(&name) {
+            name = format!("{}_{}", base, n);
+            n += 1;
+        }
+        name
+    }
+
+    /// Adds a file and its tables to the workspace and returns the source
+    /// name. Loading a path that is already open replaces that source and
+    /// keeps its name, so reloading a file does not create a duplicate.
+    pub fn add_source(
+        &mut self,
+        path: String,
+        load: LoadOptions,
+        schema_overrides: SchemaOverrides,
+        tables: Vec<(String, Table, NumberFormats)>,
+    ) -> &DataSource {
+        let existing = self.sources.iter().position(|s| s.path == path);
+        let name = match existing {
+            Some(idx) => {
+                let old = self.sources[idx].clone();
+                for table in &old.tables {
+                    self.remove_table(table);
+                }
+                old.name
+            }
+            None => {
+                let stem = Path::new(&path)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("source");
+                self.unique_source_name(stem)
+            }
+        };
+
+        let single = tables.len() == 1;
+        let mut table_names = Vec::with_capacity(tables.len());
+        for (table_name, table, formats) in tables {
+            let qualified = qualified_table_name(&name, &table_name, single);
+            self.add_table(qualified.clone(), table);
+            self.set_number_formats(qualified.clone(), formats);
+            table_names.push(qualified);
+        }
+
+        let source = DataSource {
+            name,
+            path,
+            format: load.format(),
+            tables: table_names,
+            schema_overrides,
+            load,
+            read_offset: None,
+            partial_row: false,
+        };
+        let idx = match existing {
+            Some(idx) => {
+                self.sources[idx] = source;
+                idx
+            }
+            None => {
+                self.sources.push(source);
+                self.sources.len() - 1
+            }
+        };
+        &self.sources[idx]
+    }
+
+    /// Removes a source and its tables. If the active table belonged to it,
+    /// the first remaining table becomes active.
+    pub fn remove_source(&mut self, name: &str) -> Result<DataSource, String> {
+        let idx = self
+            .sources
+            .iter()
+            .position(|s| s.name == name)
+            .ok_or_else(|| format!("Source '{}' not found", name))?;
+        let source = self.sources.remove(idx);
+
+        for table in &source.tables {
+            self.remove_table(table);
+            self.calculated_fields.remove(table);
+            self.pipelines.remove(table);
+        }
+        if self.active_table.is_none() {
+            self.active_table = self.table_order.first().cloned();
+        }
+
+        Ok(source)
+    }
+
+    /// Renames a source and re-qualifies its table names.
+    pub fn rename_source(&mut self, name: &str, new_name: &str) -> Result<&DataSource, String> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() || new_name.contains(TABLE_SEPARATOR) {
+            return Err(format!(
+                "Source names must be non-empty and cannot contain '{}'",
+                TABLE_SEPARATOR
+            ));
+        }
+        let idx = self
+            .sources
+            .iter()
+            .position(|s| s.name == name)
+            .ok_or_else(|| format!("Source '{}' not found", name))?;
+        if new_name == name {
+            return Ok(&self.sources[idx]);
+        }
+        if self.sources.iter().any(|s| s.name == new_name) || self.tables.contains_key(new_name) {
+            return Err(format!("A source named '{}' already exists", new_name));
+        }
+
+        let old_tables = self.sources[idx].tables.clone();
+        let single = old_tables.len() == 1 && old_tables[0] == name;
+        let prefix = format!("{}{}", name, TABLE_SEPARATOR);
+        let mut new_tables = Vec::with_capacity(old_tables.len());
+
+        for old in old_tables {
+            let table_name = old.strip_prefix(&prefix).unwrap_or(&old);
+            let renamed = qualified_table_name(new_name, table_name, single);
+
+            if let Some(table) = self.tables.remove(&old) {
+                self.tables.insert(renamed.clone(), table);
+            }
+            if let Some(formats) = self.number_formats.remove(&old) {
+                self.number_formats.insert(renamed.clone(), formats);
+            }
+            if let Some(formats) = self.date_formats.remove(&old) {
+                self.date_formats.insert(renamed.clone(), formats);
+            }
+            if let Some(fields) = self.calculated_fields.remove(&old) {
+                self.calculated_fields.insert(renamed.clone(), fields);
+            }
+            if let Some(pipeline) = self.pipelines.remove(&old) {
+                self.pipelines.insert(renamed.clone(), pipeline);
+            }
+            if let Some(version) = self.versions.remove(&old) {
+                self.versions.insert(renamed.clone(), version);
+            }
+            if let Some(pos) = self.table_order.iter().position(|t| *t == old) {
+                self.table_order[pos] = renamed.clone();
+            }
+            if self.active_table.as_deref() == Some(old.as_str()) {
+                self.active_table = Some(renamed.clone());
+            }
+            for derived in &mut self.derived_tables {
+                derived.derivation.rename_table(&old, &renamed);
+            }
+            new_tables.push(renamed);
+        }
+
+        let source = &mut self.sources[idx];
+        source.name = new_name.to_string();
+        source.tables = new_tables;
+        Ok(source)
+    }
+
+    pub fn get_source(&self, name: &str) -> Option<&DataSource> {
+        self.sources.iter().find(|s| s.name == name)
+    }
+
+    /// The source a registered table was read from.
+    pub fn source_of_table(&self, table: &str) -> Option<&DataSource> {
+        self.sources
+            .iter()
+            .find(|s| s.tables.iter().any(|t| t == table))
+    }
+
+    /// The source of the active table.
+    pub fn active_source(&self) -> Option<&DataSource> {
+        self.active_table
+            .as_ref()
+            .and_then(|table| self.source_of_table(table))
     }
 
     pub fn set_active_table(&mut self, name: String) -> Result<(), String> {
@@ -35,31 +533,305 @@ impl DataState {
         }
     }
 
-    pub fn get_active_dataframe(&self) -> Option<&DataFrame> {
-        self.active_table.as_ref().and_then(|name| self.tables.get(name))
+    pub fn get_active_table(&self) -> Option<&Table> {
+        self.active_table
+            .as_ref()
+            .and_then(|name| self.tables.get(name))
     }
 
-    pub fn get_dataframe(&self, name: &str) -> Option<&DataFrame> {
+    pub fn get_table(&self, name: &str) -> Option<&Table> {
         self.tables.get(name)
     }
 
+    /// Current version of a table's data. Versions are never reused, even
+    /// across reloads and `clear`.
+    pub fn table_version(&self, name: &str) -> Option<u64> {
+        self.versions.get(name).copied()
+    }
+
     pub fn get_tables(&self) -> Vec<String> {
         self.table_order.clone()
     }
 
-    pub fn set_file_path(&mut self, path: String) {
-        self.file_path = Some(path);
+    /// Swaps the data of an existing table, as after new rows were appended.
+    pub fn replace_table(&mut self, name: &str, table: Table) -> Result<(), String> {
+        if !self.tables.contains_key(name) {
+            return Err(format!("Table '{}' not found", name));
+        }
+        let table = self.view(name, table);
+        self.tables.insert(name.to_string(), table);
+        self.bump_version(name);
+        Ok(())
     }
 
-    pub fn get_file_path(&self) -> Option<&String> {
-        self.file_path.as_ref()
+    pub fn get_calculated_fields(&self, table: &str) -> &[CalculatedField] {
+        self.calculated_fields
+            .get(table)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The table's source data.
+    fn base_table(&self, table: &str) -> Result<Table, DataError> {
+        self.get_table(table)
+            .map(|t| t.base().clone())
+            .ok_or_else(|| DataError::ParseError(format!("Table '{}' not found", table)))
+    }
+
+    fn store_calculated_fields(
+        &mut self,
+        table: &str,
+        fields: Vec<CalculatedField>,
+        calculated: Table,
+    ) {
+        self.tables
+            .insert(table.to_string(), calculated.materialize());
+        self.bump_version(table);
+        if fields.is_empty() {
+            self.calculated_fields.remove(table);
+        } else {
+            self.calculated_fields.insert(table.to_string(), fields);
+        }
+    }
+
+    /// Adds a calculated field to a table, or changes the formula of the
+    /// field of that name. Nothing changes if the formula is invalid.
+    pub fn set_calculated_field(
+        &mut self,
+        table: &str,
+        field: CalculatedField,
+    ) -> Result<(), DataError> {
+        let base = self.base_table(table)?;
+        let mut fields = self.get_calculated_fields(table).to_vec();
+        match fields.iter_mut().find(|f| f.name == field.name) {
+            Some(existing) => existing.formula = field.formula.clone(),
+            None => fields.push(field.clone()),
+        }
+
+        let view = transform::build_view(base, self.get_steps(table), &fields);
+        let mut failed = view.failed_fields.into_iter();
+        if let Some((_, e)) = failed.find(|(name, _)| *name == field.name) {
+            return Err(e);
+        }
+        self.store_calculated_fields(table, fields, view.table);
+        Ok(())
+    }
+
+    /// Removes a calculated field unless another field of the table uses it.
+    pub fn remove_calculated_field(&mut self, table: &str, name: &str) -> Result<(), DataError> {
+        let base = self.base_table(table)?;
+        let fields = self.get_calculated_fields(table);
+        if !fields.iter().any(|f| f.name == name) {
+            return Err(DataError::ParseError(format!(
+                "Calculated field '{}' not found",
+                name
+            )));
+        }
+        let remaining: Vec<CalculatedField> =
+            fields.iter().filter(|f| f.name != name).cloned().collect();
+
+        let steps = self.get_steps(table);
+        let before = transform::build_view(base.clone(), steps, fields).failed_fields;
+        let view = transform::build_view(base, steps, &remaining);
+        if let Some(user) = newly_failed(&before, &view.failed_fields) {
+            return Err(DataError::ParseError(format!(
+                "Calculated field '{}' is used by '{}'",
+                name, user
+            )));
+        }
+        self.store_calculated_fields(table, remaining, view.table);
+        Ok(())
+    }
+
+    pub fn get_steps(&self, table: &str) -> &[TransformStep] {
+        self.pipelines
+            .get(table)
+            .map(|p| p.steps.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn get_pipeline(&self, table: &str) -> Option<&Pipeline> {
+        self.pipelines.get(table)
+    }
+
+    /// Replaces a table's steps. Nothing changes if a step cannot be applied
+    /// or the steps stop one of the table's calculated fields from working.
+    fn apply_steps(&mut self, table: &str, steps: Vec<TransformStep>) -> Result<(), DataError> {
+        let base = self.base_table(table)?;
+        let fields = self.get_calculated_fields(table);
+        let before =
+            transform::build_view(base.clone(), self.get_steps(table), fields).failed_fields;
+        let view = transform::build_view(base, &steps, fields);
+
+        if let Some((i, e)) = view.failed_steps.first() {
+            return Err(DataError::ParseError(format!(
+                "Step {} ({}) cannot be applied: {}",
+                i + 1,
+                transform::step_name(&steps[*i]),
+                e
+            )));
+        }
+        if let Some(field) = newly_failed(&before, &view.failed_fields) {
+            return Err(DataError::ParseError(format!(
+                "These steps would break calculated field '{}'",
+                field
+            )));
+        }
+
+        self.tables
+            .insert(table.to_string(), view.table.materialize());
+        self.bump_version(table);
+        self.pipelines.entry(table.to_string()).or_default().steps = steps;
+        Ok(())
+    }
+
+    /// Changes a table's steps, so that undo returns to the current ones.
+    pub fn change_steps(
+        &mut self,
+        table: &str,
+        steps: Vec<TransformStep>,
+    ) -> Result<(), DataError> {
+        let previous = self.get_steps(table).to_vec();
+        self.apply_steps(table, steps)?;
+
+        let pipeline = self.pipelines.entry(table.to_string()).or_default();
+        pipeline.undo.push(previous);
+        if pipeline.undo.len() > UNDO_LIMIT {
+            pipeline.undo.remove(0);
+        }
+        pipeline.redo.clear();
+        Ok(())
+    }
+
+    /// Sets a table's steps without recording the change, as when a project
+    /// is reopened. The table has nothing to undo or redo afterwards.
+    pub fn restore_steps(
+        &mut self,
+        table: &str,
+        steps: Vec<TransformStep>,
+    ) -> Result<(), DataError> {
+        self.apply_steps(table, steps)?;
+        let pipeline = self.pipelines.entry(table.to_string()).or_default();
+        pipeline.undo.clear();
+        pipeline.redo.clear();
+        Ok(())
+    }
+
+    /// Returns a table's steps to what they were before the last change.
+    pub fn undo_steps(&mut self, table: &str) -> Result<(), DataError> {
+        let previous = self
+            .get_pipeline(table)
+            .and_then(|p| p.undo.last().cloned())
+            .ok_or_else(|| DataError::ParseError("Nothing to undo".to_string()))?;
+        let current = self.get_steps(table).to_vec();
+        self.apply_steps(table, previous)?;
+
+        let pipeline = self.pipelines.entry(table.to_string()).or_default();
+        pipeline.undo.pop();
+        pipeline.redo.push(current);
+        Ok(())
+    }
+
+    /// Makes again the last change to a table's steps that was undone.
+    pub fn redo_steps(&mut self, table: &str) -> Result<(), DataError> {
+        let next = self
+            .get_pipeline(table)
+            .and_then(|p| p.redo.last().cloned())
+            .ok_or_else(|| DataError::ParseError("Nothing to redo".to_string()))?;
+        let current = self.get_steps(table).to_vec();
+        self.apply_steps(table, next)?;
+
+        let pipeline = self.pipelines.entry(table.to_string()).or_default();
+        pipeline.redo.pop();
+        pipeline.undo.push(current);
+        Ok(())
+    }
+
+    /// Registers a table computed from other tables, such as a join result,
+    /// as `derivation` describes. A derived table of the same name is
+    /// replaced, but tables and names belonging to sources are not.
+    pub fn add_derived_table(
+        &mut self,
+        name: &str,
+        table: Table,
+        derivation: Derivation,
+    ) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() || name.contains(TABLE_SEPARATOR) {
+            return Err(format!(
+                "Table names must be non-empty and must not contain '{}'",
+                TABLE_SEPARATOR
+            ));
+        }
+        let owned = self
+            .sources
+            .iter()
+            .any(|s| s.name == name || s.tables.iter().any(|t| t == name));
+        if owned {
+            return Err(format!("'{}' is already a loaded table", name));
+        }
+
+        self.number_formats.remove(name);
+        self.date_formats.remove(name);
+        self.add_table(name.to_string(), table);
+        self.derived_tables.retain(|t| t.name != name);
+        self.derived_tables.push(DerivedTable {
+            name: name.to_string(),
+            derivation,
+        });
+        Ok(())
+    }
+
+    pub fn set_read_offset(&mut self, source: &str, offset: Option<u64>, partial_row: bool) {
+        if let Some(source) = self.sources.iter_mut().find(|s| s.name == source) {
+            source.read_offset = offset;
+            source.partial_row = partial_row;
+        }
+    }
+
+    pub fn set_number_formats(&mut self, table: String, formats: NumberFormats) {
+        if formats.is_empty() {
+            self.number_formats.remove(&table);
+        } else {
+            self.number_formats.insert(table, formats);
+        }
+    }
+
+    pub fn get_number_formats(&self, table: &str) -> Option<&NumberFormats> {
+        self.number_formats.get(table)
+    }
+
+    /// Number formats of a table's columns under the names its steps give
+    /// them, so that a renamed column keeps its format. They are stored by
+    /// source column, as that is how the file is read again.
+    pub fn view_number_formats(&self, table: &str) -> NumberFormats {
+        let formats = self.get_number_formats(table).cloned().unwrap_or_default();
+        transform::follow_renames(formats, self.get_steps(table))
+    }
+
+    pub fn set_date_formats(&mut self, table: String, formats: DateFormats) {
+        if formats.is_empty() {
+            self.date_formats.remove(&table);
+        } else {
+            self.date_formats.insert(table, formats);
+        }
+    }
+
+    pub fn get_date_formats(&self, table: &str) -> Option<&DateFormats> {
+        self.date_formats.get(table)
     }
 
     pub fn clear(&mut self) {
         self.tables.clear();
         self.table_order.clear();
         self.active_table = None;
-        self.file_path = None;
+        self.sources.clear();
+        self.number_formats.clear();
+        self.date_formats.clear();
+        self.calculated_fields.clear();
+        self.pipelines.clear();
+        self.derived_tables.clear();
+        self.versions.clear();
     }
 
     pub fn has_data(&self) -> bool {
@@ -74,3 +846,302 @@ impl Default for DataState {
 }
 
 pub type AppDataState = Mutex<DataState>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::types::{ExcelImportOptions, LogicalType, NumberFormat, NumberLocale};
+
+    fn table(values: &[i32]) -> Table {
+        Table::Materialized(df!("value" => values).unwrap())
+    }
+
+    #[test]
+    fn test_sources_are_added_renamed_and_removed() {
+        let mut state = DataState::new();
+
+        let sales = state
+            .add_source(
+                "/data/sales.csv".to_string(),
+                LoadOptions::Csv {
+                    options: None,
+                    scan: false,
+                    number_locale: None,
+                },
+                SchemaOverrides::new(),
+                vec![("default".to_string(), table(&[1, 2]), NumberFormats::new())],
+            )
+            .name
+            .clone();
+        let budget = state.add_source(
+            "/other/sales.xlsx".to_string(),
+            LoadOptions::Excel {
+                options: ExcelImportOptions::default(),
+                number_locale: None,
+            },
+            SchemaOverrides::new(),
+            vec![
+                ("Q1".to_string(), table(&[3]), NumberFormats::new()),
+                ("Q2".to_string(), table(&[4]), NumberFormats::new()),
+            ],
+        );
+        assert_eq!(sales, "sales");
+        assert_eq!(budget.name, "sales_2");
+        assert_eq!(budget.tables, ["sales_2/Q1", "sales_2/Q2"]);
+        assert_eq!(state.get_tables(), ["sales", "sales_2/Q1", "sales_2/Q2"]);
+
+        state.set_active_table("sales_2/Q2".to_string()).unwrap();
+        state.rename_source("sales_2", "budget").unwrap();
+        assert_eq!(state.active_table.as_deref(), Some("budget/Q2"));
+        assert!(state.get_table("budget/Q1").is_some());
+        assert!(state.rename_source("budget", "sales").is_err());
+
+        state.remove_source("budget").unwrap();
+        assert_eq!(state.get_tables(), ["sales"]);
+        assert_eq!(state.active_table.as_deref(), Some("sales"));
+    }
+
+    #[test]
+    fn test_reloading_a_path_replaces_its_source() {
+        let mut state = DataState::new();
+        for values in [[1, 2], [3, 4]] {
+            state.add_source(
+                "/data/sales.csv".to_string(),
+                LoadOptions::Csv {
+                    options: None,
+                    scan: false,
+                    number_locale: None,
+                },
+                SchemaOverrides::new(),
+                vec![("default".to_string(), table(&values), NumberFormats::new())],
+            );
+        }
+
+        assert_eq!(state.sources.len(), 1);
+        let df = state.get_table("sales").unwrap().collect().unwrap();
+        assert_eq!(df.column("value").unwrap().i32().unwrap().get(0), Some(3));
+    }
+
+    #[test]
+    fn test_table_version_changes_with_its_data() {
+        let mut state = DataState::new();
+        state.add_dataframe("sales".to_string(), df!("value" => [1]).unwrap());
+        let loaded = state.table_version("sales").unwrap();
+
+        state.replace_table("sales", table(&[1, 2])).unwrap();
+        let appended = state.table_version("sales").unwrap();
+        assert_ne!(loaded, appended);
+
+        state.clear();
+        state.add_dataframe("sales".to_string(), df!("value" => [1]).unwrap());
+        assert!(!matches!(state.table_version("sales"), Some(v) if v == loaded || v == appended));
+    }
+
+    #[test]
+    fn test_frame_columns_report_nulls_and_type_details() {
+        let df = df!(
+            "id" => [1i64, 2],
+            "note" => [Some("a"), None],
+        )
+        .unwrap();
+        let df = df
+            .lazy()
+            .with_columns([
+                col("id")
+                    .cast(DataType::Decimal(Some(10), Some(2)))
+                    .alias("price"),
+                col("id")
+                    .cast(DataType::Datetime(TimeUnit::Milliseconds, None))
+                    .alias("at"),
+            ])
+            .collect()
+            .unwrap();
+
+        let columns = frame_columns(&df);
+        assert!(!columns[0].nullable);
+        assert!(columns[1].nullable);
+        assert_eq!(columns[2].dtype, LogicalType::Decimal);
+        assert_eq!(columns[2].type_label(), "decimal(10, 2)");
+        assert_eq!(columns[3].dtype, LogicalType::Datetime);
+        assert_eq!(columns[3].time_unit.as_deref(), Some("ms"));
+
+        let zoned = DataType::Datetime(TimeUnit::Milliseconds, Some("Europe/Paris".into()));
+        let at = ColumnInfo::new("at", &zoned, false);
+        assert_eq!(at.type_label(), "datetime, Europe/Paris");
+
+        let scanned = Table::scan(df.lazy()).columns().unwrap();
+        assert!(scanned.iter().all(|c| c.nullable));
+    }
+
+    #[test]
+    fn test_derived_tables_do_not_replace_source_tables() {
+        let mut state = DataState::new();
+        state.add_source(
+            "/data/orders.csv".to_string(),
+            LoadOptions::Csv {
+                options: None,
+                scan: false,
+                number_locale: None,
+            },
+            SchemaOverrides::new(),
+            vec![("default".to_string(), table(&[1]), NumberFormats::new())],
+        );
+
+        let union = |tables: &[&str]| Derivation::Union {
+            tables: tables.iter().map(|t| t.to_string()).collect(),
+            label_column: None,
+        };
+
+        assert!(state
+            .add_derived_table("orders", table(&[2]), union(&["orders"]))
+            .is_err());
+        assert!(state
+            .add_derived_table("a/b", table(&[2]), union(&["orders"]))
+            .is_err());
+
+        state
+            .add_derived_table("joined", table(&[2]), union(&["orders"]))
+            .unwrap();
+        let first = state.table_version("joined").unwrap();
+        state
+            .add_derived_table("joined", table(&[3]), union(&["orders", "orders"]))
+            .unwrap();
+        assert_ne!(state.table_version("joined"), Some(first));
+        assert_eq!(state.get_tables(), ["orders", "joined"]);
+
+        // Derivations follow the tables they read when those are renamed.
+        state.rename_source("orders", "sales").unwrap();
+        assert_eq!(
+            state.derived_tables,
+            [DerivedTable {
+                name: "joined".to_string(),
+                derivation: union(&["sales", "sales"]),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_calculated_fields_survive_reloads_of_their_table() {
+        let mut state = DataState::new();
+        state.add_dataframe("sales".to_string(), df!("value" => [1i32, 2]).unwrap());
+        let field = |name: &str, formula: &str| CalculatedField {
+            name: name.to_string(),
+            formula: formula.to_string(),
+        };
+
+        state
+            .set_calculated_field("sales", field("double", "value * 2"))
+            .unwrap();
+        state
+            .set_calculated_field("sales", field("quad", "double * 2"))
+            .unwrap();
+        assert!(state
+            .set_calculated_field("sales", field("bad", "missing + 1"))
+            .is_err());
+        assert!(state.remove_calculated_field("sales", "double").is_err());
+
+        state.replace_table("sales", table(&[5, 6, 7])).unwrap();
+        let df = state.get_table("sales").unwrap().collect().unwrap();
+        assert_eq!(df.get_column_names(), ["value", "double", "quad"]);
+        assert_eq!(df.column("quad").unwrap().i32().unwrap().get(2), Some(28));
+
+        state.remove_calculated_field("sales", "quad").unwrap();
+        assert_eq!(
+            state.get_calculated_fields("sales"),
+            [field("double", "value * 2")]
+        );
+    }
+
+    #[test]
+    fn test_number_formats_follow_renamed_columns() {
+        let mut state = DataState::new();
+        state.add_dataframe("sales".to_string(), df!("price" => [1.5f64]).unwrap());
+        let usd = NumberFormat {
+            locale: NumberLocale::DecimalPoint,
+            prefix: "$".to_string(),
+            suffix: String::new(),
+            thousands_separator: Some(','),
+            decimals: 2,
+            parentheses_negative: false,
+        };
+        state.set_number_formats(
+            "sales".to_string(),
+            NumberFormats::from([("price".to_string(), usd.clone())]),
+        );
+
+        let rename = TransformStep::Rename {
+            column: "price".to_string(),
+            to: "Price (USD)".to_string(),
+        };
+        state.change_steps("sales", vec![rename]).unwrap();
+        let formats = state.view_number_formats("sales");
+        assert_eq!(formats.get("Price (USD)"), Some(&usd));
+        assert!(!formats.contains_key("price"));
+        // Stored by source column, so a reload of the file still finds it.
+        assert!(state.get_number_formats("sales").unwrap().contains_key("price"));
+
+        state.undo_steps("sales").unwrap();
+        assert_eq!(state.view_number_formats("sales").get("price"), Some(&usd));
+    }
+
+    #[test]
+    fn test_views_of_in_memory_tables_are_collected_once() {
+        let mut state = DataState::new();
+        state.add_dataframe("sales".to_string(), df!("value" => [1i32, 2, 2]).unwrap());
+        state
+            .change_steps(
+                "sales",
+                vec![TransformStep::Dedupe {
+                    columns: Vec::new(),
+                }],
+            )
+            .unwrap();
+
+        let Some(Table::Transformed { plan, rows, .. }) = state.get_table("sales") else {
+            panic!("sales has no view");
+        };
+        assert_eq!(rows.0.get(), Some(&2));
+        assert!(plan.describe_plan().unwrap().starts_with("DF "));
+    }
+
+    #[test]
+    fn test_step_changes_can_be_undone_and_redone() {
+        let mut state = DataState::new();
+        state.add_dataframe("sales".to_string(), df!("value" => [1i32, 2, 2]).unwrap());
+        let dedupe = TransformStep::Dedupe {
+            columns: Vec::new(),
+        };
+        let rename = TransformStep::Rename {
+            column: "value".to_string(),
+            to: "units".to_string(),
+        };
+        let height = |state: &DataState| state.get_table("sales").unwrap().row_count().unwrap();
+
+        state.change_steps("sales", vec![dedupe.clone()]).unwrap();
+        state.change_steps("sales", vec![dedupe, rename]).unwrap();
+        // `value` was renamed by the previous step.
+        let mut steps = state.get_steps("sales").to_vec();
+        steps.push(TransformStep::Drop {
+            columns: vec!["value".to_string()],
+        });
+        assert!(state.change_steps("sales", steps).is_err());
+        assert_eq!(state.get_steps("sales").len(), 2);
+        assert_eq!(height(&state), 2);
+
+        state.undo_steps("sales").unwrap();
+        state.undo_steps("sales").unwrap();
+        assert!(state.undo_steps("sales").is_err());
+        assert_eq!(height(&state), 3);
+
+        state.redo_steps("sales").unwrap();
+        state.replace_table("sales", table(&[4, 4, 4, 5])).unwrap();
+        assert_eq!(height(&state), 2);
+        assert!(state.get_pipeline("sales").unwrap().can_redo());
+
+        // Restored steps cannot be undone.
+        state.restore_steps("sales", Vec::new()).unwrap();
+        let pipeline = state.get_pipeline("sales").unwrap();
+        assert!(!pipeline.can_undo() && !pipeline.can_redo());
+        assert_eq!(height(&state), 4);
+    }
+}
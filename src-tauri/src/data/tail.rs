@@ -0,0 +1,134 @@
+//! # Appending to Growing Files
+//!
+//! Log-style CSV and NDJSON files only ever grow. Instead of reading them
+//! again in full, the bytes past the end of the last read are parsed and the
+//! new rows stacked onto the table already in memory.
+
+use crate::data::union;
+use polars::prelude::*;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Tables with more chunks than this are rechunked after an append, so many
+/// small appends do not slow down queries.
+const MAX_CHUNKS: usize = 64;
+
+/// The complete lines written to a file after `offset`, with the offset just
+/// past them. A trailing line without a newline may still be being written
+/// and is left for the next read. Returns `None` when nothing new is complete.
+pub fn read_appended(path: &str, offset: u64) -> io::Result<Option<(Vec<u8>, u64)>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    if len < offset {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "The file is shorter than when it was last read; reload it instead",
+        ));
+    }
+
+    file.seek(SeekFrom::Start(offset))?;
+    let mut bytes = Vec::with_capacity((len - offset) as usize);
+    file.read_to_end(&mut bytes)?;
+
+    match bytes.iter().rposition(|&b| b == b'\n') {
+        Some(last_newline) => {
+            bytes.truncate(last_newline + 1);
+            let new_offset = offset + bytes.len() as u64;
+            Ok(Some((bytes, new_offset)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Casts the columns of new rows to the types the table already has, so a
+/// batch that happens to look different (all-empty, say) stacks cleanly.
+/// Values that do not fit the existing type become null.
+pub fn conform_to(chunk: DataFrame, schema: &Schema) -> PolarsResult<DataFrame> {
+    let columns = chunk
+        .get_columns()
+        .iter()
+        .map(|column| match schema.get(column.name()) {
+            Some(dtype) if dtype != column.dtype() => cast_column(column, dtype),
+            _ => Ok(column.clone()),
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+
+    DataFrame::new(columns)
+}
+
+fn cast_column(column: &Series, dtype: &DataType) -> PolarsResult<Series> {
+    if dtype == &DataType::Boolean && column.dtype() == &DataType::String {
+        let flags: BooleanChunked = column
+            .str()?
+            .into_iter()
+            .map(|value| match value.map(str::trim) {
+                Some(v) if v.eq_ignore_ascii_case("true") => Some(true),
+                Some(v) if v.eq_ignore_ascii_case("false") => Some(false),
+                _ => None,
+            })
+            .collect();
+        return Ok(flags.with_name(column.name().clone()).into_series());
+    }
+    column.cast(dtype)
+}
+
+/// Stacks new rows onto a table. Rows with the same columns are appended in
+/// place; new or missing columns go through a schema-reconciling union.
+pub fn append(mut table: DataFrame, chunk: DataFrame) -> PolarsResult<DataFrame> {
+    let chunk = conform_to(chunk, &table.schema())?;
+
+    if chunk.schema() == table.schema() {
+        table.vstack_mut(&chunk)?;
+        if table.n_chunks() > MAX_CHUNKS {
+            table.align_chunks();
+        }
+        Ok(table)
+    } else {
+        union::union_frames(&[table, chunk])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_appended_stops_at_last_complete_line() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("access.log.csv");
+        std::fs::write(&path, "ts,status\n1,200\n").unwrap();
+        let path_str = path.to_str().unwrap();
+        let offset = std::fs::metadata(&path).unwrap().len();
+
+        assert!(read_appended(path_str, offset).unwrap().is_none());
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(b"2,404\n3,5").unwrap();
+
+        let (bytes, new_offset) = read_appended(path_str, offset).unwrap().unwrap();
+        assert_eq!(bytes, b"2,404\n");
+        assert_eq!(new_offset, offset + 6);
+        assert!(read_appended(path_str, offset + 100).is_err());
+    }
+
+    #[test]
+    fn test_append_keeps_existing_types() {
+        let table = df!("id" => [1i64, 2], "ok" => [true, false]).unwrap();
+        let chunk = df!("id" => ["3"], "ok" => ["TRUE"]).unwrap();
+
+        let df = append(table, chunk).unwrap();
+        assert_eq!(df.height(), 3);
+        assert_eq!(df.column("id").unwrap().i64().unwrap().get(2), Some(3));
+        assert_eq!(df.column("ok").unwrap().bool().unwrap().get(2), Some(true));
+
+        let chunk = df!("id" => [4i64], "ok" => [true], "extra" => ["x"]).unwrap();
+        let df = append(df, chunk).unwrap();
+        assert_eq!(df.width(), 3);
+        assert_eq!(df.column("extra").unwrap().null_count(), 3);
+    }
+}
@@ -0,0 +1,234 @@
+//! # Fixed-Width and Custom-Delimited Text
+//!
+//! Mainframe and report exports lay columns out by character position, and
+//! some exports separate fields with delimiters such as `||` that the CSV
+//! reader cannot take. Both are rewritten here as plain comma-separated text,
+//! so they are typed, date-parsed and reported on like any other CSV file.
+//!
+//! Lines are split on line breaks before fields are, so quoted fields cannot
+//! span several lines in these files.
+
+use crate::data::sniff;
+use crate::data::types::{CsvImportOptions, FixedWidthColumn, FixedWidthOptions};
+use crate::error::DataError;
+
+/// Maximum number of lines inspected when detecting a layout.
+const SAMPLE_LINES: usize = 200;
+
+/// Whether the CSV reader handles `delimiter` itself.
+pub fn is_native_delimiter(delimiter: &str) -> bool {
+    matches!(delimiter.as_bytes(), [b] if b.is_ascii())
+}
+
+fn write_csv(rows: impl IntoIterator<Item = Vec<String>>) -> Result<Vec<u8>, DataError> {
+    let mut writer = csv::WriterBuilder::new()
+        .flexible(true)
+        .from_writer(Vec::new());
+    for row in rows {
+        writer.write_record(&row)?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| DataError::ParseError(e.to_string()))
+}
+
+/// Rewrites text separated by a delimiter the CSV reader cannot take as
+/// comma-separated CSV. Returns it with the options to read it with.
+pub fn delimited_to_csv(
+    text: &str,
+    options: &CsvImportOptions,
+) -> Result<(Vec<u8>, CsvImportOptions), DataError> {
+    if options.delimiter.is_empty() {
+        return Err(DataError::ParseError(
+            "Delimiter must not be empty".to_string(),
+        ));
+    }
+
+    let rows = text
+        .lines()
+        .skip(options.skip_rows)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| sniff::split_fields(line, &options.delimiter, options.quote_char));
+    let csv_options = CsvImportOptions {
+        has_header: options.has_header,
+        ..CsvImportOptions::default()
+    };
+    Ok((write_csv(rows)?, csv_options))
+}
+
+/// Finds columns from the whitespace that lines up across lines: every
+/// character position that is blank on all lines separates two columns.
+pub fn detect_columns(lines: &[&str]) -> Vec<FixedWidthColumn> {
+    let rows: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let filled: Vec<bool> = (0..width)
+        .map(|i| {
+            rows.iter()
+                .any(|row| row.get(i).is_some_and(|c| !c.is_whitespace()))
+        })
+        .collect();
+
+    let mut columns = Vec::new();
+    let mut start = None;
+    for (i, &is_filled) in filled.iter().chain([&false]).enumerate() {
+        match (start, is_filled) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                columns.push(FixedWidthColumn {
+                    name: None,
+                    start: s,
+                    end: i,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    columns
+}
+
+/// Cuts a line into the trimmed text of each column. Lines shorter than the
+/// layout give empty values.
+pub fn split_fixed(line: &str, columns: &[FixedWidthColumn]) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    columns
+        .iter()
+        .map(|column| {
+            let start = column.start.min(chars.len());
+            let end = column.end.clamp(start, chars.len());
+            chars[start..end]
+                .iter()
+                .collect::<String>()
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+fn data_lines<'a>(text: &'a str, options: &FixedWidthOptions) -> impl Iterator<Item = &'a str> {
+    text.lines()
+        .skip(options.skip_rows)
+        .filter(|line| !line.trim().is_empty())
+}
+
+/// Fills in what was left to detection: the column positions, whether there
+/// is a header line, and the column names.
+pub fn resolve_layout(
+    text: &str,
+    options: &FixedWidthOptions,
+) -> Result<FixedWidthOptions, DataError> {
+    let sample: Vec<&str> = data_lines(text, options).take(SAMPLE_LINES).collect();
+
+    let mut columns = if options.columns.is_empty() {
+        detect_columns(&sample)
+    } else {
+        options.columns.clone()
+    };
+    if columns.is_empty() {
+        return Err(DataError::ParseError(
+            "No fixed-width columns found; give their positions instead".to_string(),
+        ));
+    }
+    if let Some(column) = columns.iter().find(|c| c.end <= c.start) {
+        return Err(DataError::ParseError(format!(
+            "Column ending at {} must end after its start {}",
+            column.end, column.start
+        )));
+    }
+
+    let rows: Vec<Vec<String>> = sample
+        .iter()
+        .map(|line| split_fixed(line, &columns))
+        .collect();
+    let has_header = options
+        .has_header
+        .unwrap_or_else(|| sniff::detect_header(&rows));
+
+    for (i, column) in columns.iter_mut().enumerate() {
+        if column.name.is_none() {
+            let header = rows
+                .first()
+                .filter(|_| has_header)
+                .map(|row| row[i].clone())
+                .filter(|name| !name.is_empty());
+            column.name = Some(header.unwrap_or_else(|| format!("column_{}", i + 1)));
+        }
+    }
+
+    Ok(FixedWidthOptions {
+        columns,
+        has_header: Some(has_header),
+        ..options.clone()
+    })
+}
+
+/// Rewrites fixed-width text as comma-separated CSV with a header row, given
+/// a layout from `resolve_layout`.
+pub fn fixed_width_to_csv(text: &str, layout: &FixedWidthOptions) -> Result<Vec<u8>, DataError> {
+    let header: Vec<String> = layout
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            c.name
+                .clone()
+                .unwrap_or_else(|| format!("column_{}", i + 1))
+        })
+        .collect();
+    let skip_header = usize::from(layout.has_header.unwrap_or(false));
+    let rows = data_lines(text, layout)
+        .skip(skip_header)
+        .map(|line| split_fixed(line, &layout.columns));
+
+    write_csv(std::iter::once(header).chain(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = "\
+ACME BATCH REPORT
+ID    NAME        AMOUNT  DATE
+1001  Anna Meyer   12.50  2024-01-05
+1002  Li Wei      300.00  2024-01-06
+1003  Bo          7.25    2024-01-07
+";
+
+    #[test]
+    fn test_detects_columns_from_aligned_whitespace() {
+        let lines: Vec<&str> = REPORT.lines().skip(1).collect();
+        let columns = detect_columns(&lines);
+        let spans: Vec<(usize, usize)> = columns.iter().map(|c| (c.start, c.end)).collect();
+        assert_eq!(spans, [(0, 4), (6, 16), (18, 24), (26, 36)]);
+    }
+
+    #[test]
+    fn test_fixed_width_becomes_csv_with_header_names() {
+        let options = FixedWidthOptions {
+            skip_rows: 1,
+            ..Default::default()
+        };
+        let layout = resolve_layout(REPORT, &options).unwrap();
+        assert_eq!(layout.has_header, Some(true));
+
+        let csv = String::from_utf8(fixed_width_to_csv(REPORT, &layout).unwrap()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "ID,NAME,AMOUNT,DATE");
+        assert_eq!(lines[1], "1001,Anna Meyer,12.50,2024-01-05");
+        assert_eq!(lines[3], "1003,Bo,7.25,2024-01-07");
+    }
+
+    #[test]
+    fn test_multi_character_delimiter_becomes_csv() {
+        let options = CsvImportOptions {
+            delimiter: "||".to_string(),
+            ..Default::default()
+        };
+        let (csv, csv_options) = delimited_to_csv("id||note\n1||a, b\n", &options).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "id,note\n1,\"a, b\"\n");
+        assert_eq!(csv_options.delimiter, ",");
+        assert!(!is_native_delimiter("||"));
+        assert!(is_native_delimiter(";"));
+    }
+}
@@ -0,0 +1,532 @@
+//! # Transformation Steps
+//!
+//! Cleaning a table (filtering rows, renaming, retyping or dropping columns,
+//! filling nulls, removing duplicates, deriving columns) is recorded as an
+//! ordered list of steps instead of being done to the data. A table is its
+//! source data with its steps and then its calculated fields applied lazily,
+//! so steps can be undone, reordered, saved with a project and replayed when
+//! the source file is loaded again.
+
+use crate::data::formula;
+use crate::data::ingest::dataset_info;
+use crate::data::overrides;
+use crate::data::query::apply_filter;
+use crate::data::state::{AppDataState, DataState, Table};
+use crate::data::types::{
+    CalculatedField, FillStrategy, SchemaOverrides, SplitPattern, TransformPipeline, TransformStep,
+    TransformedTable,
+};
+use crate::error::DataError;
+use polars::prelude::*;
+use std::collections::HashMap;
+use tauri::State;
+
+/// Short name of a step, for error messages.
+pub fn step_name(step: &TransformStep) -> &'static str {
+    match step {
+        TransformStep::Filter(_) => "filter",
+        TransformStep::Rename { .. } => "rename",
+        TransformStep::Cast { .. } => "cast",
+        TransformStep::Drop { .. } => "drop",
+        TransformStep::FillNull { .. } => "fill null",
+        TransformStep::Dedupe { .. } => "dedupe",
+        TransformStep::Derive { .. } => "derive",
+        TransformStep::Reorder { .. } => "reorder",
+        TransformStep::Split { .. } => "split",
+    }
+}
+
+/// Stands in for regex matches so that a regex split can use the plain
+/// delimiter split. Control characters do not occur in ordinary text.
+const SPLIT_MARK: &str = "\u{1f}";
+
+fn check_unique(names: &[String]) -> Result<(), DataError> {
+    match names
+        .iter()
+        .enumerate()
+        .find(|(i, name)| names[..*i].contains(name))
+    {
+        Some((_, name)) => Err(DataError::ParseError(format!(
+            "Column '{}' is listed twice",
+            name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// The parts of `column` as a struct with one field per part.
+fn split_parts(column: &str, by: &SplitPattern, parts: usize) -> Result<Expr, DataError> {
+    let text = col(column).cast(DataType::String);
+    match by {
+        SplitPattern::Delimiter { delimiter } if !delimiter.is_empty() => {
+            Ok(text.str().splitn(lit(delimiter.clone()), parts))
+        }
+        SplitPattern::Regex { pattern } if !pattern.is_empty() => Ok(text
+            .str()
+            .replace_all(lit(pattern.clone()), lit(SPLIT_MARK), false)
+            .str()
+            .splitn(lit(SPLIT_MARK), parts)),
+        _ => Err(DataError::ParseError("Choose what to split on".to_string())),
+    }
+}
+
+fn fill_value(value: &serde_json::Value) -> Result<Expr, DataError> {
+    match value {
+        serde_json::Value::String(text) => Ok(lit(text.clone())),
+        serde_json::Value::Bool(b) => Ok(lit(*b)),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Ok(lit(i)),
+            None => Ok(lit(n.as_f64().unwrap_or_default())),
+        },
+        _ => Err(DataError::ParseError(
+            "Fill values must be text, a number or true/false".to_string(),
+        )),
+    }
+}
+
+/// Adds one step to a lazy plan.
+pub fn apply_step(lf: LazyFrame, step: &TransformStep) -> Result<LazyFrame, DataError> {
+    let schema = lf.clone().collect_schema()?;
+    let require = |column: &str| {
+        if schema.contains(column) {
+            Ok(())
+        } else {
+            Err(DataError::ColumnNotFound {
+                column: column.to_string(),
+                available: schema
+                    .iter_names()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            })
+        }
+    };
+
+    Ok(match step {
+        TransformStep::Filter(filter) => {
+            require(&filter.column)?;
+            apply_filter(lf, filter)?
+        }
+        TransformStep::Rename { column, to } => {
+            require(column)?;
+            let to = to.trim();
+            if to.is_empty() || (to != column && schema.contains(to)) {
+                return Err(DataError::ParseError(format!(
+                    "Cannot rename '{}' to '{}'",
+                    column, to
+                )));
+            }
+            lf.rename([column.as_str()], [to])
+        }
+        TransformStep::Cast { column, to } => {
+            require(column)?;
+            let overrides = SchemaOverrides::from([(column.clone(), to.clone())]);
+            overrides::apply_lazy(lf, &overrides)?
+        }
+        TransformStep::Drop { columns } => {
+            columns.iter().try_for_each(|c| require(c))?;
+            lf.drop(columns.iter().map(String::as_str))
+        }
+        TransformStep::FillNull { column, strategy } => {
+            require(column)?;
+            let filled = match strategy {
+                FillStrategy::Value { value } => col(column).fill_null(fill_value(value)?),
+                FillStrategy::Forward => col(column).forward_fill(None),
+                FillStrategy::Backward => col(column).backward_fill(None),
+                FillStrategy::Mean => col(column).fill_null(col(column).mean()),
+            };
+            lf.with_column(filled.alias(column))
+        }
+        TransformStep::Dedupe { columns } => {
+            columns.iter().try_for_each(|c| require(c))?;
+            let subset =
+                (!columns.is_empty()).then(|| columns.iter().map(|c| c.as_str().into()).collect());
+            lf.unique_stable(subset, UniqueKeepStrategy::First)
+        }
+        TransformStep::Derive { name, formula } => {
+            let field = CalculatedField {
+                name: name.clone(),
+                formula: formula.clone(),
+            };
+            let expr = formula::field_expr(&lf, &field)?;
+            lf.with_column(expr)
+        }
+        TransformStep::Reorder { columns } => {
+            columns.iter().try_for_each(|c| require(c))?;
+            check_unique(columns)?;
+            let rest = schema
+                .iter_names()
+                .filter(|name| !columns.iter().any(|c| c == name.as_str()));
+            let order: Vec<Expr> = columns
+                .iter()
+                .map(|c| col(c.as_str()))
+                .chain(rest.map(|name| col(name.clone())))
+                .collect();
+            lf.select(order)
+        }
+        TransformStep::Split { column, by, into } => {
+            require(column)?;
+            if into.len() < 2 {
+                return Err(DataError::ParseError(format!(
+                    "Split '{}' into at least two columns",
+                    column
+                )));
+            }
+            let into: Vec<String> = into.iter().map(|name| name.trim().to_string()).collect();
+            check_unique(&into)?;
+            if let Some(name) = into
+                .iter()
+                .find(|name| name.is_empty() || schema.contains(name))
+            {
+                return Err(DataError::ParseError(format!(
+                    "Cannot name a split column '{}'",
+                    name
+                )));
+            }
+
+            let parts = split_parts(column, by, into.len())?;
+            let mut order = Vec::with_capacity(schema.len() + into.len());
+            for name in schema.iter_names() {
+                order.push(col(name.clone()));
+                if name.as_str() == column {
+                    order.extend(into.iter().enumerate().map(|(i, part)| {
+                        parts
+                            .clone()
+                            .struct_()
+                            .field_by_index(i as i64)
+                            .alias(part.as_str())
+                    }));
+                }
+            }
+            lf.select(order)
+        }
+    })
+}
+
+/// A lazy plan built one change at a time, with a copy of it running on the
+/// first source row only. Type errors surface only when a plan runs, so each
+/// change is run on that copy before it is kept.
+struct Plan {
+    lf: LazyFrame,
+    probe: LazyFrame,
+}
+
+impl Plan {
+    fn new(base: &Table) -> Self {
+        let lf = base.lazy();
+        Self {
+            probe: lf.clone().limit(1),
+            lf,
+        }
+    }
+
+    fn apply(
+        &mut self,
+        change: impl Fn(LazyFrame) -> Result<LazyFrame, DataError>,
+    ) -> Result<(), DataError> {
+        let probe = change(self.probe.clone())?;
+        probe.clone().collect()?;
+        self.lf = change(self.lf.clone())?;
+        self.probe = probe;
+        Ok(())
+    }
+}
+
+/// Re-keys `map`, keyed by a table's source columns, by the names `steps` give
+/// those columns. Entries of dropped columns are left out.
+pub fn follow_renames<V>(
+    mut map: HashMap<String, V>,
+    steps: &[TransformStep],
+) -> HashMap<String, V> {
+    for step in steps {
+        match step {
+            TransformStep::Rename { column, to } => {
+                if let Some(value) = map.remove(column) {
+                    map.insert(to.clone(), value);
+                }
+            }
+            TransformStep::Drop { columns } => {
+                for column in columns {
+                    map.remove(column);
+                }
+            }
+            _ => {}
+        }
+    }
+    map
+}
+
+/// A table with its steps and calculated fields applied, and the ones that
+/// could not be, by step index and field name. Those are left out.
+pub struct TableView {
+    pub table: Table,
+    pub failed_steps: Vec<(usize, DataError)>,
+    pub failed_fields: Vec<(String, DataError)>,
+}
+
+/// Applies `steps` and then `fields` to `base`. Later steps and fields see the
+/// columns earlier ones add or rename.
+pub fn build_view(base: Table, steps: &[TransformStep], fields: &[CalculatedField]) -> TableView {
+    if steps.is_empty() && fields.is_empty() {
+        return TableView {
+            table: base,
+            failed_steps: Vec::new(),
+            failed_fields: Vec::new(),
+        };
+    }
+
+    let mut plan = Plan::new(&base);
+    let failed_steps = steps
+        .iter()
+        .enumerate()
+        .filter_map(|(i, step)| plan.apply(|lf| apply_step(lf, step)).err().map(|e| (i, e)))
+        .collect();
+    let failed_fields = fields
+        .iter()
+        .filter_map(|field| {
+            plan.apply(|lf| Ok(lf.clone().with_column(formula::field_expr(&lf, field)?)))
+                .err()
+                .map(|e| (field.name.clone(), e))
+        })
+        .collect();
+
+    TableView {
+        table: Table::Transformed {
+            base: Box::new(base),
+            plan: Box::new(plan.lf),
+            rows: Default::default(),
+        },
+        failed_steps,
+        failed_fields,
+    }
+}
+
+fn pipeline_info(data_state: &DataState, table: &str) -> TransformPipeline {
+    let pipeline = data_state.get_pipeline(table);
+    TransformPipeline {
+        table: table.to_string(),
+        steps: data_state.get_steps(table).to_vec(),
+        can_undo: pipeline.is_some_and(|p| p.can_undo()),
+        can_redo: pipeline.is_some_and(|p| p.can_redo()),
+    }
+}
+
+/// Runs `change` on a table, the active table by default, which then becomes
+/// the active table.
+pub(crate) fn change_table(
+    state: &AppDataState,
+    table: Option<String>,
+    change: impl FnOnce(&mut DataState, &str) -> Result<(), DataError>,
+) -> Result<TransformedTable, DataError> {
+    let mut data_state = state
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+    let table = table
+        .or_else(|| data_state.active_table.clone())
+        .ok_or(DataError::NoData)?;
+
+    change(&mut data_state, &table)?;
+    data_state
+        .set_active_table(table.clone())
+        .map_err(DataError::ParseError)?;
+
+    Ok(TransformedTable {
+        dataset: dataset_info(&data_state, None)?,
+        pipeline: pipeline_info(&data_state, &table),
+    })
+}
+
+/// The recorded steps of a table, the active table by default.
+#[tauri::command]
+pub async fn get_transform_steps(
+    table: Option<String>,
+    state: State<'_, AppDataState>,
+) -> Result<TransformPipeline, DataError> {
+    let data_state = state
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+    let table = table
+        .or_else(|| data_state.active_table.clone())
+        .ok_or(DataError::NoData)?;
+    Ok(pipeline_info(&data_state, &table))
+}
+
+/// Appends `step` to a table's steps.
+pub(crate) fn push_step(
+    data_state: &mut DataState,
+    table: &str,
+    step: TransformStep,
+) -> Result<(), DataError> {
+    let mut steps = data_state.get_steps(table).to_vec();
+    steps.push(step);
+    data_state.change_steps(table, steps)
+}
+
+/// Appends a step to a table's steps.
+#[tauri::command]
+pub async fn add_transform_step(
+    table: Option<String>,
+    step: TransformStep,
+    state: State<'_, AppDataState>,
+) -> Result<TransformedTable, DataError> {
+    change_table(&state, table, |data_state, table| {
+        push_step(data_state, table, step)
+    })
+}
+
+/// Removes the step at `index`.
+#[tauri::command]
+pub async fn remove_transform_step(
+    table: Option<String>,
+    index: usize,
+    state: State<'_, AppDataState>,
+) -> Result<TransformedTable, DataError> {
+    change_table(&state, table, |data_state, table| {
+        let mut steps = data_state.get_steps(table).to_vec();
+        if index >= steps.len() {
+            return Err(DataError::ParseError(format!("No step {}", index + 1)));
+        }
+        steps.remove(index);
+        data_state.change_steps(table, steps)
+    })
+}
+
+/// Moves the step at `from` to position `to`.
+#[tauri::command]
+pub async fn move_transform_step(
+    table: Option<String>,
+    from: usize,
+    to: usize,
+    state: State<'_, AppDataState>,
+) -> Result<TransformedTable, DataError> {
+    change_table(&state, table, |data_state, table| {
+        let mut steps = data_state.get_steps(table).to_vec();
+        if from >= steps.len() || to >= steps.len() {
+            return Err(DataError::ParseError(format!(
+                "Cannot move step {} to {}",
+                from + 1,
+                to + 1
+            )));
+        }
+        let step = steps.remove(from);
+        steps.insert(to, step);
+        data_state.change_steps(table, steps)
+    })
+}
+
+/// Replaces all of a table's steps when a project is reopened. The change is
+/// not recorded, so undo cannot return to the table before it was restored.
+#[tauri::command]
+pub async fn set_transform_steps(
+    table: Option<String>,
+    steps: Vec<TransformStep>,
+    state: State<'_, AppDataState>,
+) -> Result<TransformedTable, DataError> {
+    change_table(&state, table, |data_state, table| {
+        data_state.restore_steps(table, steps)
+    })
+}
+
+/// Undoes the last change to a table's steps.
+#[tauri::command]
+pub async fn undo_transform_step(
+    table: Option<String>,
+    state: State<'_, AppDataState>,
+) -> Result<TransformedTable, DataError> {
+    change_table(&state, table, DataState::undo_steps)
+}
+
+/// Redoes the last undone change to a table's steps.
+#[tauri::command]
+pub async fn redo_transform_step(
+    table: Option<String>,
+    state: State<'_, AppDataState>,
+) -> Result<TransformedTable, DataError> {
+    change_table(&state, table, DataState::redo_steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::types::{FilterOperator, FilterSpec};
+    use crate::data::types::ColumnType;
+
+    fn customers() -> Table {
+        Table::Materialized(
+            df!(
+                "id" => ["1", "2", "2", "3"],
+                "region" => [Some("EU"), None, None, Some("US")],
+                "spend" => [Some(10.0), Some(20.0), Some(20.0), None],
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_steps_apply_in_order() {
+        let steps = [
+            TransformStep::Dedupe {
+                columns: Vec::new(),
+            },
+            TransformStep::Cast {
+                column: "id".to_string(),
+                to: ColumnType::Integer,
+            },
+            TransformStep::FillNull {
+                column: "spend".to_string(),
+                strategy: FillStrategy::Mean,
+            },
+            TransformStep::Rename {
+                column: "spend".to_string(),
+                to: "revenue".to_string(),
+            },
+            TransformStep::Derive {
+                name: "big".to_string(),
+                formula: "revenue > 12".to_string(),
+            },
+            TransformStep::Filter(FilterSpec {
+                column: "region".to_string(),
+                operator: FilterOperator::IsNotNull,
+                value: serde_json::Value::Null,
+            }),
+            TransformStep::Drop {
+                columns: vec!["region".to_string()],
+            },
+        ];
+
+        let view = build_view(customers(), &steps, &[]);
+        assert!(view.failed_steps.is_empty());
+        let df = view.table.collect().unwrap();
+        assert_eq!(df.get_column_names(), ["id", "revenue", "big"]);
+        assert_eq!(df.column("id").unwrap().dtype(), &DataType::Int64);
+        let revenue: Vec<Option<f64>> = df.column("revenue").unwrap().f64().unwrap().to_vec();
+        assert_eq!(revenue, [Some(10.0), Some(15.0)]);
+    }
+
+    #[test]
+    fn test_failed_steps_are_left_out() {
+        let steps = [
+            TransformStep::Rename {
+                column: "missing".to_string(),
+                to: "x".to_string(),
+            },
+            TransformStep::Derive {
+                name: "year".to_string(),
+                formula: "year(region)".to_string(),
+            },
+            TransformStep::Drop {
+                columns: vec!["spend".to_string()],
+            },
+        ];
+        let fields = [CalculatedField {
+            name: "double".to_string(),
+            formula: "spend * 2".to_string(),
+        }];
+
+        let view = build_view(customers(), &steps, &fields);
+        let failed: Vec<usize> = view.failed_steps.iter().map(|(i, _)| *i).collect();
+        assert_eq!(failed, [0, 1]);
+        assert_eq!(view.failed_fields.len(), 1);
+        assert_eq!(view.table.collect().unwrap().width(), 2);
+    }
+}
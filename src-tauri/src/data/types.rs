@@ -1,20 +1,645 @@
+use crate::ai::types::FilterSpec;
+use polars::prelude::{DataType, TimeUnit};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Type of a column as shown to users and the AI. All integer widths are
+/// `Integer`, but times, durations, categories and nested values are kept
+/// apart from text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogicalType {
+    Integer,
+    Float,
+    Decimal,
+    Boolean,
+    String,
+    Categorical,
+    Date,
+    Datetime,
+    Time,
+    Duration,
+    List,
+    Struct,
+    Binary,
+    Null,
+    Unknown,
+}
+
+impl LogicalType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Decimal => "decimal",
+            Self::Boolean => "boolean",
+            Self::String => "string",
+            Self::Categorical => "categorical",
+            Self::Date => "date",
+            Self::Datetime => "datetime",
+            Self::Time => "time",
+            Self::Duration => "duration",
+            Self::List => "list",
+            Self::Struct => "struct",
+            Self::Binary => "binary",
+            Self::Null => "null",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for LogicalType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&DataType> for LogicalType {
+    fn from(dtype: &DataType) -> Self {
+        match dtype {
+            dtype if dtype.is_integer() => Self::Integer,
+            dtype if dtype.is_float() => Self::Float,
+            DataType::Decimal(..) => Self::Decimal,
+            DataType::Boolean => Self::Boolean,
+            DataType::String => Self::String,
+            dtype if dtype.is_categorical() || dtype.is_enum() => Self::Categorical,
+            DataType::Date => Self::Date,
+            DataType::Datetime(..) => Self::Datetime,
+            DataType::Time => Self::Time,
+            DataType::Duration(_) => Self::Duration,
+            dtype if dtype.is_list() || dtype.is_array() => Self::List,
+            DataType::Struct(_) => Self::Struct,
+            DataType::Binary | DataType::BinaryOffset => Self::Binary,
+            DataType::Null => Self::Null,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// What a column's values mean, detected from a sample of them and the
+/// column name. Tells charts and the AI how a column may be aggregated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SemanticType {
+    Email,
+    Url,
+    /// ISO 3166 alpha-2 or alpha-3 country code.
+    CountryCode,
+    /// US state name or postal abbreviation.
+    UsState,
+    PostalCode,
+    Latitude,
+    Longitude,
+    Currency,
+    Percentage,
+    /// Keys and codes that are counted, never summed.
+    Identifier,
+    /// Sentences and notes rather than categories.
+    FreeText,
+}
+
+impl SemanticType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Url => "url",
+            Self::CountryCode => "country-code",
+            Self::UsState => "us-state",
+            Self::PostalCode => "postal-code",
+            Self::Latitude => "latitude",
+            Self::Longitude => "longitude",
+            Self::Currency => "currency",
+            Self::Percentage => "percentage",
+            Self::Identifier => "identifier",
+            Self::FreeText => "free-text",
+        }
+    }
+
+    /// Whether adding the values up gives a meaningful total.
+    pub fn is_additive(&self) -> bool {
+        !matches!(
+            self,
+            Self::PostalCode
+                | Self::Latitude
+                | Self::Longitude
+                | Self::Percentage
+                | Self::Identifier
+        )
+    }
+}
+
+impl fmt::Display for SemanticType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn time_unit_name(unit: &TimeUnit) -> &'static str {
+    match unit {
+        TimeUnit::Nanoseconds => "ns",
+        TimeUnit::Microseconds => "us",
+        TimeUnit::Milliseconds => "ms",
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColumnInfo {
     pub name: String,
-    pub dtype: String,
+    pub dtype: LogicalType,
+    /// Whether the column holds nulls. Scanned tables are not read to find
+    /// out, so all their columns are reported as nullable.
     pub nullable: bool,
+    /// Significant digits and digits after the point of decimal columns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<usize>,
+    /// Resolution of datetime and duration columns: `ns`, `us` or `ms`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_unit: Option<String>,
+    /// Timezone of datetime columns that have one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub semantic_type: Option<SemanticType>,
+    /// Formula of a calculated field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formula: Option<String>,
+}
+
+impl ColumnInfo {
+    pub fn new(name: &str, dtype: &DataType, nullable: bool) -> Self {
+        let mut info = Self {
+            name: name.to_string(),
+            dtype: LogicalType::from(dtype),
+            nullable,
+            precision: None,
+            scale: None,
+            time_unit: None,
+            timezone: None,
+            semantic_type: None,
+            formula: None,
+        };
+        match dtype {
+            DataType::Decimal(precision, scale) => {
+                info.precision = *precision;
+                info.scale = *scale;
+            }
+            DataType::Datetime(unit, timezone) => {
+                info.time_unit = Some(time_unit_name(unit).to_string());
+                info.timezone = timezone.as_ref().map(|tz| tz.to_string());
+            }
+            DataType::Duration(unit) => {
+                info.time_unit = Some(time_unit_name(unit).to_string());
+            }
+            _ => {}
+        }
+        info
+    }
+
+    /// The type with its precision or timezone, as in `decimal(10, 2)` or
+    /// `datetime, Europe/Paris`.
+    pub fn type_label(&self) -> String {
+        match (self.precision, self.scale, &self.timezone) {
+            (Some(precision), Some(scale), _) => {
+                format!("{}({}, {})", self.dtype, precision, scale)
+            }
+            (_, _, Some(timezone)) => format!("{}, {}", self.dtype, timezone),
+            _ => self.dtype.to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatasetInfo {
+    /// Name of the workspace source the tables belong to.
+    pub source: String,
     pub file_name: String,
     pub file_path: String,
     pub file_size: u64,
     pub row_count: usize,
     pub columns: Vec<ColumnInfo>,
+    /// Every table in the workspace.
     pub tables: Vec<String>,
+    /// The table `row_count` and `columns` describe.
+    pub active_table: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report: Option<IngestReport>,
+}
+
+/// Which rows `join_tables` keeps.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum JoinKind {
+    /// Rows with a match on both sides.
+    Inner,
+    /// Every left row, with right columns where a match exists.
+    Left,
+    /// Every right row, with left columns where a match exists.
+    Right,
+    /// Every row of both sides.
+    Outer,
+    /// Left rows that have a match, with left columns only.
+    Semi,
+    /// Left rows without a match, with left columns only.
+    Anti,
+}
+
+/// A left column matched against a right column.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JoinKey {
+    pub left: String,
+    pub right: String,
+}
+
+/// How well two tables matched on their keys. Rows with a null key never
+/// match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct JoinStats {
+    pub left_rows: usize,
+    pub right_rows: usize,
+    pub result_rows: usize,
+    /// Left rows without a matching right row.
+    pub unmatched_left: usize,
+    /// Right rows without a matching left row.
+    pub unmatched_right: usize,
+    /// Distinct keys found on more than one row of each side.
+    pub duplicate_left_keys: usize,
+    pub duplicate_right_keys: usize,
+    /// Most right rows a single left row matched. Above 1, left rows are
+    /// repeated in the result and sums over left columns are inflated.
+    pub max_fan_out: usize,
+}
+
+/// The table a join registered, with its match statistics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JoinedTable {
+    pub dataset: DatasetInfo,
+    pub stats: JoinStats,
+}
+
+/// How a derived table was computed from other tables, so that a project can
+/// compute it again when it is opened.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Derivation {
+    /// A `join_tables` result.
+    Join {
+        left: String,
+        right: String,
+        keys: Vec<JoinKey>,
+        kind: JoinKind,
+    },
+    /// A `union_tables` result.
+    Union {
+        tables: Vec<String>,
+        label_column: Option<String>,
+    },
+}
+
+impl Derivation {
+    /// Points references to table `from` at table `to`.
+    pub fn rename_table(&mut self, from: &str, to: &str) {
+        let tables = match self {
+            Derivation::Join { left, right, .. } => vec![left, right],
+            Derivation::Union { tables, .. } => tables.iter_mut().collect(),
+        };
+        for table in tables.into_iter().filter(|t| *t == from) {
+            *table = to.to_string();
+        }
+    }
+}
+
+/// A table registered by `DataState::add_derived_table`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DerivedTable {
+    pub name: String,
+    pub derivation: Derivation,
+}
+
+/// A column defined by a formula over a table's other columns, such as
+/// `revenue - cost`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CalculatedField {
+    pub name: String,
+    pub formula: String,
+}
+
+/// What replaces the nulls of a column in a fill-null step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum FillStrategy {
+    /// A text, number or boolean value.
+    Value { value: serde_json::Value },
+    /// The previous non-null value.
+    Forward,
+    /// The next non-null value.
+    Backward,
+    /// The column mean.
+    Mean,
+}
+
+/// A recorded change to a table, replayed on its source data in order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum TransformStep {
+    /// Keeps the rows matching the filter.
+    Filter(FilterSpec),
+    Rename {
+        column: String,
+        to: String,
+    },
+    Cast {
+        column: String,
+        to: ColumnType,
+    },
+    Drop {
+        columns: Vec<String>,
+    },
+    FillNull {
+        column: String,
+        strategy: FillStrategy,
+    },
+    /// Removes repeated rows, keeping the first. Rows are compared on
+    /// `columns`, or on every column when there are none.
+    Dedupe {
+        #[serde(default)]
+        columns: Vec<String>,
+    },
+    /// Adds a column computed by a formula, as calculated fields do.
+    Derive {
+        name: String,
+        formula: String,
+    },
+    /// Moves `columns` to the front in the given order. The other columns
+    /// follow in their current order.
+    Reorder {
+        columns: Vec<String>,
+    },
+    /// Splits a column's text into new columns named `into`, placed after it.
+    /// The last new column takes the rest of the text.
+    Split {
+        column: String,
+        by: SplitPattern,
+        into: Vec<String>,
+    },
+}
+
+/// What separates the parts of a column being split.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum SplitPattern {
+    Delimiter { delimiter: String },
+    Regex { pattern: String },
+}
+
+/// A table's recorded steps and whether they can be undone or redone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformPipeline {
+    pub table: String,
+    pub steps: Vec<TransformStep>,
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+/// A table after a change to its steps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformedTable {
+    pub dataset: DatasetInfo,
+    pub pipeline: TransformPipeline,
+}
+
+/// A table after a column type change, with the values that could not be
+/// converted and became null.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetypedTable {
+    pub dataset: DatasetInfo,
+    pub pipeline: TransformPipeline,
+    pub issue: Option<ColumnIssue>,
+}
+
+/// File format of a workspace source.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceFormat {
+    Csv,
+    FixedWidth,
+    Excel,
+    Json,
+    Parquet,
+    Arrow,
+    Sqlite,
+    Archive,
+    Folder,
+}
+
+/// The loader and options a source was read with, kept so that it can be
+/// read again the same way when its file changes or a project is opened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum LoadOptions {
+    Csv {
+        options: Option<CsvImportOptions>,
+        scan: bool,
+        number_locale: Option<NumberLocale>,
+    },
+    FixedWidth {
+        options: FixedWidthOptions,
+        number_locale: Option<NumberLocale>,
+    },
+    Excel {
+        options: ExcelImportOptions,
+        number_locale: Option<NumberLocale>,
+    },
+    Json {
+        options: JsonImportOptions,
+    },
+    Parquet {
+        scan: bool,
+    },
+    Arrow {
+        scan: bool,
+    },
+    Sqlite {
+        tables: Option<Vec<String>>,
+        query: Option<String>,
+    },
+    Archive,
+    Folder,
+}
+
+impl LoadOptions {
+    pub fn format(&self) -> SourceFormat {
+        match self {
+            LoadOptions::Csv { .. } => SourceFormat::Csv,
+            LoadOptions::FixedWidth { .. } => SourceFormat::FixedWidth,
+            LoadOptions::Excel { .. } => SourceFormat::Excel,
+            LoadOptions::Json { .. } => SourceFormat::Json,
+            LoadOptions::Parquet { .. } => SourceFormat::Parquet,
+            LoadOptions::Arrow { .. } => SourceFormat::Arrow,
+            LoadOptions::Sqlite { .. } => SourceFormat::Sqlite,
+            LoadOptions::Archive => SourceFormat::Archive,
+            LoadOptions::Folder => SourceFormat::Folder,
+        }
+    }
+}
+
+/// A loaded file and the qualified names of its tables.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceInfo {
+    pub name: String,
+    pub path: String,
+    pub format: SourceFormat,
+    pub tables: Vec<String>,
+}
+
+/// How a table's columns changed when its source was reloaded. A table that
+/// appeared lists all its columns as added; one that vanished, as removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaDiff {
+    pub table: String,
+    pub added: Vec<ColumnInfo>,
+    pub removed: Vec<String>,
+    /// Columns whose type changed, with their new type.
+    pub retyped: Vec<ColumnInfo>,
+}
+
+/// Payload of the `source-reloaded` event sent when a watched file changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceReload {
+    pub source: String,
+    pub path: String,
+    /// Tables whose columns changed; empty when only the rows did.
+    pub schema_changes: Vec<SchemaDiff>,
+    /// Set when the file could not be read again. The previous data is kept.
+    pub error: Option<String>,
+}
+
+/// Payload of the `load-progress` events sent while a file is loading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadProgress {
+    pub path: String,
+    /// Bytes of the file read so far, counted before decompression.
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+    /// Rows parsed, once known.
+    pub rows: Option<usize>,
+    /// Workbook sheet, database table or archive member being read.
+    pub sheet: Option<String>,
+}
+
+/// Statistics of every column of a table, from `profile_table`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableProfile {
+    pub table: String,
+    pub row_count: usize,
+    pub columns: Vec<ColumnProfile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub dtype: LogicalType,
+    pub null_count: usize,
+    /// Approximate number of distinct values, counting null as one.
+    pub distinct_count: usize,
+    /// Smallest and largest values, for columns whose values can be ordered.
+    pub min: Option<serde_json::Value>,
+    pub max: Option<serde_json::Value>,
+    /// Moments and quantiles of numeric columns.
+    pub mean: Option<f64>,
+    pub std: Option<f64>,
+    pub quantiles: Vec<QuantileValue>,
+    /// Most frequent non-null values, most frequent first.
+    pub top_values: Vec<ValueCount>,
+    /// Equal-width bins between the minimum and maximum of numeric columns.
+    pub histogram: Vec<HistogramBin>,
+    /// Length in characters of text values.
+    pub lengths: Option<LengthStats>,
+    /// Span of date columns, and of text columns that hold dates.
+    pub date_range: Option<DateRange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantileValue {
+    pub quantile: f64,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueCount {
+    pub value: serde_json::Value,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistogramBin {
+    pub start: f64,
+    pub end: f64,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LengthStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start: String,
+    pub end: String,
+}
+
+/// Data quality findings from an import, so dropped values are visible
+/// instead of silently turning into nulls.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IngestReport {
+    pub total_rows: usize,
+    /// Values that were present in the file but became null during typing.
+    pub nulls_introduced: usize,
+    pub column_issues: Vec<ColumnIssue>,
+    /// Total number of rows with the wrong field count.
+    pub malformed_row_count: usize,
+    /// The first malformed rows, for display.
+    pub malformed_rows: Vec<MalformedRow>,
+    /// Sheets or archive members that could not be read and were left out.
+    #[serde(default)]
+    pub skipped_tables: Vec<SkippedTable>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnIssue {
+    pub column: String,
+    pub target_type: LogicalType,
+    pub failed_count: usize,
+    /// Share of non-empty values that failed to convert (0.0-1.0).
+    pub failed_ratio: f64,
+    pub samples: Vec<RejectedValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedValue {
+    /// 1-based data row number.
+    pub row: usize,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MalformedRow {
+    /// 1-based line number in the file.
+    pub line: u64,
+    pub expected_fields: usize,
+    pub found_fields: usize,
+    pub raw: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedTable {
+    pub name: String,
+    pub reason: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +650,201 @@ pub struct DataPage {
     pub limit: usize,
 }
 
+/// Text encoding of a delimited file.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// Windows-1252, also used for Latin-1 files.
+    Windows1252,
+}
+
+/// How a CSV file should be parsed. Produced by `preview_csv` and accepted
+/// by `load_csv`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CsvImportOptions {
+    /// Field separator. Anything other than a single ASCII character, such as
+    /// `||` or `§`, is only supported when the file is read into memory.
+    pub delimiter: String,
+    pub quote_char: Option<char>,
+    pub has_header: bool,
+    /// Lines to skip before the header (e.g. report titles).
+    pub skip_rows: usize,
+    pub encoding: TextEncoding,
+}
+
+impl Default for CsvImportOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ",".to_string(),
+            quote_char: Some('"'),
+            has_header: true,
+            skip_rows: 0,
+            encoding: TextEncoding::Utf8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvPreview {
+    pub options: CsvImportOptions,
+    pub columns: Vec<ColumnInfo>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// A column of a fixed-width file, by character position on each line.
+/// Positions count from 0 and `end` is exclusive.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FixedWidthColumn {
+    /// Taken from the header line, or `column_<n>`, when omitted.
+    #[serde(default)]
+    pub name: Option<String>,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// How `load_fixed_width` cuts lines into columns.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct FixedWidthOptions {
+    /// Detected from the whitespace that lines up across the first lines
+    /// when empty.
+    pub columns: Vec<FixedWidthColumn>,
+    /// Whether the first line after `skip_rows` holds column names. Detected
+    /// when omitted.
+    pub has_header: Option<bool>,
+    /// Lines to skip before the header (e.g. report titles).
+    pub skip_rows: usize,
+    pub encoding: TextEncoding,
+}
+
+impl Default for FixedWidthOptions {
+    fn default() -> Self {
+        Self {
+            columns: Vec::new(),
+            has_header: None,
+            skip_rows: 0,
+            encoding: TextEncoding::Utf8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixedWidthPreview {
+    /// The options with detected columns and header filled in.
+    pub options: FixedWidthOptions,
+    pub columns: Vec<ColumnInfo>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// Which part of a workbook `load_excel` reads. With nothing selected, every
+/// sheet is loaded.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ExcelImportOptions {
+    /// Load only this sheet.
+    pub sheet: Option<String>,
+    /// A1-style cell range such as `B4:K900`, read from `sheet` or the first sheet.
+    pub range: Option<String>,
+    /// An Excel Table or defined name. Takes precedence over `sheet` and `range`.
+    pub table: Option<String>,
+    /// Sheet row holding the column names, numbered from 1 as in Excel.
+    /// Detected when omitted.
+    pub header_row: Option<u32>,
+}
+
+/// How `load_json` turns a document into a table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct JsonImportOptions {
+    /// JSON pointer to the record array inside a response envelope, such as
+    /// `/data/items`. The document root is used when omitted.
+    pub record_path: Option<String>,
+    /// Unnest objects into dotted column names such as `customer.address.city`.
+    pub flatten: bool,
+    /// Array columns to expand into one row per element.
+    pub explode: Vec<String>,
+}
+
+/// A table or view in a SQLite database.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SqliteObject {
+    pub name: String,
+    /// `table` or `view`.
+    pub kind: String,
+}
+
+/// Column type chosen by the user instead of the inferred one.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ColumnType {
+    Integer,
+    Float,
+    /// Parsed with a strftime format such as `%d.%m.%Y`; inferred when omitted.
+    Date {
+        #[serde(default)]
+        format: Option<String>,
+    },
+    Boolean,
+    /// Kept as text so the column is grouped on rather than summed, even when
+    /// its values are numeric codes.
+    Categorical,
+    String,
+}
+
+/// Column name to type, applied when a file is loaded.
+pub type SchemaOverrides = HashMap<String, ColumnType>;
+
+/// Decimal separator convention for formatted numbers. Spaces and
+/// apostrophes are accepted as digit grouping in both.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum NumberLocale {
+    /// `1,234.56`
+    DecimalPoint,
+    /// `1.234,56`
+    DecimalComma,
+}
+
+impl NumberLocale {
+    pub fn decimal_separator(self) -> char {
+        match self {
+            NumberLocale::DecimalPoint => '.',
+            NumberLocale::DecimalComma => ',',
+        }
+    }
+
+    pub fn thousands_separator(self) -> char {
+        match self {
+            NumberLocale::DecimalPoint => ',',
+            NumberLocale::DecimalComma => '.',
+        }
+    }
+}
+
+/// How a numeric column was written in the source file, e.g. `$1,234.50`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NumberFormat {
+    pub locale: NumberLocale,
+    /// Text before the number, such as a currency symbol.
+    pub prefix: String,
+    /// Text after the number, such as `%` or ` €`.
+    pub suffix: String,
+    pub thousands_separator: Option<char>,
+    pub decimals: usize,
+    /// Negatives written as `(300)` rather than `-300`.
+    pub parentheses_negative: bool,
+}
+
+/// Column name to original number format, for columns parsed from text.
+pub type NumberFormats = HashMap<String, NumberFormat>;
+
+/// Column name to the chrono format its values were parsed with, for date
+/// columns parsed from text.
+pub type DateFormats = HashMap<String, String>;
+
 /// Reason for data reduction - used for UI feedback.
 /// Matches the frontend enum for direct serialization.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
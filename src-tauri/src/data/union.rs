@@ -0,0 +1,386 @@
+//! # Table Unions
+//!
+//! Stacks tables whose columns do not fully line up, such as monthly exports
+//! that gained a column along the way. Columns are matched by name, missing
+//! columns are filled with nulls and differing types are promoted to a type
+//! that can hold both. The same reconciliation stacks loaded tables, such as
+//! the per-month sheets of a workbook, into a new table.
+
+use crate::data::ingest::dataset_info;
+use crate::data::state::{AppDataState, Table, TABLE_SEPARATOR};
+use crate::data::types::{DatasetInfo, Derivation};
+use crate::error::DataError;
+use polars::prelude::*;
+use tauri::State;
+
+/// Name of the column recording which file each unioned row came from.
+pub const SOURCE_FILE_COLUMN: &str = "__source_file";
+
+/// The type both columns can be cast to without losing values. Booleans widen
+/// to the number they meet, integers to the widest integer that holds both and
+/// integers and floats to float. Timestamps take the finer unit and, when
+/// their time zones differ, UTC; dates become midnight UTC. Anything else that
+/// differs falls back to text.
+pub fn promote(left: &DataType, right: &DataType) -> DataType {
+    match (left, right) {
+        (l, r) if l == r => l.clone(),
+        (DataType::Null, other) | (other, DataType::Null) => other.clone(),
+        (DataType::Boolean, other) | (other, DataType::Boolean) if other.is_numeric() => {
+            other.clone()
+        }
+        (l, r) if is_unsigned(l) && is_unsigned(r) => DataType::UInt64,
+        // Int64 cannot hold the upper half of UInt64.
+        (DataType::UInt64, r) if r.is_integer() => DataType::Float64,
+        (l, DataType::UInt64) if l.is_integer() => DataType::Float64,
+        (l, r) if l.is_integer() && r.is_integer() => DataType::Int64,
+        (l, r) if l.is_numeric() && r.is_numeric() => DataType::Float64,
+        (DataType::Datetime(left_unit, left_tz), DataType::Datetime(right_unit, right_tz)) => {
+            DataType::Datetime(
+                finer(*left_unit, *right_unit),
+                common_time_zone(left_tz, right_tz),
+            )
+        }
+        (DataType::Date, DataType::Datetime(unit, tz))
+        | (DataType::Datetime(unit, tz), DataType::Date) => {
+            DataType::Datetime(*unit, common_time_zone(&None, tz))
+        }
+        _ => DataType::String,
+    }
+}
+
+/// Unlike `DataType::is_unsigned_integer`, also true for the small unsigned
+/// types when their polars features are off.
+fn is_unsigned(dtype: &DataType) -> bool {
+    matches!(
+        dtype,
+        DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64
+    )
+}
+
+fn finer(left: TimeUnit, right: TimeUnit) -> TimeUnit {
+    use TimeUnit::*;
+    match (left, right) {
+        (Nanoseconds, _) | (_, Nanoseconds) => Nanoseconds,
+        (Microseconds, _) | (_, Microseconds) => Microseconds,
+        _ => Milliseconds,
+    }
+}
+
+/// Time zone for timestamps from two columns. Casting keeps the instants, and
+/// timestamps without a time zone are read as UTC, so where the zones differ
+/// UTC shows every value as it was stored.
+fn common_time_zone(left: &Option<TimeZone>, right: &Option<TimeZone>) -> Option<TimeZone> {
+    if left == right {
+        left.clone()
+    } else {
+        Some("UTC".into())
+    }
+}
+
+/// Every column across the frames, in order of first appearance, with its
+/// promoted type.
+pub fn unified_schema(frames: &[DataFrame]) -> Schema {
+    let mut schema = Schema::default();
+
+    for df in frames {
+        for column in df.get_columns() {
+            let dtype = match schema.get(column.name()) {
+                Some(existing) => promote(existing, column.dtype()),
+                None => column.dtype().clone(),
+            };
+            schema.with_column(column.name().clone(), dtype);
+        }
+    }
+
+    schema
+}
+
+/// Reshapes a frame to the schema: columns are put in schema order, cast to
+/// the schema type, and added as nulls where the frame lacks them.
+fn conform(df: &DataFrame, schema: &Schema) -> PolarsResult<DataFrame> {
+    let columns = schema
+        .iter()
+        .map(|(name, dtype)| match df.column(name) {
+            Ok(column) => column.cast(dtype),
+            Err(_) => Ok(Series::full_null(name.clone(), df.height(), dtype)),
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+
+    DataFrame::new(columns)
+}
+
+/// Stacks frames into one, reconciling their schemas.
+pub fn union_frames(frames: &[DataFrame]) -> PolarsResult<DataFrame> {
+    let schema = unified_schema(frames);
+    let mut result = DataFrame::empty_with_schema(&schema);
+
+    for df in frames {
+        result.vstack_mut(&conform(df, &schema)?)?;
+    }
+    result.align_chunks();
+
+    Ok(result)
+}
+
+/// Adds a leading column `name` holding `label` on every row.
+pub fn with_label(mut df: DataFrame, name: &str, label: &str) -> PolarsResult<DataFrame> {
+    let column = Series::new(name.into(), vec![label; df.height()]);
+    df.insert_column(0, column)?;
+    Ok(df)
+}
+
+/// Adds a leading column holding `file_name` on every row. A file that has a
+/// column of that name already is rejected rather than have it shadowed.
+pub fn with_source_file(df: DataFrame, file_name: &str) -> Result<DataFrame, DataError> {
+    if df.column(SOURCE_FILE_COLUMN).is_ok() {
+        return Err(DataError::ParseError(format!(
+            "'{}' already has a column named '{}'",
+            file_name, SOURCE_FILE_COLUMN
+        )));
+    }
+    Ok(with_label(df, SOURCE_FILE_COLUMN, file_name)?)
+}
+
+/// Label of a table's rows in a stacked table: the sheet or table name
+/// without its source, as in `Q1` for `budget/Q1`.
+fn table_label(name: &str) -> &str {
+    name.rsplit(TABLE_SEPARATOR).next().unwrap_or(name)
+}
+
+/// Stacks named tables into one, reconciling their schemas. With
+/// `label_column`, a leading column of that name records each row's table.
+pub fn stack_tables(
+    tables: &[(String, Table)],
+    label_column: Option<&str>,
+) -> Result<DataFrame, DataError> {
+    if tables.len() < 2 {
+        return Err(DataError::ParseError(
+            "Choose at least two tables to stack".to_string(),
+        ));
+    }
+
+    let mut frames = Vec::with_capacity(tables.len());
+    for (name, table) in tables {
+        let df = table.collect()?;
+        let df = match label_column {
+            Some(column) if df.column(column).is_ok() => {
+                return Err(DataError::ParseError(format!(
+                    "Table '{}' already has a column named '{}'",
+                    name, column
+                )));
+            }
+            Some(column) => with_label(df, column, table_label(name))?,
+            None => df,
+        };
+        frames.push(df);
+    }
+
+    Ok(union_frames(&frames)?)
+}
+
+/// Stacks the given tables, in order, into a new table `name`, which becomes
+/// the active table. Columns are matched by name; see `union_frames`. With
+/// `label_column`, a leading column of that name records each row's table.
+#[tauri::command]
+pub async fn union_tables(
+    tables: Vec<String>,
+    name: String,
+    label_column: Option<String>,
+    state: State<'_, AppDataState>,
+) -> Result<DatasetInfo, DataError> {
+    let named = {
+        let data_state = state
+            .lock()
+            .map_err(|e| DataError::ParseError(e.to_string()))?;
+        tables
+            .iter()
+            .map(|table| {
+                data_state
+                    .get_table(table)
+                    .cloned()
+                    .map(|t| (table.clone(), t))
+                    .ok_or_else(|| DataError::ParseError(format!("Table '{}' not found", table)))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let label_column = label_column
+        .as_deref()
+        .map(str::trim)
+        .filter(|column| !column.is_empty());
+    let df = stack_tables(&named, label_column)?;
+    let derivation = Derivation::Union {
+        tables,
+        label_column: label_column.map(str::to_string),
+    };
+
+    let mut data_state = state
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+    data_state
+        .add_derived_table(&name, Table::Materialized(df), derivation)
+        .map_err(DataError::ParseError)?;
+    data_state
+        .set_active_table(name.trim().to_string())
+        .map_err(DataError::ParseError)?;
+
+    dataset_info(&data_state, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_promote_widens_numbers_and_falls_back_to_text() {
+        assert_eq!(promote(&DataType::Int32, &DataType::Int64), DataType::Int64);
+        assert_eq!(
+            promote(&DataType::Int64, &DataType::Float64),
+            DataType::Float64
+        );
+        assert_eq!(
+            promote(&DataType::Null, &DataType::Boolean),
+            DataType::Boolean
+        );
+        assert_eq!(
+            promote(&DataType::Int64, &DataType::String),
+            DataType::String
+        );
+        assert_eq!(
+            promote(&DataType::Date, &DataType::Boolean),
+            DataType::String
+        );
+    }
+
+    #[test]
+    fn test_promote_keeps_every_value() {
+        assert_eq!(
+            promote(&DataType::Boolean, &DataType::Int32),
+            DataType::Int32
+        );
+        assert_eq!(
+            promote(&DataType::Float64, &DataType::Boolean),
+            DataType::Float64
+        );
+        assert_eq!(
+            promote(&DataType::UInt8, &DataType::UInt64),
+            DataType::UInt64
+        );
+        assert_eq!(promote(&DataType::UInt32, &DataType::Int8), DataType::Int64);
+        assert_eq!(
+            promote(&DataType::UInt64, &DataType::Int64),
+            DataType::Float64
+        );
+
+        let berlin = DataType::Datetime(TimeUnit::Milliseconds, Some("Europe/Berlin".into()));
+        let utc_ns = DataType::Datetime(TimeUnit::Nanoseconds, Some("UTC".into()));
+        let naive_us = DataType::Datetime(TimeUnit::Microseconds, None);
+        assert_eq!(promote(&berlin, &utc_ns), utc_ns);
+        assert_eq!(
+            promote(&naive_us, &berlin),
+            DataType::Datetime(TimeUnit::Microseconds, Some("UTC".into()))
+        );
+        assert_eq!(promote(&DataType::Date, &naive_us), naive_us);
+        assert_eq!(
+            promote(&berlin, &DataType::Date),
+            DataType::Datetime(TimeUnit::Milliseconds, Some("UTC".into()))
+        );
+    }
+
+    #[test]
+    fn test_union_adds_missing_columns_as_null() {
+        let january = df!("id" => [1i64, 2], "amount" => [10i64, 20]).unwrap();
+        let february = df!(
+            "id" => [3i64],
+            "amount" => [2.5f64],
+            "region" => ["North"]
+        )
+        .unwrap();
+
+        let january = with_source_file(january, "2024-01.csv").unwrap();
+        let february = with_source_file(february, "2024-02.csv").unwrap();
+        let df = union_frames(&[january, february]).unwrap();
+
+        assert_eq!(df.height(), 3);
+        assert_eq!(
+            df.get_column_names(),
+            [SOURCE_FILE_COLUMN, "id", "amount", "region"]
+        );
+        assert_eq!(df.column("amount").unwrap().dtype(), &DataType::Float64);
+        assert_eq!(df.column("region").unwrap().null_count(), 2);
+
+        let files = df.column(SOURCE_FILE_COLUMN).unwrap();
+        assert_eq!(files.str().unwrap().get(2), Some("2024-02.csv"));
+    }
+
+    #[test]
+    fn test_source_file_column_does_not_shadow_data() {
+        let df = df!(SOURCE_FILE_COLUMN => ["mine"]).unwrap();
+        assert!(with_source_file(df, "2024-01.csv").is_err());
+    }
+
+    #[test]
+    fn test_union_keeps_large_unsigned_and_mixed_unit_values() {
+        // The same instant, in milliseconds and in microseconds.
+        let stamp = |value: i64, unit| {
+            Series::new("at".into(), [value])
+                .cast(&DataType::Datetime(unit, None))
+                .unwrap()
+        };
+        let first = DataFrame::new(vec![
+            Series::new("count".into(), [u64::MAX]),
+            Series::new("flag".into(), [true]),
+            stamp(1_700_000_000_000, TimeUnit::Milliseconds),
+        ])
+        .unwrap();
+        let second = DataFrame::new(vec![
+            Series::new("count".into(), [-1i64]),
+            Series::new("flag".into(), [7i32]),
+            stamp(1_700_000_000_000_000, TimeUnit::Microseconds),
+        ])
+        .unwrap();
+
+        let df = union_frames(&[first, second]).unwrap();
+        let count = df.column("count").unwrap().f64().unwrap();
+        assert_eq!(count.get(0), Some(u64::MAX as f64));
+        assert_eq!(count.get(1), Some(-1.0));
+        let flags: Vec<Option<i32>> = df
+            .column("flag")
+            .unwrap()
+            .i32()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(flags, [Some(1), Some(7)]);
+        let at = df.column("at").unwrap();
+        assert_eq!(
+            at.dtype(),
+            &DataType::Datetime(TimeUnit::Microseconds, None)
+        );
+        let micros = at.to_physical_repr().into_owned();
+        assert_eq!(micros.get(0).unwrap(), micros.get(1).unwrap());
+    }
+
+    #[test]
+    fn test_stack_tables_labels_rows_by_sheet() {
+        let tables = vec![
+            (
+                "sales/January".to_string(),
+                Table::Materialized(df!("region" => ["North"], "units" => [3i32]).unwrap()),
+            ),
+            (
+                "sales/February".to_string(),
+                Table::Materialized(df!("units" => [4.5f64], "region" => ["South"]).unwrap()),
+            ),
+        ];
+
+        let df = stack_tables(&tables, Some("month")).unwrap();
+        assert_eq!(df.get_column_names(), ["month", "region", "units"]);
+        assert_eq!(df.column("units").unwrap().dtype(), &DataType::Float64);
+        let months = df.column("month").unwrap();
+        assert_eq!(months.str().unwrap().get(1), Some("February"));
+
+        assert!(stack_tables(&tables, Some("region")).is_err());
+        assert!(stack_tables(&tables[..1], None).is_err());
+    }
+}
@@ -0,0 +1,376 @@
+//! # Source Watching
+//!
+//! Opt-in live reload of workspace sources. A watched source is read again
+//! with its original loader options whenever its file changes on disk, and a
+//! `source-reloaded` event reports any schema changes so the frontend can
+//! refresh its charts.
+
+use crate::data::ingest;
+use crate::data::state::AppDataState;
+use crate::data::types::{ColumnInfo, SchemaDiff, SourceReload};
+use crate::error::DataError;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Event emitted after a watched source has been reloaded.
+pub const RELOAD_EVENT: &str = "source-reloaded";
+
+/// Quiet period after the last change before a source is reloaded, so a file
+/// that is still being written is read once, when it is complete.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Longest a change waits for a quiet period. A file that is written to
+/// without pause, such as a log, is still reloaded this often.
+const MAX_WAIT: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+pub struct SourceWatcher {
+    /// Started on the first `watch_source` call.
+    watcher: Option<RecommendedWatcher>,
+    /// Paths of the watched sources, shared with the reload thread.
+    paths: Arc<Mutex<Vec<String>>>,
+}
+
+pub type AppWatchState = Mutex<SourceWatcher>;
+
+/// Directory watched for a source. Watching the directory rather than the
+/// file keeps working when a script replaces the file instead of rewriting
+/// it. For glob patterns this is the deepest directory without wildcards.
+fn watch_dir(path: &str) -> PathBuf {
+    let path = Path::new(path);
+    let mut dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(Path::new("."))
+    };
+    while ingest::is_pattern(&dir.to_string_lossy()) {
+        dir = dir.parent().unwrap_or(Path::new("."));
+    }
+    dir.to_path_buf()
+}
+
+/// Whether a changed file belongs to the source read from `source_path`.
+fn affects(source_path: &str, changed: &Path) -> bool {
+    let source = Path::new(source_path);
+    if source.is_dir() {
+        changed.parent() == Some(source)
+    } else if ingest::is_pattern(source_path) {
+        glob::Pattern::new(source_path).is_ok_and(|pattern| pattern.matches_path(changed))
+    } else {
+        changed == source
+    }
+}
+
+/// Column changes between two snapshots of a source's tables.
+fn schema_changes(
+    before: &[(String, Vec<ColumnInfo>)],
+    after: &[(String, Vec<ColumnInfo>)],
+) -> Vec<SchemaDiff> {
+    let mut tables: Vec<&String> = after.iter().map(|(table, _)| table).collect();
+    for (table, _) in before {
+        if !tables.contains(&table) {
+            tables.push(table);
+        }
+    }
+
+    let columns_of = |snapshot: &'_ [(String, Vec<ColumnInfo>)], table: &str| {
+        snapshot
+            .iter()
+            .find(|(name, _)| name == table)
+            .map(|(_, columns)| columns.clone())
+            .unwrap_or_default()
+    };
+
+    tables
+        .into_iter()
+        .filter_map(|table| {
+            let old = columns_of(before, table);
+            let new = columns_of(after, table);
+            let find_old = |name: &str| old.iter().find(|c| c.name == name);
+
+            let diff = SchemaDiff {
+                table: table.clone(),
+                added: new
+                    .iter()
+                    .filter(|c| find_old(&c.name).is_none())
+                    .cloned()
+                    .collect(),
+                removed: old
+                    .iter()
+                    .filter(|c| !new.iter().any(|n| n.name == c.name))
+                    .map(|c| c.name.clone())
+                    .collect(),
+                retyped: new
+                    .iter()
+                    .filter(|c| find_old(&c.name).is_some_and(|o| o.dtype != c.dtype))
+                    .cloned()
+                    .collect(),
+            };
+            let changed =
+                !diff.added.is_empty() || !diff.removed.is_empty() || !diff.retyped.is_empty();
+            changed.then_some(diff)
+        })
+        .collect()
+}
+
+type SourceSnapshot = (String, Vec<(String, Vec<ColumnInfo>)>);
+
+/// The name of the source loaded from `path` and the columns of its tables.
+fn snapshot(state: &AppDataState, path: &str) -> Option<SourceSnapshot> {
+    let data_state = state.lock().ok()?;
+    let source = data_state.sources.iter().find(|s| s.path == path)?;
+
+    let tables = source
+        .tables
+        .iter()
+        .filter_map(|name| {
+            let columns = data_state.get_table(name)?.columns().ok()?;
+            Some((name.clone(), columns))
+        })
+        .collect();
+    Some((source.name.clone(), tables))
+}
+
+fn reload(app: &AppHandle, path: &str) {
+    let state = app.state::<AppDataState>();
+    // The source may have been removed since it was watched.
+    let Some((name, before)) = snapshot(&state, path) else {
+        return;
+    };
+
+    let result =
+        tauri::async_runtime::block_on(ingest::reload_source(&name, app.clone(), state.clone()));
+    let reload = match result {
+        Ok(()) => {
+            let (name, after) = snapshot(&state, path).unwrap_or((name, Vec::new()));
+            SourceReload {
+                schema_changes: schema_changes(&before, &after),
+                source: name,
+                path: path.to_string(),
+                error: None,
+            }
+        }
+        Err(e) => SourceReload {
+            source: name,
+            path: path.to_string(),
+            schema_changes: Vec::new(),
+            error: Some(e.to_string()),
+        },
+    };
+
+    let _ = app.emit(RELOAD_EVENT, reload);
+}
+
+/// Removes and returns the pending sources that have waited `MAX_WAIT` since
+/// their first unreloaded change.
+fn take_overdue(pending: &mut Vec<(String, Instant)>) -> Vec<String> {
+    let (overdue, waiting) = pending
+        .drain(..)
+        .partition::<Vec<_>, _>(|(_, since)| since.elapsed() >= MAX_WAIT);
+    *pending = waiting;
+    overdue.into_iter().map(|(path, _)| path).collect()
+}
+
+/// Collects the sources touched by file events and reloads each once its
+/// files have been quiet for `DEBOUNCE`, or have kept changing for
+/// `MAX_WAIT`. Ends when the watcher is dropped.
+fn reload_loop(
+    app: AppHandle,
+    paths: Arc<Mutex<Vec<String>>>,
+    events: Receiver<notify::Result<Event>>,
+) {
+    // Sources with unreloaded changes, and when the first of them came in.
+    let mut pending: Vec<(String, Instant)> = Vec::new();
+
+    loop {
+        match events.recv_timeout(DEBOUNCE) {
+            Ok(Ok(event)) => {
+                let relevant = matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                );
+                if relevant {
+                    let Ok(watched) = paths.lock().map(|p| p.clone()) else {
+                        return;
+                    };
+                    for path in watched {
+                        let touched = event.paths.iter().any(|changed| affects(&path, changed));
+                        if touched && !pending.iter().any(|(p, _)| *p == path) {
+                            pending.push((path, Instant::now()));
+                        }
+                    }
+                }
+            }
+            Ok(Err(_)) => {}
+            Err(RecvTimeoutError::Timeout) => {
+                for (path, _) in pending.drain(..) {
+                    reload(&app, &path);
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        for path in take_overdue(&mut pending) {
+            reload(&app, &path);
+        }
+    }
+}
+
+fn source_path(data: &AppDataState, name: &str) -> Result<String, DataError> {
+    let data_state = data
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+    data_state
+        .get_source(name)
+        .map(|s| s.path.clone())
+        .ok_or_else(|| DataError::ParseError(format!("Source '{}' not found", name)))
+}
+
+/// Reloads a source whenever its file, or any file matching its folder or
+/// pattern, changes on disk. Each reload emits a `source-reloaded` event.
+#[tauri::command]
+pub async fn watch_source(
+    name: String,
+    app: AppHandle,
+    data: State<'_, AppDataState>,
+    watch: State<'_, AppWatchState>,
+) -> Result<(), DataError> {
+    let path = source_path(&data, &name)?;
+    let mut guard = watch
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+    let state = &mut *guard;
+
+    let watcher = match &mut state.watcher {
+        Some(watcher) => watcher,
+        None => {
+            let (sender, events) = mpsc::channel();
+            let watcher = notify::recommended_watcher(sender)?;
+            let paths = state.paths.clone();
+            thread::spawn(move || reload_loop(app, paths, events));
+            state.watcher.insert(watcher)
+        }
+    };
+
+    let mut paths = state
+        .paths
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+    if paths.contains(&path) {
+        return Ok(());
+    }
+    let dir = watch_dir(&path);
+    if !paths.iter().any(|p| watch_dir(p) == dir) {
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    }
+    paths.push(path);
+    Ok(())
+}
+
+/// Stops watching `path`, and its directory once no other watched source is
+/// in it. Does nothing if the path is not watched.
+pub(crate) fn stop_watching(watch: &AppWatchState, path: &str) -> Result<(), DataError> {
+    let mut guard = watch
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+    let state = &mut *guard;
+
+    let mut paths = state
+        .paths
+        .lock()
+        .map_err(|e| DataError::ParseError(e.to_string()))?;
+    paths.retain(|p| p != path);
+
+    let dir = watch_dir(path);
+    if let Some(watcher) = &mut state.watcher {
+        if !paths.iter().any(|p| watch_dir(p) == dir) {
+            // The directory may already be gone.
+            let _ = watcher.unwatch(&dir);
+        }
+    }
+    Ok(())
+}
+
+/// Stops reloading a source when its file changes.
+#[tauri::command]
+pub async fn unwatch_source(
+    name: String,
+    data: State<'_, AppDataState>,
+    watch: State<'_, AppWatchState>,
+) -> Result<(), DataError> {
+    let path = source_path(&data, &name)?;
+    stop_watching(&watch, &path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::DataType;
+
+    fn column(name: &str, dtype: DataType) -> ColumnInfo {
+        ColumnInfo::new(name, &dtype, true)
+    }
+
+    #[test]
+    fn test_schema_changes_reports_added_removed_and_retyped_columns() {
+        let before = vec![(
+            "sales".to_string(),
+            vec![
+                column("id", DataType::Int64),
+                column("amount", DataType::Int64),
+                column("note", DataType::String),
+            ],
+        )];
+        let after = vec![(
+            "sales".to_string(),
+            vec![
+                column("id", DataType::Int64),
+                column("amount", DataType::Float64),
+                column("region", DataType::String),
+            ],
+        )];
+
+        let changes = schema_changes(&before, &after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].added[0].name, "region");
+        assert_eq!(changes[0].removed, ["note"]);
+        assert_eq!(changes[0].retyped[0].name, "amount");
+
+        assert!(schema_changes(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_constantly_changing_sources_are_reloaded_after_max_wait() {
+        let mut pending = vec![
+            ("/logs/app.log".to_string(), Instant::now() - MAX_WAIT),
+            ("/data/sales.csv".to_string(), Instant::now()),
+        ];
+
+        assert_eq!(take_overdue(&mut pending), ["/logs/app.log"]);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].0, "/data/sales.csv");
+    }
+
+    #[test]
+    fn test_glob_sources_watch_their_directory() {
+        assert_eq!(
+            watch_dir("/data/exports/2024-*.csv"),
+            Path::new("/data/exports")
+        );
+        assert_eq!(watch_dir("/data/*/sales.csv"), Path::new("/data"));
+        assert!(affects(
+            "/data/exports/2024-*.csv",
+            Path::new("/data/exports/2024-03.csv")
+        ));
+        assert!(!affects(
+            "/data/exports/2024-*.csv",
+            Path::new("/data/exports/2023-12.csv")
+        ));
+        assert!(affects("/data/sales.csv", Path::new("/data/sales.csv")));
+    }
+}
@@ -101,6 +101,30 @@ impl From<calamine::XlsxError> for DataError {
     }
 }
 
+impl From<csv::Error> for DataError {
+    fn from(err: csv::Error) -> Self {
+        DataError::ParseError(err.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for DataError {
+    fn from(err: rusqlite::Error) -> Self {
+        DataError::ReadError(err.to_string())
+    }
+}
+
+impl From<notify::Error> for DataError {
+    fn from(err: notify::Error) -> Self {
+        DataError::ReadError(err.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for DataError {
+    fn from(err: zip::result::ZipError) -> Self {
+        DataError::ReadError(err.to_string())
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum AIError {
     #[error("API request failed: {0}")]
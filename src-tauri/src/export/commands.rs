@@ -1,4 +1,6 @@
+use crate::data::numeric::format_number;
 use crate::data::state::AppDataState;
+use crate::data::types::{NumberFormat, NumberFormats};
 use crate::error::DataError;
 use image::ImageReader;
 use polars::prelude::*;
@@ -19,6 +21,53 @@ fn df_to_csv_string(df: &DataFrame) -> Result<String, DataError> {
     String::from_utf8(buffer).map_err(|e| DataError::WriteError(e.to_string()))
 }
 
+/// Writes numeric columns that were parsed from formatted text (`$1,234.50`)
+/// back as text in that format.
+fn restore_number_formats(
+    mut df: DataFrame,
+    formats: Option<&NumberFormats>,
+) -> Result<DataFrame, DataError> {
+    for (name, format) in formats.into_iter().flatten() {
+        let Ok(values) = df.column(name).and_then(|c| c.f64()) else {
+            continue;
+        };
+        let text: StringChunked = values
+            .into_iter()
+            .map(|v| v.map(|v| format_number(v, format)))
+            .collect();
+        df.replace(name, text.with_name(name.as_str().into()).into_series())?;
+    }
+    Ok(df)
+}
+
+/// Excel number format equivalent to a source number format.
+fn excel_number_format(format: &NumberFormat) -> String {
+    let quoted = |s: &str| {
+        if s.is_empty() {
+            String::new()
+        } else {
+            format!("\"{}\"", s)
+        }
+    };
+
+    let mut digits = if format.thousands_separator.is_some() {
+        "#,##0".to_string()
+    } else {
+        "0".to_string()
+    };
+    if format.decimals > 0 {
+        digits.push('.');
+        digits.push_str(&"0".repeat(format.decimals));
+    }
+
+    let positive = format!("{}{}{}", quoted(&format.prefix), digits, quoted(&format.suffix));
+    if format.parentheses_negative {
+        format!("{0};({0})", positive)
+    } else {
+        positive
+    }
+}
+
 fn estimate_column_width(col: &Series, max_rows_to_check: usize) -> f64 {
     let header_len = col.name().len();
     let mut max_len = header_len;
@@ -48,7 +97,12 @@ pub async fn export_csv(
     path: Option<String>,
 ) -> Result<String, DataError> {
     let data_state = state.lock().map_err(|e| DataError::ParseError(e.to_string()))?;
-    let df = data_state.get_active_dataframe().ok_or(DataError::NoData)?;
+    let df = data_state.get_active_table().ok_or(DataError::NoData)?.collect()?;
+    let formats = data_state
+        .active_table
+        .as_deref()
+        .map(|name| data_state.view_number_formats(name));
+    let df = restore_number_formats(df, formats.as_ref())?;
 
     let save_path = match path {
         Some(p) => PathBuf::from(p),
@@ -67,7 +121,7 @@ pub async fn export_csv(
         }
     };
 
-    let csv_content = df_to_csv_string(df)?;
+    let csv_content = df_to_csv_string(&df)?;
     let mut file = File::create(&save_path).map_err(|e| DataError::WriteError(e.to_string()))?;
     file.write_all(csv_content.as_bytes())
         .map_err(|e| DataError::WriteError(e.to_string()))?;
@@ -82,7 +136,7 @@ pub async fn export_excel(
     path: Option<String>,
 ) -> Result<String, DataError> {
     let data_state = state.lock().map_err(|e| DataError::ParseError(e.to_string()))?;
-    let df = data_state.get_active_dataframe().ok_or(DataError::NoData)?;
+    let df = data_state.get_active_table().ok_or(DataError::NoData)?.collect()?;
 
     let save_path = match path {
         Some(p) => PathBuf::from(p),
@@ -106,6 +160,21 @@ pub async fn export_excel(
 
     let header_format = Format::new().set_bold();
 
+    let formats = data_state
+        .active_table
+        .as_deref()
+        .map(|name| data_state.view_number_formats(name));
+    let number_formats: Vec<Option<Format>> = df
+        .get_column_names()
+        .iter()
+        .map(|name| {
+            formats
+                .as_ref()
+                .and_then(|f| f.get(name.as_str()))
+                .map(|f| Format::new().set_num_format(excel_number_format(f)))
+        })
+        .collect();
+
     let columns: Vec<&Series> = df.get_columns().iter().map(|c| c.as_series()).collect();
     for (col_idx, col) in columns.iter().enumerate() {
         worksheet
@@ -155,9 +224,14 @@ pub async fn export_excel(
                 Some(AnyValue::Float32(v)) => {
                     worksheet.write_number(row, col_num, v as f64).ok();
                 }
-                Some(AnyValue::Float64(v)) => {
-                    worksheet.write_number(row, col_num, v).ok();
-                }
+                Some(AnyValue::Float64(v)) => match &number_formats[col_idx] {
+                    Some(format) => {
+                        worksheet.write_number_with_format(row, col_num, v, format).ok();
+                    }
+                    None => {
+                        worksheet.write_number(row, col_num, v).ok();
+                    }
+                },
                 Some(AnyValue::Boolean(v)) => {
                     worksheet.write_boolean(row, col_num, v).ok();
                 }
@@ -6,10 +6,24 @@ mod project;
 mod settings;
 
 use ai::{process_ai_chat, process_ai_query};
+use data::columns::{cast_column, drop_columns, rename_column, reorder_columns, split_column};
+use data::formula::{add_calculated_field, remove_calculated_field};
 use data::ingest::{
-    clear_data, get_data_page, list_excel_sheets, load_csv, load_excel, load_json, set_active_table,
+    add_source, append_source, clear_data, get_data_page, list_excel_sheets, list_excel_tables,
+    list_sources, list_sqlite_tables, load_archive, load_arrow, load_csv, load_excel,
+    load_fixed_width, load_folder, load_json, load_parquet, load_sqlite, preview_csv,
+    preview_fixed_width, remove_source, rename_source, restore_source, set_active_table,
 };
+use data::join::join_tables;
+use data::profile::{profile_table, AppProfileCache};
+use data::progress::{cancel_load, AppLoadState};
 use data::state::AppDataState;
+use data::transform::{
+    add_transform_step, get_transform_steps, move_transform_step, redo_transform_step,
+    remove_transform_step, set_transform_steps, undo_transform_step,
+};
+use data::union::union_tables;
+use data::watch::{unwatch_source, watch_source, AppWatchState};
 use data::{
     execute_progressive_query, execute_scatter_query, execute_table_query,
     execute_visualization_query,
@@ -31,11 +45,47 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .invoke_handler(tauri::generate_handler![
             load_csv,
+            preview_csv,
+            load_fixed_width,
+            preview_fixed_width,
             load_excel,
             load_json,
+            load_parquet,
+            load_arrow,
             list_excel_sheets,
+            list_excel_tables,
+            load_sqlite,
+            list_sqlite_tables,
+            load_archive,
+            load_folder,
+            append_source,
+            cancel_load,
+            watch_source,
+            unwatch_source,
             set_active_table,
+            list_sources,
+            add_source,
+            restore_source,
+            remove_source,
+            rename_source,
             get_data_page,
+            profile_table,
+            join_tables,
+            union_tables,
+            add_calculated_field,
+            remove_calculated_field,
+            get_transform_steps,
+            add_transform_step,
+            remove_transform_step,
+            move_transform_step,
+            set_transform_steps,
+            undo_transform_step,
+            redo_transform_step,
+            rename_column,
+            drop_columns,
+            cast_column,
+            reorder_columns,
+            split_column,
             clear_data,
             get_settings,
             update_settings,
@@ -60,6 +110,9 @@ pub fn run() {
         .setup(|app| {
             let data_state = AppDataState::default();
             app.manage(data_state);
+            app.manage(AppWatchState::default());
+            app.manage(AppLoadState::default());
+            app.manage(AppProfileCache::default());
 
             Ok(())
         })
@@ -4,7 +4,7 @@ use crate::data::state::AppDataState;
 use crate::error::ProjectError;
 use schema::{
     DataSourceType, DatasetSchema, InsyteProject, OpenProjectResponse, ProjectData,
-    QueryHistoryItem, RecentProject, Worksheet,
+    ProjectSource, QueryHistoryItem, RecentProject, Worksheet,
 };
 use std::fs;
 use std::path::PathBuf;
@@ -71,40 +71,19 @@ pub async fn save_project(
         .lock()
         .map_err(|e| ProjectError::WriteError(e.to_string()))?;
 
-    let df = data_state.get_active_dataframe().ok_or(ProjectError::NoData)?;
-    let file_path = data_state.get_file_path().cloned();
-
-    let columns: Vec<_> = df
-        .get_columns()
-        .iter()
-        .map(|col| {
-            let dtype = match col.dtype() {
-                polars::prelude::DataType::Int8
-                | polars::prelude::DataType::Int16
-                | polars::prelude::DataType::Int32
-                | polars::prelude::DataType::Int64
-                | polars::prelude::DataType::UInt8
-                | polars::prelude::DataType::UInt16
-                | polars::prelude::DataType::UInt32
-                | polars::prelude::DataType::UInt64 => "integer",
-                polars::prelude::DataType::Float32 | polars::prelude::DataType::Float64 => "float",
-                polars::prelude::DataType::Boolean => "boolean",
-                polars::prelude::DataType::Date | polars::prelude::DataType::Datetime(_, _) => {
-                    "date"
-                }
-                _ => "string",
-            };
-            schema::ColumnSchema {
-                name: col.name().to_string(),
-                dtype: dtype.to_string(),
-                nullable: true,
-            }
-        })
-        .collect();
+    let table = data_state.get_active_table().ok_or(ProjectError::NoData)?;
+    let active_source = data_state.active_source();
+
+    let columns = table
+        .columns()
+        .map_err(|e| ProjectError::WriteError(e.to_string()))?;
+    let row_count = table
+        .row_count()
+        .map_err(|e| ProjectError::WriteError(e.to_string()))?;
 
     let schema = DatasetSchema {
         columns,
-        row_count: df.height(),
+        row_count,
     };
 
     let project = InsyteProject {
@@ -113,8 +92,35 @@ pub async fn save_project(
         modified_at: chrono::Utc::now(),
         data: ProjectData {
             source_type: DataSourceType::Path,
-            source_path: file_path,
+            source_path: active_source.map(|s| s.path.clone()),
             schema,
+            schema_overrides: active_source
+                .map(|s| s.schema_overrides.clone())
+                .unwrap_or_default(),
+            sources: data_state
+                .sources
+                .iter()
+                .map(|s| ProjectSource {
+                    name: s.name.clone(),
+                    path: s.path.clone(),
+                    format: s.format,
+                    schema_overrides: s.schema_overrides.clone(),
+                    load: Some(s.load.clone()),
+                })
+                .collect(),
+            derived_tables: data_state.derived_tables.clone(),
+            active_table: data_state.active_table.clone(),
+            transform_steps: data_state
+                .pipelines
+                .iter()
+                .filter(|(_, pipeline)| !pipeline.steps.is_empty())
+                .map(|(table, pipeline)| (table.clone(), pipeline.steps.clone()))
+                .collect(),
+            calculated_fields: data_state
+                .calculated_fields
+                .iter()
+                .map(|(table, fields)| (table.clone(), fields.clone()))
+                .collect(),
         },
         visualization: None,
         worksheets,
@@ -1,7 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use crate::ai::types::VisualizationSpec;
+use crate::data::types::{
+    CalculatedField, ColumnInfo, DerivedTable, LoadOptions, SchemaOverrides, SourceFormat,
+    TransformStep,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InsyteProject {
@@ -37,6 +42,37 @@ pub struct ProjectData {
     pub source_type: DataSourceType,
     pub source_path: Option<String>,
     pub schema: DatasetSchema,
+    /// Column types chosen by the user, reapplied when the source is reloaded.
+    #[serde(default, skip_serializing_if = "SchemaOverrides::is_empty")]
+    pub schema_overrides: SchemaOverrides,
+    /// Every file in the workspace. Older projects only have `source_path`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<ProjectSource>,
+    /// Join and union results, computed again from the sources in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derived_tables: Vec<DerivedTable>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_table: Option<String>,
+    /// Transformation steps by table, replayed on the sources once loaded.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub transform_steps: BTreeMap<String, Vec<TransformStep>>,
+    /// Calculated fields by table, added again after the steps are replayed.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub calculated_fields: BTreeMap<String, Vec<CalculatedField>>,
+}
+
+/// A workspace source, reloaded when the project is opened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSource {
+    pub name: String,
+    pub path: String,
+    pub format: SourceFormat,
+    #[serde(default, skip_serializing_if = "SchemaOverrides::is_empty")]
+    pub schema_overrides: SchemaOverrides,
+    /// Loader options the source was read with. Older projects reload their
+    /// sources with default options.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load: Option<LoadOptions>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,17 +83,10 @@ pub enum DataSourceType {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatasetSchema {
-    pub columns: Vec<ColumnSchema>,
+    pub columns: Vec<ColumnInfo>,
     pub row_count: usize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ColumnSchema {
-    pub name: String,
-    pub dtype: String,
-    pub nullable: bool,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryHistoryItem {
     pub id: String,
@@ -10,6 +10,10 @@ pub struct AppSettings {
     pub auto_save: bool,
     pub theme: String,
     pub max_preview_rows: usize,
+    /// Whether CSV, Parquet and Arrow files are queried from disk instead of
+    /// being loaded into memory.
+    #[serde(default)]
+    pub scan_files: bool,
 }
 
 impl Default for AppSettings {
@@ -20,6 +24,7 @@ impl Default for AppSettings {
             auto_save: true,
             theme: "light".to_string(),
             max_preview_rows: 100,
+            scan_files: false,
         }
     }
 }
@@ -1,12 +1,22 @@
 import { open } from "@tauri-apps/plugin-dialog";
+import toast from "react-hot-toast";
 import { AppShell } from "./components/layout";
 import { WelcomeScreen } from "./components/welcome";
 import { SettingsDialog } from "./components/settings";
 import { useAppStore } from "./stores/appStore";
 import { useDataStore } from "./stores/dataStore";
 import { useVizBuilderStore } from "./stores/vizBuilderStore";
-import { loadFile, getDataPage } from "./services/fileService";
-import { openProject } from "./services/projectService";
+import {
+    describeLoadProgress,
+    describeSkippedTables,
+    getDataPage,
+    loadFileWithProgress,
+} from "./services/fileService";
+import {
+    describeSkippedRestore,
+    openProject,
+    restoreProjectSources,
+} from "./services/projectService";
 
 function App() {
     const {
@@ -18,6 +28,7 @@ function App() {
         setQueryHistory,
         setProjectPath,
         setProcessing,
+        setLoadingPath,
         setError,
         setWorksheets,
     } = useAppStore();
@@ -33,7 +44,7 @@ function App() {
             const selected = await open({
                 multiple: false,
                 filters: [
-                    { name: "Data Files", extensions: ["csv", "xlsx", "xls", "json"] },
+                    { name: "Data Files", extensions: ["csv", "tsv", "txt", "xlsx", "xlsm", "xls", "xlsb", "ods", "json", "parquet", "arrow", "feather", "sqlite", "sqlite3", "db"] },
                 ],
             });
 
@@ -42,7 +53,14 @@ function App() {
                 
                 resetVizBuilder();
                 
-                const info = await loadFile(selected);
+                setLoadingPath(selected);
+                const info = await loadFileWithProgress(selected, (progress) =>
+                    setProcessing(true, describeLoadProgress(progress))
+                ).finally(() => setLoadingPath(null));
+                const skipped = describeSkippedTables(info);
+                if (skipped) {
+                    toast.error(skipped);
+                }
 
                 setDataset({
                     fileName: info.fileName,
@@ -51,13 +69,17 @@ function App() {
                     rowCount: info.rowCount,
                     fileSize: info.fileSize,
                     tables: info.tables,
+                    activeTable: info.activeTable,
                 });
 
                 const page = await getDataPage(0, 10000, info.columns);
                 setRowData(page.rows, page.totalRows);
             }
         } catch (err) {
-            setError(err instanceof Error ? err.message : String(err));
+            const message = err instanceof Error ? err.message : String(err);
+            if (!message.includes("cancelled")) {
+                setError(message);
+            }
         } finally {
             setProcessing(false);
         }
@@ -68,8 +90,13 @@ function App() {
             setProcessing(true, "Opening project...");
             const { path, project } = await openProject();
 
-            if (project.data.sourcePath) {
-                const info = await loadFile(project.data.sourcePath);
+            const restored = await restoreProjectSources(project.data);
+            const skipped = describeSkippedRestore(restored);
+            if (skipped) {
+                toast.error(skipped);
+            }
+            const info = restored.info;
+            if (info) {
                 setDataset({
                     fileName: info.fileName,
                     filePath: info.filePath,
@@ -77,6 +104,7 @@ function App() {
                     rowCount: info.rowCount,
                     fileSize: info.fileSize,
                     tables: info.tables,
+                    activeTable: info.activeTable,
                 });
 
                 const page = await getDataPage(0, 10000, info.columns);
@@ -104,8 +132,13 @@ function App() {
             setProcessing(true, "Opening project...");
             const { project } = await openProject(path);
 
-            if (project.data.sourcePath) {
-                const info = await loadFile(project.data.sourcePath);
+            const restored = await restoreProjectSources(project.data);
+            const skipped = describeSkippedRestore(restored);
+            if (skipped) {
+                toast.error(skipped);
+            }
+            const info = restored.info;
+            if (info) {
                 setDataset({
                     fileName: info.fileName,
                     filePath: info.filePath,
@@ -113,6 +146,7 @@ function App() {
                     rowCount: info.rowCount,
                     fileSize: info.fileSize,
                     tables: info.tables,
+                    activeTable: info.activeTable,
                 });
 
                 const page = await getDataPage(0, 10000, info.columns);
@@ -123,9 +123,12 @@ export function DataPanel() {
                 return {
                     worksheets: newWorksheets,
                     currentVisualization: null,
+                    fileName: info.fileName,
+                    filePath: info.filePath,
                     columns: info.columns,
                     rowCount: info.rowCount,
                     fileSize: info.fileSize,
+                    tables: info.tables,
                     activeTable: tableName,
                 };
             });
@@ -1,5 +1,7 @@
+import { useEffect, useState } from "react";
 import { Hash, Type, Calendar, ToggleLeft, FileSpreadsheet } from "lucide-react";
 import { useAppStore } from "../../stores/appStore";
+import { profileTable, type ColumnProfile } from "../../services/fileService";
 import type { Column } from "../../types";
 
 const typeIcons: Record<Column["dtype"], React.ReactNode> = {
@@ -22,11 +24,39 @@ function formatNumber(num: number): string {
     return new Intl.NumberFormat().format(num);
 }
 
-function FieldItem({ column }: { column: Column }) {
+function describeProfile(profile: ColumnProfile): string {
+    const lines = [
+        `${formatNumber(profile.nullCount)} nulls`,
+        `~${formatNumber(profile.distinctCount)} distinct`,
+    ];
+    if (profile.dateRange) {
+        lines.push(`${profile.dateRange.start} to ${profile.dateRange.end}`);
+    } else if (profile.min !== null && profile.max !== null) {
+        lines.push(`${String(profile.min)} to ${String(profile.max)}`);
+    }
+    if (profile.mean !== null) {
+        lines.push(`mean ${formatNumber(profile.mean)}`);
+    }
+    if (profile.topValues.length > 0) {
+        const top = profile.topValues.slice(0, 3).map((v) => `${String(v.value)} (${v.count})`);
+        lines.push(`top: ${top.join(", ")}`);
+    }
+    return lines.join("\n");
+}
+
+function FieldItem({ column, profile }: { column: Column; profile?: ColumnProfile }) {
     return (
-        <div className="flex items-center gap-3 px-4 py-2 text-neutral-700 hover:bg-blue-50 hover:text-neutral-900 cursor-default transition-colors">
+        <div
+            className="flex items-center gap-3 px-4 py-2 text-neutral-700 hover:bg-blue-50 hover:text-neutral-900 cursor-default transition-colors"
+            title={profile ? describeProfile(profile) : undefined}
+        >
             <span className="shrink-0">{typeIcons[column.dtype]}</span>
             <span className="truncate text-xs">{column.name}</span>
+            {profile && profile.nullCount > 0 && (
+                <span className="ml-auto shrink-0 text-[10px] text-neutral-400">
+                    {formatNumber(profile.nullCount)} null
+                </span>
+            )}
         </div>
     );
 }
@@ -71,7 +101,24 @@ function DatasetInfo() {
 }
 
 export function FieldsPanel() {
-    const { columns, dataLoaded } = useAppStore();
+    const { columns, dataLoaded, activeTable, dataVersion } = useAppStore();
+    const [profiles, setProfiles] = useState<Record<string, ColumnProfile>>({});
+
+    useEffect(() => {
+        setProfiles({});
+        if (!dataLoaded || !activeTable) return;
+
+        let cancelled = false;
+        profileTable(activeTable)
+            .then((profile) => {
+                if (cancelled) return;
+                setProfiles(Object.fromEntries(profile.columns.map((c) => [c.name, c])));
+            })
+            .catch((err) => console.error("Failed to profile table:", err));
+        return () => {
+            cancelled = true;
+        };
+    }, [dataLoaded, activeTable, dataVersion]);
 
     return (
         <div className="flex flex-col h-full">
@@ -89,7 +136,7 @@ export function FieldsPanel() {
                 {dataLoaded ? (
                     <div className="py-1">
                         {columns.map((col) => (
-                            <FieldItem key={col.name} column={col} />
+                            <FieldItem key={col.name} column={col} profile={profiles[col.name]} />
                         ))}
                     </div>
                 ) : (
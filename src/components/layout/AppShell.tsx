@@ -5,7 +5,7 @@ import { Canvas } from "./Canvas";
 import { StatusBar } from "./StatusBar";
 import { ResizablePanel } from "./ResizablePanel";
 import { AIChatSidebar } from "../ai";
-import { useMenuEvents } from "../../hooks";
+import { useMenuEvents, useSourceReload } from "../../hooks";
 import { WorksheetBar } from "../worksheets/WorksheetBar";
 import { ErrorBoundary } from "../common";
 import { useAppStore } from "../../stores/appStore";
@@ -33,6 +33,7 @@ function CollapsedAIPanel() {
 
 export function AppShell() {
     useMenuEvents();
+    useSourceReload();
     const { sidebarCollapsed, aiPanelCollapsed } = useAppStore();
 
     return (
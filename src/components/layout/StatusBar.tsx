@@ -1,15 +1,83 @@
+import { useEffect, useState } from "react";
+import toast from "react-hot-toast";
 import { useAppStore } from "../../stores/appStore";
-import { Loader2 } from "lucide-react";
+import {
+    cancelLoad,
+    listSources,
+    unwatchSource,
+    watchSource,
+} from "../../services/fileService";
+import { cn } from "../../utils";
+import { Eye, EyeOff, Loader2 } from "lucide-react";
 
 export function StatusBar() {
-    const { dataLoaded, fileName, rowCount, columns, isProcessing } = useAppStore();
+    const {
+        dataLoaded,
+        fileName,
+        rowCount,
+        columns,
+        isProcessing,
+        processingMessage,
+        loadingPath,
+        tables,
+        activeTable,
+        watchedSources,
+        setSourceWatched,
+    } = useAppStore();
+
+    // The source the active table was read from, which the watch toggle acts on.
+    const [source, setSource] = useState<string | null>(null);
+    useEffect(() => {
+        if (!dataLoaded || !activeTable) {
+            setSource(null);
+            return;
+        }
+        let stale = false;
+        listSources()
+            .then((sources) => {
+                if (!stale) {
+                    setSource(sources.find((s) => s.tables.includes(activeTable))?.name ?? null);
+                }
+            })
+            .catch(() => {
+                if (!stale) setSource(null);
+            });
+        return () => {
+            stale = true;
+        };
+    }, [dataLoaded, activeTable, tables]);
+
+    const watched = source !== null && watchedSources.includes(source);
+
+    const toggleWatch = async () => {
+        if (!source) return;
+        try {
+            if (watched) {
+                await unwatchSource(source);
+            } else {
+                await watchSource(source);
+            }
+            setSourceWatched(source, !watched);
+        } catch (err) {
+            toast.error(err instanceof Error ? err.message : String(err));
+        }
+    };
 
     return (
         <footer className="h-8 bg-sidebar border-t border-border flex items-center px-4 text-xs text-text-muted shrink-0">
             {isProcessing ? (
                 <span className="flex items-center gap-2">
                     <Loader2 size={14} className="animate-spin" />
-                    Processing
+                    {processingMessage ?? "Processing"}
+                    {loadingPath && (
+                        <button
+                            type="button"
+                            onClick={() => cancelLoad(loadingPath)}
+                            className="text-primary hover:underline"
+                        >
+                            Cancel
+                        </button>
+                    )}
                 </span>
             ) : (
                 <span>Ready</span>
@@ -20,6 +88,20 @@ export function StatusBar() {
             {dataLoaded && (
                 <div className="flex items-center gap-4">
                     <span>{fileName}</span>
+                    {source && (
+                        <button
+                            type="button"
+                            onClick={toggleWatch}
+                            className={cn(
+                                "flex items-center gap-1 hover:text-text",
+                                watched && "text-primary"
+                            )}
+                            title={watched ? "Stop reloading when the file changes" : "Reload when the file changes on disk"}
+                        >
+                            {watched ? <Eye size={12} /> : <EyeOff size={12} />}
+                            {watched ? "Watching" : "Watch"}
+                        </button>
+                    )}
                     <span>{rowCount.toLocaleString()} rows</span>
                     <span>{columns?.length} cols</span>
                 </div>
@@ -39,7 +39,8 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
             settings.groq_model !== originalSettings.groq_model ||
             settings.auto_save !== originalSettings.auto_save ||
             settings.theme !== originalSettings.theme ||
-            settings.max_preview_rows !== originalSettings.max_preview_rows
+            settings.max_preview_rows !== originalSettings.max_preview_rows ||
+            settings.scan_files !== originalSettings.scan_files
         );
     }, [settings, originalSettings]);
 
@@ -204,6 +205,32 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
                                         />
                                     </button>
                                 </div>
+
+                                <div className="flex items-center justify-between py-2">
+                                    <div>
+                                        <div className="text-sm font-medium text-neutral-700">
+                                            Query Large Files from Disk
+                                        </div>
+                                        <p className="text-xs text-neutral-500">
+                                            Scan CSV, Parquet and Arrow files instead of loading them
+                                            into memory, for files larger than memory.
+                                        </p>
+                                    </div>
+                                    <button
+                                        onClick={() => updateField("scan_files", !settings.scan_files)}
+                                        className={cn(
+                                            "w-10 h-5 rounded-full transition-colors",
+                                            settings.scan_files ? "bg-primary" : "bg-neutral-300"
+                                        )}
+                                    >
+                                        <div
+                                            className={cn(
+                                                "w-4 h-4 bg-white rounded-full shadow transition-transform",
+                                                settings.scan_files ? "translate-x-5" : "translate-x-0.5"
+                                            )}
+                                        />
+                                    </button>
+                                </div>
                             </Tabs.Content>
 
                             <Tabs.Content value="ai" className="p-5 space-y-5 overflow-auto">
@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback } from "react";
 import { RefreshCw, Maximize2, Minimize2, Download, Loader2, AlertCircle, Info, AlertTriangle } from "lucide-react";
+import { useAppStore } from "../../stores/appStore";
 import { useChartConfigStore } from "../../stores/chartConfigStore";
 import { executeVisualizationQuery, executeScatterQuery } from "../../services/aiService";
 import { menuService } from "../../services/menuService";
@@ -53,6 +54,7 @@ export function ChartContainer({ spec }: ChartContainerProps) {
     const [showReductionDetails, setShowReductionDetails] = useState(false);
 
     const chartConfig = useChartConfigStore((state) => state.config);
+    const dataVersion = useAppStore((state) => state.dataVersion);
 
     useEffect(() => {
         setChartData(null);
@@ -88,7 +90,8 @@ export function ChartContainer({ spec }: ChartContainerProps) {
         } finally {
             setIsLoading(false);
         }
-    }, [spec]);
+        // dataVersion changes when the source is reloaded from disk
+    }, [spec, dataVersion]);
 
     useEffect(() => {
         fetchData();
@@ -2,4 +2,5 @@ export { useDataset } from "./useDataset";
 export { useErrorHandler } from "./useErrorHandler";
 export { useMenuEvents } from "./useMenuEvents";
 export { useQuery } from "./useQuery";
+export { useSourceReload } from "./useSourceReload";
 export { useTableData } from "./useTableData";
\ No newline at end of file
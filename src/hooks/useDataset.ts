@@ -1,8 +1,17 @@
 import { useCallback, useState } from "react";
+import toast from "react-hot-toast";
 import { useAppStore } from "../stores/appStore";
 import { useDataStore } from "../stores/dataStore";
 import { useUiStore } from "../stores/uiStore";
-import { openFileDialog, getDataPage, clearData, loadFile } from "../services/fileService";
+import {
+    openFileDialog,
+    getDataPage,
+    clearData,
+    loadFileWithProgress,
+    cancelLoad,
+    describeLoadProgress,
+    describeSkippedTables,
+} from "../services/fileService";
 
 export function useDataset() {
     const [isLoading, setIsLoading] = useState(false);
@@ -18,6 +27,8 @@ export function useDataset() {
         setDataset,
         clearDataset,
         setProcessing,
+        loadingPath,
+        setLoadingPath,
         setError: setAppError,
     } = useAppStore();
 
@@ -36,8 +47,10 @@ export function useDataset() {
             }
 
             setProcessing(true, "Loading file");
-
-            const info = await loadFile(path);
+            setLoadingPath(path);
+            const info = await loadFileWithProgress(path, (progress) =>
+                setProcessing(true, describeLoadProgress(progress))
+            ).finally(() => setLoadingPath(null));
 
             setDataset({
                 fileName: info.fileName,
@@ -46,22 +59,38 @@ export function useDataset() {
                 rowCount: info.rowCount,
                 fileSize: info.fileSize,
                 tables: info.tables,
+                activeTable: info.activeTable,
             });
 
             addRecentFile(info.filePath);
 
+            const skipped = describeSkippedTables(info);
+            if (skipped) {
+                toast.error(skipped);
+            }
+
             const page = await getDataPage(0, 10000, info.columns);
             setRowData(page.rows, page.totalRows);
 
             setProcessing(false);
         } catch (err) {
-            const message = err instanceof Error ? err.message : "Failed to load file";
-            setError(message);
-            setAppError(message);
+            const message = err instanceof Error ? err.message : String(err);
+            setProcessing(false);
+            if (!message.includes("cancelled")) {
+                setError(message);
+                setAppError(message);
+            }
         } finally {
             setIsLoading(false);
         }
-    }, [setDataset, setProcessing, setAppError, addRecentFile, setRowData]);
+    }, [setDataset, setProcessing, setLoadingPath, setAppError, addRecentFile, setRowData]);
+
+    /** Stops the file that is loading; `openFile` then returns without an error. */
+    const cancelOpen = useCallback(async () => {
+        if (loadingPath) {
+            await cancelLoad(loadingPath);
+        }
+    }, [loadingPath]);
 
     const closeFile = useCallback(async () => {
         try {
@@ -83,6 +112,7 @@ export function useDataset() {
         isLoading,
         error,
         openFile,
+        cancelOpen,
         closeFile,
     };
 }
\ No newline at end of file
@@ -11,8 +11,15 @@ import {
     openProject,
     newProject,
     addToRecent,
+    restoreProjectSources,
+    describeSkippedRestore,
 } from "../services/projectService";
-import { loadFile, getDataPage } from "../services/fileService";
+import {
+    describeLoadProgress,
+    describeSkippedTables,
+    getDataPage,
+    loadFileWithProgress,
+} from "../services/fileService";
 
 /**
  * Hook to handle menu events from the native Tauri menu.
@@ -34,6 +41,7 @@ export function useMenuEvents() {
         setQueryHistory,
         clearDataset,
         setProcessing,
+        setLoadingPath,
         setError,
         setShowWelcome,
         setSettingsOpen,
@@ -66,8 +74,13 @@ export function useMenuEvents() {
             setProcessing(true, "Opening project...");
             const { path, project } = await openProject();
             
-            if (project.data.sourcePath) {
-                const info = await loadFile(project.data.sourcePath);
+            const restored = await restoreProjectSources(project.data);
+            const skipped = describeSkippedRestore(restored);
+            if (skipped) {
+                toast.error(skipped);
+            }
+            const info = restored.info;
+            if (info) {
                 setDataset({
                     fileName: info.fileName,
                     filePath: info.filePath,
@@ -75,6 +88,7 @@ export function useMenuEvents() {
                     rowCount: info.rowCount,
                     fileSize: info.fileSize,
                     tables: info.tables,
+                    activeTable: info.activeTable,
                 });
 
                 const page = await getDataPage(0, 10000, info.columns);
@@ -164,7 +178,7 @@ export function useMenuEvents() {
                 filters: [
                     {
                         name: "Data Files",
-                        extensions: ["csv", "xlsx", "xls", "json"],
+                        extensions: ["csv", "tsv", "txt", "xlsx", "xlsm", "xls", "xlsb", "ods", "json", "parquet", "arrow", "feather", "sqlite", "sqlite3", "db"],
                     },
                 ],
             });
@@ -174,7 +188,14 @@ export function useMenuEvents() {
                 
                 resetVizBuilder();
                 
-                const info = await loadFile(selected);
+                setLoadingPath(selected);
+                const info = await loadFileWithProgress(selected, (progress) =>
+                    setProcessing(true, describeLoadProgress(progress))
+                ).finally(() => setLoadingPath(null));
+                const skipped = describeSkippedTables(info);
+                if (skipped) {
+                    toast.error(skipped);
+                }
 
                 setDataset({
                     fileName: info.fileName,
@@ -183,17 +204,21 @@ export function useMenuEvents() {
                     rowCount: info.rowCount,
                     fileSize: info.fileSize,
                     tables: info.tables,
+                    activeTable: info.activeTable,
                 });
 
                 const page = await getDataPage(0, 10000, info.columns);
                 setRowData(page.rows, page.totalRows);
             }
         } catch (err) {
-            setError(err instanceof Error ? err.message : String(err));
+            const message = err instanceof Error ? err.message : String(err);
+            if (!message.includes("cancelled")) {
+                setError(message);
+            }
         } finally {
             setProcessing(false);
         }
-    }, [setDataset, setProcessing, setError, setRowData, resetVizBuilder]);
+    }, [setDataset, setProcessing, setLoadingPath, setError, setRowData, resetVizBuilder]);
 
     const handleCloseProject = useCallback(() => {
         clearDataset();
@@ -0,0 +1,65 @@
+import { useEffect } from "react";
+import toast from "react-hot-toast";
+import { useAppStore } from "../stores/appStore";
+import { useDataStore } from "../stores/dataStore";
+import {
+    getDataPage,
+    onSourceReloaded,
+    setActiveTable,
+    type SourceReload,
+} from "../services/fileService";
+
+function describeChanges(reload: SourceReload): string {
+    const parts = reload.schemaChanges.flatMap((diff) => [
+        ...diff.added.map((c) => `+${c.name}`),
+        ...diff.removed.map((name) => `-${name}`),
+        ...diff.retyped.map((c) => `${c.name} → ${c.dtype}`),
+    ]);
+    return parts.length > 0
+        ? `${reload.source} reloaded with column changes: ${parts.join(", ")}`
+        : `${reload.source} reloaded`;
+}
+
+/**
+ * Hook to apply reloads of watched sources.
+ *
+ * Listens for `source-reloaded` events, refreshes the dataset summary and
+ * table rows, and bumps the data version so open charts query again.
+ */
+export function useSourceReload() {
+    useEffect(() => {
+        let unlisten: (() => void) | null = null;
+        let cancelled = false;
+
+        onSourceReloaded(async (reload) => {
+            if (reload.error) {
+                toast.error(`Could not reload ${reload.source}: ${reload.error}`);
+                return;
+            }
+
+            const { activeTable, refreshDataset } = useAppStore.getState();
+            if (!activeTable) return;
+
+            try {
+                const info = await setActiveTable(activeTable);
+                refreshDataset(info);
+                const page = await getDataPage(0, 10000, info.columns);
+                useDataStore.getState().setRowData(page.rows, page.totalRows);
+                toast.success(describeChanges(reload));
+            } catch (err) {
+                toast.error(err instanceof Error ? err.message : String(err));
+            }
+        }).then((fn) => {
+            if (cancelled) {
+                fn();
+            } else {
+                unlisten = fn;
+            }
+        });
+
+        return () => {
+            cancelled = true;
+            unlisten?.();
+        };
+    }, []);
+}
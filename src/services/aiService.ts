@@ -59,6 +59,7 @@ interface BackendSettings {
     auto_save: boolean;
     theme: string;
     max_preview_rows: number;
+    scan_files: boolean;
 }
 
 export interface AppSettings {
@@ -67,6 +68,7 @@ export interface AppSettings {
     autoSave: boolean;
     theme: string;
     maxPreviewRows: number;
+    scanFiles: boolean;
 }
 
 function transformVisualizationSpec(spec: BackendVisualizationSpec): VisualizationSpec {
@@ -163,6 +165,7 @@ function transformSettingsFromBackend(settings: BackendSettings): AppSettings {
         autoSave: settings.auto_save,
         theme: settings.theme,
         maxPreviewRows: settings.max_preview_rows,
+        scanFiles: settings.scan_files,
     };
 }
 
@@ -173,6 +176,7 @@ function transformSettingsToBackend(settings: AppSettings): BackendSettings {
         auto_save: settings.autoSave,
         theme: settings.theme,
         max_preview_rows: settings.maxPreviewRows,
+        scan_files: settings.scanFiles,
     };
 }
 
@@ -1,8 +1,11 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-dialog";
-import type { Column } from "../types";
+import type { Column, FilterSpec, LogicalType, SemanticType } from "../types";
+import { getSettings } from "./settingsService";
 
 interface BackendDatasetInfo {
+  source: string;
   file_name: string;
   file_path: string;
   file_size: number;
@@ -11,8 +14,18 @@ interface BackendDatasetInfo {
     name: string;
     dtype: string;
     nullable: boolean;
+    precision?: number;
+    scale?: number;
+    time_unit?: string;
+    timezone?: string;
+    semantic_type?: SemanticType;
+    formula?: string;
   }>;
   tables?: string[];
+  active_table: string;
+  report?: {
+    skipped_tables?: SkippedTable[];
+  } | null;
 }
 
 interface BackendDataPage {
@@ -23,12 +36,104 @@ interface BackendDataPage {
 }
 
 export interface DatasetInfo {
+  /** Workspace source the active table belongs to. */
+  source: string;
   fileName: string;
   filePath: string;
   fileSize: number;
   rowCount: number;
   columns: Column[];
+  /** Every table in the workspace. */
   tables: string[];
+  activeTable: string;
+  /** Sheets or archive members that could not be read and were left out. */
+  skippedTables: SkippedTable[];
+}
+
+export interface SkippedTable {
+  name: string;
+  reason: string;
+}
+
+export type SourceFormat =
+  | "csv"
+  | "fixed-width"
+  | "excel"
+  | "json"
+  | "parquet"
+  | "arrow"
+  | "sqlite"
+  | "archive"
+  | "folder";
+
+export interface SourceInfo {
+  name: string;
+  path: string;
+  format: SourceFormat;
+  tables: string[];
+}
+
+/**
+ * The loader and options a source was read with, as saved in projects. It is
+ * passed back to `restoreSource` as it is.
+ */
+export type LoadOptions = { type: string } & Record<string, unknown>;
+
+export type ColumnTypeOverride =
+  | { type: "integer" }
+  | { type: "float" }
+  | { type: "date"; format?: string }
+  | { type: "boolean" }
+  | { type: "categorical" }
+  | { type: "string" };
+
+export type SchemaOverrides = Record<string, ColumnTypeOverride>;
+
+export interface ExcelImportOptions {
+  sheet?: string;
+  /** A1-style cell range such as "B4:K900". */
+  range?: string;
+  /** Excel Table or defined name. */
+  table?: string;
+  /** 1-based sheet row holding the column names; detected when omitted. */
+  header_row?: number;
+}
+
+export interface JsonImportOptions {
+  /** JSON pointer to the record array, e.g. "/data/items". */
+  record_path?: string;
+  /** Unnest objects into dotted column names. */
+  flatten?: boolean;
+  /** Array columns to expand into one row per element. */
+  explode?: string[];
+}
+
+/** A fixed-width column by character position; `end` is exclusive. */
+export interface FixedWidthColumn {
+  name?: string | null;
+  start: number;
+  end: number;
+}
+
+export interface FixedWidthOptions {
+  /** Detected from aligned whitespace when empty. */
+  columns?: FixedWidthColumn[];
+  /** Detected when omitted. */
+  has_header?: boolean | null;
+  skip_rows?: number;
+  encoding?: "utf8" | "utf16-le" | "utf16-be" | "windows1252";
+}
+
+export interface FixedWidthPreview {
+  /** Options with the detected columns and header filled in. */
+  options: FixedWidthOptions;
+  columns: Column[];
+  rows: unknown[][];
+}
+
+export interface SqliteObject {
+  name: string;
+  kind: "table" | "view";
 }
 
 export interface DataPage {
@@ -43,10 +148,12 @@ function mapDtype(dtype: string): Column["dtype"] {
     case "integer":
       return "integer";
     case "float":
+    case "decimal":
       return "float";
     case "boolean":
       return "boolean";
     case "date":
+    case "datetime":
       return "date";
     default:
       return "string";
@@ -55,19 +162,29 @@ function mapDtype(dtype: string): Column["dtype"] {
 
 function transformDatasetInfo(info: BackendDatasetInfo): DatasetInfo {
   return {
+    source: info.source,
     fileName: info.file_name,
     filePath: info.file_path,
     fileSize: info.file_size,
     rowCount: info.row_count,
-    columns: info.columns.map((col) => ({
-      name: col.name,
-      dtype: mapDtype(col.dtype),
-      nullable: col.nullable,
-    })),
+    columns: transformColumns(info.columns),
     tables: info.tables || [],
+    activeTable: info.active_table,
+    skippedTables: info.report?.skipped_tables || [],
   };
 }
 
+/** A warning naming the tables a load left out, or null when none were. */
+export function describeSkippedTables(info: DatasetInfo): string | null {
+  if (info.skippedTables.length === 0) {
+    return null;
+  }
+  const details = info.skippedTables
+    .map((table) => `${table.name}: ${table.reason}`)
+    .join("\n");
+  return `Some tables could not be read and were left out:\n${details}`;
+}
+
 function transformDataPage(
   page: BackendDataPage,
   columns: Column[]
@@ -98,19 +215,39 @@ export async function openFileDialog(): Promise<string | null> {
     filters: [
       {
         name: "Data Files",
-        extensions: ["csv", "xlsx", "xls", "json"],
+        extensions: ["csv", "tsv", "txt", "fwf", "prn", "xlsx", "xlsm", "xls", "xlsb", "ods", "json", "ndjson", "jsonl", "parquet", "arrow", "feather", "sqlite", "sqlite3", "db", "gz", "zst", "bz2", "zip", "tar", "tgz"],
       },
       {
         name: "CSV",
-        extensions: ["csv"],
+        extensions: ["csv", "tsv", "txt"],
+      },
+      {
+        name: "Fixed-width text",
+        extensions: ["fwf", "prn"],
       },
       {
-        name: "Excel",
-        extensions: ["xlsx", "xls"],
+        name: "Spreadsheets",
+        extensions: ["xlsx", "xlsm", "xls", "xlsb", "ods"],
       },
       {
         name: "JSON",
-        extensions: ["json"],
+        extensions: ["json", "ndjson", "jsonl"],
+      },
+      {
+        name: "Parquet",
+        extensions: ["parquet"],
+      },
+      {
+        name: "Arrow IPC",
+        extensions: ["arrow", "feather"],
+      },
+      {
+        name: "SQLite",
+        extensions: ["sqlite", "sqlite3", "db"],
+      },
+      {
+        name: "Compressed & Archives",
+        extensions: ["gz", "zst", "bz2", "zip", "tar", "tgz"],
       },
     ],
   });
@@ -122,22 +259,105 @@ export async function openFileDialog(): Promise<string | null> {
   return null;
 }
 
-export async function loadFile(path: string): Promise<DatasetInfo> {
-  const ext = path.split(".").pop()?.toLowerCase();
+const COMPRESSION_EXTENSIONS = ["gz", "zst", "bz2"];
+
+function isArchive(path: string): boolean {
+  return /\.(zip|tar|tar\.gz|tgz)$/i.test(path);
+}
+
+function isCompressed(path: string): boolean {
+  const ext = path.toLowerCase().split(".").pop();
+  return ext !== undefined && COMPRESSION_EXTENSIONS.includes(ext);
+}
+
+/** Extension of the data in a file, looking through compression (`a.csv.gz` is `csv`). */
+function dataExtension(path: string): string | undefined {
+  const parts = path.toLowerCase().split(".");
+  const ext = parts.pop();
+  if (ext && COMPRESSION_EXTENSIONS.includes(ext)) {
+    return parts.pop();
+  }
+  return ext;
+}
+
+/** Picks a folder whose files `loadFolder` unions into one table. */
+export async function openFolderDialog(): Promise<string | null> {
+  const selected = await open({ directory: true, multiple: false });
+  return typeof selected === "string" ? selected : null;
+}
+
+/**
+ * Loads a file with the loader for its extension. `scan` queries plain CSV,
+ * Parquet and Arrow files from disk instead of reading them into memory; it
+ * defaults to the "Query Large Files from Disk" setting.
+ */
+export async function loadFile(
+  path: string,
+  schemaOverrides?: SchemaOverrides,
+  options: { scan?: boolean } = {}
+): Promise<DatasetInfo> {
+  if (isArchive(path)) {
+    return loadArchive(path);
+  }
+
+  const ext = dataExtension(path);
+  const scan = options.scan ?? (await getSettings()).scan_files;
 
   let info: BackendDatasetInfo;
 
   switch (ext) {
     case "csv":
-      info = await invoke<BackendDatasetInfo>("load_csv", { path });
+    case "tsv":
+    case "txt":
+      // The delimiter is sniffed, so tab-separated and plain text files load as CSV.
+      info = await invoke<BackendDatasetInfo>("load_csv", {
+        path,
+        // Compressed files cannot be scanned.
+        scan: scan && !isCompressed(path),
+        schemaOverrides: schemaOverrides ?? null,
+      });
+      break;
+    case "fwf":
+    case "prn":
+      info = await invoke<BackendDatasetInfo>("load_fixed_width", {
+        path,
+        options: null,
+        schemaOverrides: schemaOverrides ?? null,
+      });
       break;
     case "xlsx":
+    case "xlsm":
     case "xls":
+    case "xlsb":
+    case "ods":
       // Load all sheets by default
-      info = await invoke<BackendDatasetInfo>("load_excel", { path, sheet: null });
+      info = await invoke<BackendDatasetInfo>("load_excel", {
+        path,
+        options: null,
+        schemaOverrides: schemaOverrides ?? null,
+      });
       break;
     case "json":
-      info = await invoke<BackendDatasetInfo>("load_json", { path });
+    case "ndjson":
+    case "jsonl":
+      info = await invoke<BackendDatasetInfo>("load_json", {
+        path,
+        options: { flatten: true },
+        schemaOverrides: schemaOverrides ?? null,
+      });
+      break;
+    case "parquet":
+      info = await invoke<BackendDatasetInfo>("load_parquet", { path, scan });
+      break;
+    case "arrow":
+    case "feather":
+      info = await invoke<BackendDatasetInfo>("load_arrow", { path, scan });
+      break;
+    case "sqlite":
+    case "sqlite3":
+    case "db":
+      // Load all tables by default
+      info = await invoke<BackendDatasetInfo>("load_sqlite", { path });
       break;
     default:
       throw new Error(`Unsupported file format: ${ext}`);
@@ -150,31 +370,634 @@ export async function listExcelSheets(path: string): Promise<string[]> {
   return invoke<string[]>("list_excel_sheets", { path });
 }
 
+export async function listExcelTables(path: string): Promise<string[]> {
+  return invoke<string[]>("list_excel_tables", { path });
+}
+
 export async function loadExcelSheet(
   path: string,
   sheet: string
 ): Promise<DatasetInfo> {
-  // This might be used to force reload a specific sheet, but our new backend loads all.
-  // We can just use setActiveTable if it's already loaded, or re-load.
-  // For now, let's just re-use load_excel, it ignores 'sheet' param if we removed it, 
-  // OR we should keep the 'sheet' param in backend but make it optional? 
-  // I updated backend to accept `sheet: Option<String>` but then I rewrote the logic to IGNORE it and load all sheets if `sheets` found.
-  // Wait, I rewrote `load_excel` to `if let Some((first_sheet_name...))`. I didn't check the `sheet` param to set active table.
-  // That's fine, we can set active table after loading.
-  
+  return loadExcel(path, { sheet });
+}
+
+export async function loadExcel(
+  path: string,
+  options: ExcelImportOptions,
+  schemaOverrides?: SchemaOverrides
+): Promise<DatasetInfo> {
   const info = await invoke<BackendDatasetInfo>("load_excel", {
     path,
-    sheet: null,
+    options,
+    schemaOverrides: schemaOverrides ?? null,
+  });
+  return transformDatasetInfo(info);
+}
+
+export async function loadJson(
+  path: string,
+  options: JsonImportOptions,
+  schemaOverrides?: SchemaOverrides
+): Promise<DatasetInfo> {
+  const info = await invoke<BackendDatasetInfo>("load_json", {
+    path,
+    options,
+    schemaOverrides: schemaOverrides ?? null,
   });
-  
-  // If a specific sheet was requested, we should set it as active.
-  if (sheet && info.tables?.includes(sheet)) {
-      return await setActiveTable(sheet);
+  return transformDatasetInfo(info);
+}
+
+/** Detects the column layout of a fixed-width file and parses its first rows. */
+export async function previewFixedWidth(
+  path: string,
+  options?: FixedWidthOptions
+): Promise<FixedWidthPreview> {
+  const preview = await invoke<{
+    options: FixedWidthOptions;
+    columns: BackendDatasetInfo["columns"];
+    rows: unknown[][];
+  }>("preview_fixed_width", { path, options: options ?? null });
+  return { ...preview, columns: transformColumns(preview.columns) };
+}
+
+/**
+ * Loads a fixed-width text file. Column positions are detected from aligned
+ * whitespace unless `options` gives them.
+ */
+export async function loadFixedWidth(
+  path: string,
+  options?: FixedWidthOptions,
+  schemaOverrides?: SchemaOverrides
+): Promise<DatasetInfo> {
+  const info = await invoke<BackendDatasetInfo>("load_fixed_width", {
+    path,
+    options: options ?? null,
+    schemaOverrides: schemaOverrides ?? null,
+  });
+  return transformDatasetInfo(info);
+}
+
+export async function listSqliteTables(path: string): Promise<SqliteObject[]> {
+  return invoke<SqliteObject[]>("list_sqlite_tables", { path });
+}
+
+/** Loads the named tables or views, or the result of a custom SELECT. */
+export async function loadSqlite(
+  path: string,
+  selection: { tables?: string[]; query?: string }
+): Promise<DatasetInfo> {
+  const info = await invoke<BackendDatasetInfo>("load_sqlite", {
+    path,
+    tables: selection.tables ?? null,
+    query: selection.query ?? null,
+  });
+  return transformDatasetInfo(info);
+}
+
+/** Loads every CSV, JSON and spreadsheet in a .zip or .tar(.gz) archive. */
+export async function loadArchive(path: string): Promise<DatasetInfo> {
+  const info = await invoke<BackendDatasetInfo>("load_archive", { path });
+  return transformDatasetInfo(info);
+}
+
+/**
+ * Unions the files in a folder, or matching a glob such as `exports/2024-*.csv`,
+ * into one table with a `__source_file` column.
+ */
+export async function loadFolder(pathOrPattern: string): Promise<DatasetInfo> {
+  const info = await invoke<BackendDatasetInfo>("load_folder", { path: pathOrPattern });
+  return transformDatasetInfo(info);
+}
+
+/** Loads a project source with the loader and options it was saved with. */
+export async function restoreSource(
+  path: string,
+  load: LoadOptions,
+  schemaOverrides?: SchemaOverrides
+): Promise<DatasetInfo> {
+  const info = await invoke<BackendDatasetInfo>("restore_source", {
+    path,
+    load,
+    schemaOverrides: schemaOverrides ?? null,
+  });
+  return transformDatasetInfo(info);
+}
+
+/**
+ * Reads the rows appended to a CSV or NDJSON source since it was loaded,
+ * without reading the whole file again. Makes the source's table active.
+ */
+export async function appendSource(name: string): Promise<DatasetInfo> {
+  const info = await invoke<BackendDatasetInfo>("append_source", { name });
+  return transformDatasetInfo(info);
+}
+
+/** How a table's columns changed when its source was reloaded. */
+export interface SchemaDiff {
+  table: string;
+  added: Column[];
+  removed: string[];
+  /** Columns whose type changed, with their new type. */
+  retyped: Column[];
+}
+
+/** Sent when a watched source has been read again after its file changed. */
+export interface SourceReload {
+  source: string;
+  path: string;
+  schemaChanges: SchemaDiff[];
+  /** Set when the file could not be read; the previous data is kept. */
+  error: string | null;
+}
+
+interface BackendSourceReload {
+  source: string;
+  path: string;
+  schema_changes: Array<{
+    table: string;
+    added: BackendDatasetInfo["columns"];
+    removed: string[];
+    retyped: BackendDatasetInfo["columns"];
+  }>;
+  error: string | null;
+}
+
+function transformColumns(columns: BackendDatasetInfo["columns"]): Column[] {
+  return columns.map((col) => ({
+    name: col.name,
+    dtype: mapDtype(col.dtype),
+    nullable: col.nullable,
+    logicalType: col.dtype as LogicalType,
+    precision: col.precision,
+    scale: col.scale,
+    timeUnit: col.time_unit,
+    timezone: col.timezone,
+    semanticType: col.semantic_type,
+    formula: col.formula,
+  }));
+}
+
+/** Reloads a source whenever its file changes on disk. */
+export async function watchSource(name: string): Promise<void> {
+  return invoke("watch_source", { name });
+}
+
+export async function unwatchSource(name: string): Promise<void> {
+  return invoke("unwatch_source", { name });
+}
+
+export async function onSourceReloaded(
+  callback: (reload: SourceReload) => void
+): Promise<UnlistenFn> {
+  return listen<BackendSourceReload>("source-reloaded", (event) => {
+    const reload = event.payload;
+    callback({
+      source: reload.source,
+      path: reload.path,
+      schemaChanges: reload.schema_changes.map((diff) => ({
+        table: diff.table,
+        added: transformColumns(diff.added),
+        removed: diff.removed,
+        retyped: transformColumns(diff.retyped),
+      })),
+      error: reload.error,
+    });
+  });
+}
+
+/** Sent while a CSV, JSON or Excel file is loading. */
+export interface LoadProgress {
+  path: string;
+  /** Bytes of the file read so far, counted before decompression. */
+  bytesRead: number;
+  totalBytes: number;
+  /** Rows parsed, once known. */
+  rows: number | null;
+  /** Workbook sheet, database table, archive member or folder file being read. */
+  sheet: string | null;
+}
+
+/** A status line for a load in progress. */
+export function describeLoadProgress(progress: LoadProgress): string {
+  if (progress.sheet) return `Reading ${progress.sheet}`;
+  if (progress.rows !== null) return `Read ${progress.rows.toLocaleString()} rows`;
+  if (progress.totalBytes === 0) return "Loading file";
+  const percent = Math.floor((progress.bytesRead / progress.totalBytes) * 100);
+  return `Loading file (${percent}%)`;
+}
+
+interface BackendLoadProgress {
+  path: string;
+  bytes_read: number;
+  total_bytes: number;
+  rows: number | null;
+  sheet: string | null;
+}
+
+export async function onLoadProgress(
+  callback: (progress: LoadProgress) => void
+): Promise<UnlistenFn> {
+  return listen<BackendLoadProgress>("load-progress", (event) => {
+    const progress = event.payload;
+    callback({
+      path: progress.path,
+      bytesRead: progress.bytes_read,
+      totalBytes: progress.total_bytes,
+      rows: progress.rows,
+      sheet: progress.sheet,
+    });
+  });
+}
+
+/** Loads a file as `loadFile` does, passing its progress to `onProgress`. */
+export async function loadFileWithProgress(
+  path: string,
+  onProgress: (progress: LoadProgress) => void
+): Promise<DatasetInfo> {
+  const unlisten = await onLoadProgress((progress) => {
+    if (progress.path === path) {
+      onProgress(progress);
+    }
+  });
+  try {
+    return await loadFile(path);
+  } finally {
+    unlisten();
   }
+}
+
+/**
+ * Stops a running load of `path`; the load then fails with a cancellation
+ * error. Returns whether a load was running.
+ */
+export async function cancelLoad(path: string): Promise<boolean> {
+  return invoke<boolean>("cancel_load", { path });
+}
+
+/** Statistics of one column, from `profileTable`. */
+export interface ColumnProfile {
+  name: string;
+  dtype: Column["dtype"];
+  nullCount: number;
+  /** Approximate number of distinct values, counting null as one. */
+  distinctCount: number;
+  min: unknown;
+  max: unknown;
+  mean: number | null;
+  std: number | null;
+  quantiles: Array<{ quantile: number; value: number }>;
+  /** Most frequent non-null values, most frequent first. */
+  topValues: Array<{ value: unknown; count: number }>;
+  histogram: Array<{ start: number; end: number; count: number }>;
+  lengths: { min: number; max: number; mean: number } | null;
+  dateRange: { start: string; end: string } | null;
+}
+
+export interface TableProfile {
+  table: string;
+  rowCount: number;
+  columns: ColumnProfile[];
+}
+
+interface BackendTableProfile {
+  table: string;
+  row_count: number;
+  columns: Array<{
+    name: string;
+    dtype: string;
+    null_count: number;
+    distinct_count: number;
+    min: unknown;
+    max: unknown;
+    mean: number | null;
+    std: number | null;
+    quantiles: Array<{ quantile: number; value: number }>;
+    top_values: Array<{ value: unknown; count: number }>;
+    histogram: Array<{ start: number; end: number; count: number }>;
+    lengths: { min: number; max: number; mean: number } | null;
+    date_range: { start: string; end: string } | null;
+  }>;
+}
 
+/**
+ * Per-column statistics of a table, the active table by default. Results are
+ * cached by the backend until the table's data changes.
+ */
+export async function profileTable(table?: string): Promise<TableProfile> {
+  const profile = await invoke<BackendTableProfile>("profile_table", {
+    table: table ?? null,
+  });
+  return {
+    table: profile.table,
+    rowCount: profile.row_count,
+    columns: profile.columns.map((col) => ({
+      name: col.name,
+      dtype: mapDtype(col.dtype),
+      nullCount: col.null_count,
+      distinctCount: col.distinct_count,
+      min: col.min,
+      max: col.max,
+      mean: col.mean,
+      std: col.std,
+      quantiles: col.quantiles,
+      topValues: col.top_values,
+      histogram: col.histogram,
+      lengths: col.lengths,
+      dateRange: col.date_range,
+    })),
+  };
+}
+
+export type JoinKind = "inner" | "left" | "right" | "outer" | "semi" | "anti";
+
+/** A pair of key columns, one from each table. */
+export interface JoinKey {
+  left: string;
+  right: string;
+}
+
+/** How well the keys of a join matched. */
+export interface JoinStats {
+  leftRows: number;
+  rightRows: number;
+  resultRows: number;
+  /** Left rows whose key has no match on the right. */
+  unmatchedLeft: number;
+  unmatchedRight: number;
+  /** Keys that occur on more than one row of the left table. */
+  duplicateLeftKeys: number;
+  duplicateRightKeys: number;
+  /** Most right rows any matched left key joins to. */
+  maxFanOut: number;
+}
+
+interface BackendJoinedTable {
+  dataset: BackendDatasetInfo;
+  stats: {
+    left_rows: number;
+    right_rows: number;
+    result_rows: number;
+    unmatched_left: number;
+    unmatched_right: number;
+    duplicate_left_keys: number;
+    duplicate_right_keys: number;
+    max_fan_out: number;
+  };
+}
+
+/**
+ * Joins two loaded tables and registers the result as table `name`, which
+ * becomes the active table.
+ */
+export async function joinTables(
+  left: string,
+  right: string,
+  keys: JoinKey[],
+  kind: JoinKind,
+  name: string
+): Promise<{ dataset: DatasetInfo; stats: JoinStats }> {
+  const joined = await invoke<BackendJoinedTable>("join_tables", {
+    left,
+    right,
+    keys,
+    kind,
+    name,
+  });
+  const stats = joined.stats;
+  return {
+    dataset: transformDatasetInfo(joined.dataset),
+    stats: {
+      leftRows: stats.left_rows,
+      rightRows: stats.right_rows,
+      resultRows: stats.result_rows,
+      unmatchedLeft: stats.unmatched_left,
+      unmatchedRight: stats.unmatched_right,
+      duplicateLeftKeys: stats.duplicate_left_keys,
+      duplicateRightKeys: stats.duplicate_right_keys,
+      maxFanOut: stats.max_fan_out,
+    },
+  };
+}
+
+/**
+ * Stacks tables, such as the sheets of a workbook, into a new table `name`,
+ * which becomes the active table. Columns are matched by name, missing ones
+ * are filled with nulls, and differing types are widened. With `labelColumn`,
+ * a leading column of that name records which table each row came from.
+ */
+export async function unionTables(
+  tables: string[],
+  name: string,
+  labelColumn?: string
+): Promise<DatasetInfo> {
+  const info = await invoke<BackendDatasetInfo>("union_tables", {
+    tables,
+    name,
+    labelColumn: labelColumn ?? null,
+  });
+  return transformDatasetInfo(info);
+}
+
+/** A column defined by a formula over its table's other columns. */
+export interface CalculatedField {
+  name: string;
+  formula: string;
+}
+
+/**
+ * Adds a calculated field to a table, the active table by default, or changes
+ * the formula of the field with that name. Formulas use column names, or
+ * `[Column Name]` for names with spaces, and functions such as `if`, `year`
+ * and `len`: `if(region = "EU", price * 1.2, price)`. The table becomes the
+ * active table.
+ */
+export async function addCalculatedField(
+  name: string,
+  formula: string,
+  table?: string
+): Promise<DatasetInfo> {
+  const info = await invoke<BackendDatasetInfo>("add_calculated_field", {
+    table: table ?? null,
+    name,
+    formula,
+  });
   return transformDatasetInfo(info);
 }
 
+/** Removes a calculated field from a table, the active table by default. */
+export async function removeCalculatedField(
+  name: string,
+  table?: string
+): Promise<DatasetInfo> {
+  const info = await invoke<BackendDatasetInfo>("remove_calculated_field", {
+    table: table ?? null,
+    name,
+  });
+  return transformDatasetInfo(info);
+}
+
+export type FillStrategy =
+  | { type: "value"; value: string | number | boolean }
+  | { type: "forward" }
+  | { type: "backward" }
+  | { type: "mean" };
+
+/** A recorded change to a table, replayed on its source data in order. */
+export type TransformStep =
+  | ({ type: "filter" } & FilterSpec)
+  | { type: "rename"; column: string; to: string }
+  | { type: "cast"; column: string; to: ColumnTypeOverride }
+  | { type: "drop"; columns: string[] }
+  | { type: "fill-null"; column: string; strategy: FillStrategy }
+  /** Keeps the first of repeated rows, compared on `columns` or on all columns. */
+  | { type: "dedupe"; columns: string[] }
+  | { type: "derive"; name: string; formula: string }
+  /** Moves `columns` to the front; the others follow in their current order. */
+  | { type: "reorder"; columns: string[] }
+  /** New columns are placed after `column`; the last takes the rest of the text. */
+  | { type: "split"; column: string; by: SplitPattern; into: string[] };
+
+export type SplitPattern =
+  | { type: "delimiter"; delimiter: string }
+  | { type: "regex"; pattern: string };
+
+export interface TransformPipeline {
+  table: string;
+  steps: TransformStep[];
+  canUndo: boolean;
+  canRedo: boolean;
+}
+
+interface BackendTransformPipeline {
+  table: string;
+  steps: TransformStep[];
+  can_undo: boolean;
+  can_redo: boolean;
+}
+
+function transformPipeline(pipeline: BackendTransformPipeline): TransformPipeline {
+  return {
+    table: pipeline.table,
+    steps: pipeline.steps,
+    canUndo: pipeline.can_undo,
+    canRedo: pipeline.can_redo,
+  };
+}
+
+async function changeSteps(
+  command: string,
+  args: Record<string, unknown>
+): Promise<{ dataset: DatasetInfo; pipeline: TransformPipeline }> {
+  const result = await invoke<{
+    dataset: BackendDatasetInfo;
+    pipeline: BackendTransformPipeline;
+  }>(command, args);
+  return {
+    dataset: transformDatasetInfo(result.dataset),
+    pipeline: transformPipeline(result.pipeline),
+  };
+}
+
+/** The recorded steps of a table, the active table by default. */
+export async function getTransformSteps(table?: string): Promise<TransformPipeline> {
+  const pipeline = await invoke<BackendTransformPipeline>("get_transform_steps", {
+    table: table ?? null,
+  });
+  return transformPipeline(pipeline);
+}
+
+/**
+ * Step changes apply to a table, the active table by default, which becomes
+ * the active table. They fail without changing anything when a step cannot
+ * be applied, such as a rename of a column an earlier step dropped.
+ */
+export async function addTransformStep(step: TransformStep, table?: string) {
+  return changeSteps("add_transform_step", { table: table ?? null, step });
+}
+
+export async function removeTransformStep(index: number, table?: string) {
+  return changeSteps("remove_transform_step", { table: table ?? null, index });
+}
+
+export async function moveTransformStep(from: number, to: number, table?: string) {
+  return changeSteps("move_transform_step", { table: table ?? null, from, to });
+}
+
+/** Restores a table's saved steps when a project is reopened, with nothing to undo. */
+export async function setTransformSteps(steps: TransformStep[], table?: string) {
+  return changeSteps("set_transform_steps", { table: table ?? null, steps });
+}
+
+export async function undoTransformStep(table?: string) {
+  return changeSteps("undo_transform_step", { table: table ?? null });
+}
+
+export async function redoTransformStep(table?: string) {
+  return changeSteps("redo_transform_step", { table: table ?? null });
+}
+
+/** Values that were present before a type change but could not be converted. */
+export interface CastIssue {
+  column: string;
+  failedCount: number;
+  /** Share of non-empty values that failed to convert (0.0-1.0). */
+  failedRatio: number;
+  /** The first failures, with 1-based row numbers. */
+  samples: { row: number; value: string }[];
+}
+
+interface BackendColumnIssue {
+  column: string;
+  failed_count: number;
+  failed_ratio: number;
+  samples: { row: number; value: string }[];
+}
+
+export async function renameColumn(column: string, to: string, table?: string) {
+  return changeSteps("rename_column", { table: table ?? null, column, to });
+}
+
+export async function dropColumns(columns: string[], table?: string) {
+  return changeSteps("drop_columns", { table: table ?? null, columns });
+}
+
+/** Converts a column to another type, reporting values that became null. */
+export async function castColumn(
+  column: string,
+  to: ColumnTypeOverride,
+  table?: string
+): Promise<{ dataset: DatasetInfo; pipeline: TransformPipeline; issue: CastIssue | null }> {
+  const result = await invoke<{
+    dataset: BackendDatasetInfo;
+    pipeline: BackendTransformPipeline;
+    issue: BackendColumnIssue | null;
+  }>("cast_column", { table: table ?? null, column, to });
+  return {
+    dataset: transformDatasetInfo(result.dataset),
+    pipeline: transformPipeline(result.pipeline),
+    issue: result.issue && {
+      column: result.issue.column,
+      failedCount: result.issue.failed_count,
+      failedRatio: result.issue.failed_ratio,
+      samples: result.issue.samples,
+    },
+  };
+}
+
+/** Moves `columns` to the front of the table, in the given order. */
+export async function reorderColumns(columns: string[], table?: string) {
+  return changeSteps("reorder_columns", { table: table ?? null, columns });
+}
+
+export async function splitColumn(
+  column: string,
+  by: SplitPattern,
+  into: string[],
+  table?: string
+) {
+  return changeSteps("split_column", { table: table ?? null, column, by, into });
+}
+
 export async function setActiveTable(tableName: string): Promise<DatasetInfo> {
     console.log('[fileService] setActiveTable called with tableName:', tableName);
     console.log('[fileService] Invoking Tauri command: set_active_table');
@@ -185,6 +1008,26 @@ export async function setActiveTable(tableName: string): Promise<DatasetInfo> {
     return transformed;
 }
 
+export async function listSources(): Promise<SourceInfo[]> {
+  return invoke<SourceInfo[]>("list_sources");
+}
+
+/** Loads a file into the workspace alongside the sources already open. */
+export async function addSource(path: string): Promise<DatasetInfo> {
+  const info = await invoke<BackendDatasetInfo>("add_source", { path });
+  return transformDatasetInfo(info);
+}
+
+/** Returns the new active table, or null when no data is left. */
+export async function removeSource(name: string): Promise<DatasetInfo | null> {
+  const info = await invoke<BackendDatasetInfo | null>("remove_source", { name });
+  return info ? transformDatasetInfo(info) : null;
+}
+
+export async function renameSource(name: string, newName: string): Promise<SourceInfo> {
+  return invoke<SourceInfo>("rename_source", { name, newName });
+}
+
 export async function getDataPage(
   offset: number,
   limit: number,
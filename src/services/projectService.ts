@@ -1,5 +1,45 @@
 import { invoke } from "@tauri-apps/api/core";
 import type { VisualizationSpec, QueryHistoryItem, Worksheet } from "../types";
+import {
+    addCalculatedField,
+    clearData,
+    joinTables,
+    loadFile,
+    loadFolder,
+    renameSource,
+    restoreSource,
+    setActiveTable,
+    setTransformSteps,
+    unionTables,
+    type CalculatedField,
+    type DatasetInfo,
+    type JoinKey,
+    type JoinKind,
+    type LoadOptions,
+    type SchemaOverrides,
+    type SourceFormat,
+    type TransformStep,
+} from "./fileService";
+
+export interface ProjectSource {
+    name: string;
+    path: string;
+    format: SourceFormat;
+    schemaOverrides: SchemaOverrides;
+    /** Loader options, missing in projects saved before they were kept. */
+    load: LoadOptions | null;
+}
+
+/** How a join or union result was computed, as `join_tables` or `union_tables` was called. */
+export type Derivation =
+    | { type: "join"; left: string; right: string; keys: JoinKey[]; kind: JoinKind }
+    | { type: "union"; tables: string[]; label_column: string | null };
+
+/** A join or union result, computed again when the project is opened. */
+export interface DerivedTable {
+    name: string;
+    derivation: Derivation;
+}
 
 export interface ProjectData {
     sourceType: "Path" | "Embedded";
@@ -12,6 +52,15 @@ export interface ProjectData {
         }>;
         rowCount: number;
     };
+    schemaOverrides: SchemaOverrides;
+    sources: ProjectSource[];
+    /** Derived tables in the order they were computed. */
+    derivedTables: DerivedTable[];
+    activeTable: string | null;
+    /** Transformation steps by table name. */
+    transformSteps: Record<string, TransformStep[]>;
+    /** Calculated fields by table name. */
+    calculatedFields: Record<string, CalculatedField[]>;
 }
 
 export interface InsyteProject {
@@ -59,6 +108,18 @@ interface BackendOpenProjectResponse {
             }>;
             row_count: number;
         };
+        schema_overrides?: SchemaOverrides;
+        sources?: Array<{
+            name: string;
+            path: string;
+            format: SourceFormat;
+            schema_overrides?: SchemaOverrides;
+            load?: LoadOptions;
+        }>;
+        derived_tables?: DerivedTable[];
+        active_table?: string | null;
+        transform_steps?: Record<string, TransformStep[]>;
+        calculated_fields?: Record<string, CalculatedField[]>;
     };
     worksheets: Array<{
         id: string;
@@ -148,6 +209,18 @@ export async function openProject(
                     columns: backend.data.schema.columns,
                     rowCount: backend.data.schema.row_count,
                 },
+                schemaOverrides: backend.data.schema_overrides ?? {},
+                sources: (backend.data.sources ?? []).map((source) => ({
+                    name: source.name,
+                    path: source.path,
+                    format: source.format,
+                    schemaOverrides: source.schema_overrides ?? {},
+                    load: source.load ?? null,
+                })),
+                derivedTables: backend.data.derived_tables ?? [],
+                activeTable: backend.data.active_table ?? null,
+                transformSteps: backend.data.transform_steps ?? {},
+                calculatedFields: backend.data.calculated_fields ?? {},
             },
             worksheets: backend.worksheets,
             activeWorksheetId: backend.active_worksheet_id,
@@ -163,6 +236,128 @@ export async function openProject(
     };
 }
 
+/** The outcome of `restoreProjectSources`. */
+export interface RestoredProject {
+    /** Info of the table that was active, or null when the project has no data. */
+    info: DatasetInfo | null;
+    /** What could not be restored and why, one line each. */
+    skipped: string[];
+}
+
+/**
+ * Replaces the workspace with the sources a project was saved with, under
+ * their saved names and read with their saved loader options. Projects from
+ * before multi-source workspaces only have `sourcePath`. Transformation steps
+ * are replayed and calculated fields added back to the source tables, then
+ * join and union results are computed again in the order they were made, each
+ * followed by its own steps and fields. What no longer applies is left out
+ * and listed in `skipped`.
+ */
+export async function restoreProjectSources(data: ProjectData): Promise<RestoredProject> {
+    const sources: Array<
+        Pick<ProjectSource, "path" | "schemaOverrides"> &
+            Partial<Pick<ProjectSource, "name" | "format" | "load">>
+    > =
+        data.sources.length > 0
+            ? data.sources
+            : data.sourcePath
+              ? [{ path: data.sourcePath, schemaOverrides: data.schemaOverrides }]
+              : [];
+    if (sources.length === 0) {
+        return { info: null, skipped: [] };
+    }
+
+    await clearData();
+
+    let firstTable: string | null = null;
+    for (const source of sources) {
+        const info = source.load
+            ? await restoreSource(source.path, source.load, source.schemaOverrides)
+            : source.format === "folder"
+              ? await loadFolder(source.path)
+              : await loadFile(source.path, source.schemaOverrides);
+        let tables = [info.activeTable];
+        if (source.name && source.name !== info.source) {
+            tables = (await renameSource(info.source, source.name)).tables;
+        }
+        firstTable = firstTable ?? tables[0];
+    }
+
+    // Derived tables were computed from their inputs' transformed views, so
+    // those are restored first, and each derived table before its own steps.
+    const skipped: string[] = [];
+    const derived = new Set(data.derivedTables.map((table) => table.name));
+    const tables = new Set([
+        ...Object.keys(data.transformSteps),
+        ...Object.keys(data.calculatedFields),
+    ]);
+    for (const table of tables) {
+        if (!derived.has(table)) {
+            await restorePipeline(data, table, skipped);
+        }
+    }
+
+    for (const table of data.derivedTables) {
+        try {
+            await deriveTable(table);
+        } catch (error) {
+            skipped.push(`Table '${table.name}': ${errorMessage(error)}`);
+            continue;
+        }
+        await restorePipeline(data, table.name, skipped);
+    }
+
+    const activeTable = data.activeTable ?? firstTable;
+    return {
+        info: activeTable ? await setActiveTable(activeTable) : null,
+        skipped,
+    };
+}
+
+/** A warning listing what a project restore left out, or null when nothing was. */
+export function describeSkippedRestore(restored: RestoredProject): string | null {
+    if (restored.skipped.length === 0) {
+        return null;
+    }
+    return `Parts of the project could not be restored:\n${restored.skipped.join("\n")}`;
+}
+
+/**
+ * Replays a table's saved steps, without undo history, and adds back its
+ * calculated fields. What no longer applies is added to `skipped`.
+ */
+async function restorePipeline(data: ProjectData, table: string, skipped: string[]): Promise<void> {
+    const steps = data.transformSteps[table];
+    if (steps) {
+        try {
+            await setTransformSteps(steps, table);
+        } catch (error) {
+            skipped.push(`Steps of '${table}': ${errorMessage(error)}`);
+        }
+    }
+
+    for (const field of data.calculatedFields[table] ?? []) {
+        try {
+            await addCalculatedField(field.name, field.formula, table);
+        } catch (error) {
+            skipped.push(`Calculated field '${field.name}' of '${table}': ${errorMessage(error)}`);
+        }
+    }
+}
+
+async function deriveTable({ name, derivation }: DerivedTable): Promise<void> {
+    if (derivation.type === "join") {
+        const { left, right, keys, kind } = derivation;
+        await joinTables(left, right, keys, kind, name);
+    } else {
+        await unionTables(derivation.tables, name, derivation.label_column ?? undefined);
+    }
+}
+
+function errorMessage(error: unknown): string {
+    return error instanceof Error ? error.message : String(error);
+}
+
 export async function newProject(): Promise<void> {
     return invoke("new_project");
 }
@@ -6,6 +6,8 @@ export interface AppSettings {
     auto_save: boolean;
     theme: string;
     max_preview_rows: number;
+    /** Query CSV, Parquet and Arrow files from disk instead of loading them into memory. */
+    scan_files: boolean;
 }
 
 export async function getSettings(): Promise<AppSettings> {
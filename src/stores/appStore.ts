@@ -14,6 +14,8 @@ interface AppState {
     activeView: "table" | "chart";
     isProcessing: boolean;
     processingMessage: string | null;
+    /** Path of the file being loaded, while its load can be cancelled. */
+    loadingPath: string | null;
 
     // Worksheet state
     worksheets: Worksheet[];
@@ -31,6 +33,10 @@ interface AppState {
     // Dataset state
     tables: string[];
     activeTable: string | null;
+    /** Bumped when loaded data changes in place, so charts query it again. */
+    dataVersion: number;
+    /** Sources reloaded when their file changes on disk. */
+    watchedSources: string[];
     
     error: string | null;
 }
@@ -49,6 +55,7 @@ interface AppActions {
     setAiPanelCollapsed: (collapsed: boolean) => void;
     setActiveView: (view: "table" | "chart") => void;
     setProcessing: (processing: boolean, message?: string) => void;
+    setLoadingPath: (path: string | null) => void;
     setDataset: (info: {
         fileName: string;
         filePath: string;
@@ -56,9 +63,21 @@ interface AppActions {
         rowCount: number;
         fileSize: number;
         tables: string[];
+        activeTable?: string;
     }) => void;
     setActiveTable: (table: string) => void;
+    /** Updates the dataset after a reload, keeping worksheets and charts. */
+    refreshDataset: (info: {
+        fileName: string;
+        filePath: string;
+        columns: Column[];
+        rowCount: number;
+        fileSize: number;
+        tables: string[];
+        activeTable: string;
+    }) => void;
     clearDataset: () => void;
+    setSourceWatched: (source: string, watched: boolean) => void;
     setVisualization: (spec: VisualizationSpec | null) => void;
     setProjectPath: (path: string | null) => void;
     setDirty: (dirty: boolean) => void;
@@ -86,6 +105,7 @@ const initialState: AppState = {
     activeView: "table",
     isProcessing: false,
     processingMessage: null,
+    loadingPath: null,
 
     worksheets: [],
     activeWorksheetId: null,
@@ -98,6 +118,8 @@ const initialState: AppState = {
     settingsOpen: false,
     tables: [],
     activeTable: null,
+    dataVersion: 0,
+    watchedSources: [],
     error: null,
 };
 
@@ -201,6 +223,8 @@ export const useAppStore = create<AppState & AppActions>((set, get) => ({
             processingMessage: processing ? (message ?? null) : null,
         }),
 
+    setLoadingPath: (path) => set({ loadingPath: path }),
+
     setDataset: (info) => {
         // Create initial default worksheet
         const initialSheetId = crypto.randomUUID();
@@ -218,7 +242,8 @@ export const useAppStore = create<AppState & AppActions>((set, get) => ({
             rowCount: info.rowCount,
             fileSize: info.fileSize,
             tables: info.tables || [],
-            activeTable: info.tables?.[0] || null,
+            activeTable: info.activeTable ?? info.tables?.[0] ?? null,
+            watchedSources: [],
             
             worksheets: [initialSheet],
             activeWorksheetId: initialSheetId,
@@ -235,6 +260,18 @@ export const useAppStore = create<AppState & AppActions>((set, get) => ({
 
     setActiveTable: (table) => set({ activeTable: table }),
 
+    refreshDataset: (info) =>
+        set((state) => ({
+            fileName: info.fileName,
+            filePath: info.filePath,
+            columns: info.columns,
+            rowCount: info.rowCount,
+            fileSize: info.fileSize,
+            tables: info.tables,
+            activeTable: info.activeTable,
+            dataVersion: state.dataVersion + 1,
+        })),
+
     clearDataset: () =>
         set({
             dataLoaded: false,
@@ -245,6 +282,7 @@ export const useAppStore = create<AppState & AppActions>((set, get) => ({
             fileSize: 0,
             tables: [],
             activeTable: null,
+            watchedSources: [],
             worksheets: [],
             activeWorksheetId: null,
             currentVisualization: null,
@@ -253,6 +291,13 @@ export const useAppStore = create<AppState & AppActions>((set, get) => ({
             error: null,
         }),
 
+    setSourceWatched: (source, watched) =>
+        set((state) => ({
+            watchedSources: watched
+                ? [...state.watchedSources.filter((s) => s !== source), source]
+                : state.watchedSources.filter((s) => s !== source),
+        })),
+
     setVisualization: (spec) =>
         set((state) => {
             // Update active worksheet
@@ -38,6 +38,25 @@ interface VizBuilderActions {
     loadFromSpec: (spec: VisualizationSpec) => void;
 }
 
+/**
+ * Aggregation to switch to for a measure whose values cannot be added up:
+ * identifiers and postal codes are counted, coordinates and percentages
+ * averaged. Returns null when summing is fine.
+ */
+function defaultAggregation(column: Column | undefined): AggregationType | null {
+    switch (column?.semanticType) {
+        case "identifier":
+        case "postal-code":
+            return "count";
+        case "latitude":
+        case "longitude":
+        case "percentage":
+            return "avg";
+        default:
+            return null;
+    }
+}
+
 const initialState: VizBuilderState = {
     isOpen: false,
     chartType: "bar",
@@ -76,6 +95,10 @@ export const useVizBuilderStore = create<VizBuilderState & VizBuilderActions>((s
             if (column?.dtype === "date") {
                 updates.yDateBinning = "year";
             }
+            const aggregation = defaultAggregation(column);
+            if (aggregation && get().aggregation === "sum") {
+                updates.aggregation = aggregation;
+            }
         }
         set(updates);
     },
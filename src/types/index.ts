@@ -1,9 +1,51 @@
 export * from "./chartConfigs";
 
+/** Column type as reported by the backend, before it is narrowed to `dtype`. */
+export type LogicalType =
+    | "integer"
+    | "float"
+    | "decimal"
+    | "boolean"
+    | "string"
+    | "categorical"
+    | "date"
+    | "datetime"
+    | "time"
+    | "duration"
+    | "list"
+    | "struct"
+    | "binary"
+    | "null"
+    | "unknown";
+
+/** What a column's values mean, detected by the backend from a sample. */
+export type SemanticType =
+    | "email"
+    | "url"
+    | "country-code"
+    | "us-state"
+    | "postal-code"
+    | "latitude"
+    | "longitude"
+    | "currency"
+    | "percentage"
+    | "identifier"
+    | "free-text";
+
 export interface Column {
     name: string;
     dtype: "string" | "integer" | "float" | "date" | "boolean";
+    /** Whether the column holds nulls. Always true for scanned tables. */
     nullable: boolean;
+    logicalType?: LogicalType;
+    precision?: number;
+    scale?: number;
+    /** `ns`, `us` or `ms`, for datetime and duration columns. */
+    timeUnit?: string;
+    timezone?: string;
+    semanticType?: SemanticType;
+    /** Formula of a calculated field, such as `revenue - cost`. */
+    formula?: string;
 }
 
 export type DateBinning = "year" | "quarter" | "month" | "day";